@@ -0,0 +1,82 @@
+mod common;
+
+use common::AsyncTcp;
+use embassy_executor::{Executor, Spawner};
+use embassy_time::Timer;
+use static_cell::StaticCell;
+
+static RESOURCES: StaticCell<embassy_ha::DeviceResources> = StaticCell::new();
+static IMAGE_BUFFER: StaticCell<heapless::Vec<u8, 512>> = StaticCell::new();
+
+const SIZE: u32 = 8;
+
+#[embassy_executor::task]
+async fn main_task(spawner: Spawner) {
+    let mut stream = AsyncTcp::connect(std::env!("MQTT_ADDRESS"));
+
+    let mut device = embassy_ha::new(
+        RESOURCES.init(Default::default()),
+        embassy_ha::DeviceConfig {
+            device_id: "example-device-id",
+            device_name: "Example Device Name",
+            manufacturer: "Example Device Manufacturer",
+            model: "Example Device Model",
+        },
+    );
+
+    let image = embassy_ha::create_image(
+        &device,
+        "image-id",
+        embassy_ha::ImageConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Image Name"),
+                ..Default::default()
+            },
+            content_type: Some(embassy_ha::constants::HA_IMAGE_CONTENT_TYPE_BMP),
+        },
+        IMAGE_BUFFER.init(Default::default()),
+    );
+
+    spawner.must_spawn(image_task(image));
+
+    embassy_ha::run(&mut device, &mut stream).await.unwrap();
+}
+
+/// An uncompressed 24 bit BMP checkerboard in the given color.
+fn checkerboard(color: [u8; 3]) -> Vec<u8> {
+    let data_size = SIZE * SIZE * 3;
+    let mut bmp = Vec::new();
+    bmp.extend_from_slice(b"BM");
+    bmp.extend_from_slice(&(54 + data_size).to_le_bytes());
+    bmp.extend_from_slice(&[0; 4]);
+    bmp.extend_from_slice(&54u32.to_le_bytes());
+    bmp.extend_from_slice(&40u32.to_le_bytes());
+    bmp.extend_from_slice(&SIZE.to_le_bytes());
+    bmp.extend_from_slice(&SIZE.to_le_bytes());
+    bmp.extend_from_slice(&1u16.to_le_bytes());
+    bmp.extend_from_slice(&24u16.to_le_bytes());
+    bmp.extend_from_slice(&[0; 4]);
+    bmp.extend_from_slice(&data_size.to_le_bytes());
+    bmp.extend_from_slice(&[0; 16]);
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let [r, g, b] = if (x + y) % 2 == 0 { color } else { [0; 3] };
+            bmp.extend_from_slice(&[b, g, r]);
+        }
+    }
+    bmp
+}
+
+#[embassy_executor::task]
+async fn image_task(mut image: embassy_ha::Image<'static>) {
+    let colors = [[255, 0, 0], [0, 255, 0], [0, 0, 255]];
+
+    let mut idx = 0;
+    loop {
+        image.publish(&checkerboard(colors[idx]));
+        idx = (idx + 1) % colors.len();
+        Timer::after_secs(5).await;
+    }
+}
+
+example_main!();
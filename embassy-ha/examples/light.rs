@@ -0,0 +1,57 @@
+mod common;
+
+use common::AsyncTcp;
+use embassy_executor::{Executor, Spawner};
+use embassy_time::Timer;
+use static_cell::StaticCell;
+
+static RESOURCES: StaticCell<embassy_ha::DeviceResources> = StaticCell::new();
+
+#[embassy_executor::task]
+async fn main_task(spawner: Spawner) {
+    let mut stream = AsyncTcp::connect(std::env!("MQTT_ADDRESS"));
+
+    let mut device = embassy_ha::new(
+        RESOURCES.init(Default::default()),
+        embassy_ha::DeviceConfig {
+            device_id: "example-device-id",
+            device_name: "Example Device Name",
+            manufacturer: "Example Device Manufacturer",
+            model: "Example Device Model",
+        },
+    );
+
+    let light = embassy_ha::create_light(
+        &device,
+        "light-id",
+        embassy_ha::LightConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Example Light"),
+                ..Default::default()
+            },
+            effects: &["solid", "blink"],
+            ..Default::default()
+        },
+    );
+
+    spawner.must_spawn(light_task(light));
+
+    embassy_ha::run(&mut device, &mut stream).await.unwrap();
+}
+
+#[embassy_executor::task]
+async fn light_task(mut light: embassy_ha::Light<'static>) {
+    loop {
+        let value = light.wait().await;
+        tracing::info!(
+            "state = {}, brightness = {}, color = {:?}, effect = {:?}",
+            value.state,
+            value.brightness,
+            value.color,
+            value.effect.map(|effect| light.effects()[effect])
+        );
+        Timer::after_secs(1).await;
+    }
+}
+
+example_main!();
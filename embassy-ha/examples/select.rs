@@ -0,0 +1,51 @@
+mod common;
+
+use common::AsyncTcp;
+use embassy_executor::{Executor, Spawner};
+use embassy_time::Timer;
+use static_cell::StaticCell;
+
+static RESOURCES: StaticCell<embassy_ha::DeviceResources> = StaticCell::new();
+
+#[embassy_executor::task]
+async fn main_task(spawner: Spawner) {
+    let mut stream = AsyncTcp::connect(std::env!("MQTT_ADDRESS"));
+
+    let mut device = embassy_ha::new(
+        RESOURCES.init(Default::default()),
+        embassy_ha::DeviceConfig {
+            device_id: "example-device-id",
+            device_name: "Example Device Name",
+            manufacturer: "Example Device Manufacturer",
+            model: "Example Device Model",
+        },
+    );
+
+    let select = embassy_ha::create_select(
+        &device,
+        "select-id",
+        embassy_ha::SelectConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Example Select"),
+                ..Default::default()
+            },
+            options: &["red", "green", "blue"],
+            ..Default::default()
+        },
+    );
+
+    spawner.must_spawn(select_task(select));
+
+    embassy_ha::run(&mut device, &mut stream).await.unwrap();
+}
+
+#[embassy_executor::task]
+async fn select_task(mut select: embassy_ha::Select<'static>) {
+    loop {
+        let index = select.wait().await;
+        tracing::info!("value = {}", select.options()[index]);
+        Timer::after_secs(1).await;
+    }
+}
+
+example_main!();
@@ -0,0 +1,39 @@
+mod common;
+
+use common::AsyncTcp;
+use embassy_executor::{Executor, Spawner};
+use static_cell::StaticCell;
+
+static RESOURCES: StaticCell<embassy_ha::DeviceResources> = StaticCell::new();
+
+#[embassy_executor::task]
+async fn main_task(spawner: Spawner) {
+    let mut stream = AsyncTcp::connect(std::env!("MQTT_ADDRESS"));
+
+    let mut device = embassy_ha::new(
+        RESOURCES.init(Default::default()),
+        embassy_ha::DeviceConfig {
+            device_id: "example-device-id",
+            device_name: "Example Device Name",
+            manufacturer: "Example Device Manufacturer",
+            model: "Example Device Model",
+        },
+    );
+
+    // published by the `mqtt_statestream` integration of Home Assistant
+    let subscription = embassy_ha::create_subscription(&device, "homeassistant/sensor/outside_temperature/state");
+
+    spawner.must_spawn(subscription_task(subscription));
+
+    embassy_ha::run(&mut device, &mut stream).await.unwrap();
+}
+
+#[embassy_executor::task]
+async fn subscription_task(mut subscription: embassy_ha::Subscription<'static>) {
+    loop {
+        let payload = subscription.wait().await;
+        tracing::info!("{} = {}", subscription.topic(), payload);
+    }
+}
+
+example_main!();
@@ -0,0 +1,52 @@
+mod common;
+
+use common::AsyncTcp;
+use embassy_executor::{Executor, Spawner};
+use embassy_time::Timer;
+use static_cell::StaticCell;
+
+static RESOURCES: StaticCell<embassy_ha::DeviceResources> = StaticCell::new();
+static TEXT_BUFFER: StaticCell<embassy_ha::TextBuffer<64>> = StaticCell::new();
+
+#[embassy_executor::task]
+async fn main_task(spawner: Spawner) {
+    let mut stream = AsyncTcp::connect(std::env!("MQTT_ADDRESS"));
+
+    let mut device = embassy_ha::new(
+        RESOURCES.init(Default::default()),
+        embassy_ha::DeviceConfig {
+            device_id: "example-device-id",
+            device_name: "Example Device Name",
+            manufacturer: "Example Device Manufacturer",
+            model: "Example Device Model",
+        },
+    );
+
+    let text = embassy_ha::create_text(
+        &device,
+        "text-id",
+        embassy_ha::TextConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Example Text"),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        TEXT_BUFFER.init(embassy_ha::TextBuffer::new()),
+    );
+
+    spawner.must_spawn(text_task(text));
+
+    embassy_ha::run(&mut device, &mut stream).await.unwrap();
+}
+
+#[embassy_executor::task]
+async fn text_task(mut text: embassy_ha::Text<'static>) {
+    loop {
+        let value = text.wait().await;
+        tracing::info!("value = {}", value);
+        Timer::after_secs(1).await;
+    }
+}
+
+example_main!();
@@ -7,12 +7,20 @@ pub const HA_DOMAIN_LIGHT: &str = "light";
 pub const HA_DOMAIN_BUTTON: &str = "button";
 pub const HA_DOMAIN_SELECT: &str = "select";
 pub const HA_DOMAIN_NUMBER: &str = "number";
+pub const HA_DOMAIN_TEXT: &str = "text";
 pub const HA_DOMAIN_DEVICE_TRACKER: &str = "device_tracker";
+pub const HA_DOMAIN_IMAGE: &str = "image";
 
 pub const HA_NUMBER_MODE_AUTO: &str = "auto";
 pub const HA_NUMBER_MODE_BOX: &str = "box";
 pub const HA_NUMBER_MODE_SLIDER: &str = "slider";
 
+pub const HA_TEXT_MODE_TEXT: &str = "text";
+pub const HA_TEXT_MODE_PASSWORD: &str = "password";
+
+pub const HA_IMAGE_CONTENT_TYPE_PNG: &str = "image/png";
+pub const HA_IMAGE_CONTENT_TYPE_BMP: &str = "image/bmp";
+
 pub const HA_STATE_CLASS_MEASUREMENT: &str = "measurement";
 pub const HA_STATE_CLASS_TOTAL: &str = "total";
 pub const HA_STATE_CLASS_TOTAL_INCREASING: &str = "total_increasing";
@@ -207,6 +215,10 @@ pub const HA_BINARY_SENSOR_STATE_OFF: &str = "OFF";
 
 pub const HA_BUTTON_PAYLOAD_PRESS: &str = "PRESS";
 
+pub const HA_LIGHT_SCHEMA_JSON: &str = "json";
+pub const HA_LIGHT_COLOR_MODE_RGB: &str = "rgb";
+pub const HA_LIGHT_COLOR_MODE_BRIGHTNESS: &str = "brightness";
+
 // Number units - Energy
 pub const HA_UNIT_ENERGY_JOULE: &str = "J";
 pub const HA_UNIT_ENERGY_KILOJOULE: &str = "kJ";
@@ -34,5 +34,11 @@ pub(crate) struct EntityConfig {
     pub max: Option<f32>,
     pub step: Option<f32>,
     pub mode: Option<&'static str>,
+    pub pattern: Option<&'static str>,
+    pub options: Option<&'static [&'static str]>,
     pub suggested_display_precision: Option<u8>,
+    pub supported_color_modes: Option<&'static [&'static str]>,
+    pub brightness: Option<bool>,
+    pub effect_list: Option<&'static [&'static str]>,
+    pub content_type: Option<&'static str>,
 }
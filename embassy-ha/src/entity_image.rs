@@ -0,0 +1,61 @@
+use heapless::VecView;
+
+use crate::{Entity, EntityCommonConfig, EntityConfig, constants};
+
+/// Configuration for an image entity.
+///
+/// The image is published as raw bytes of the given content type, e.g. `image/png`.
+#[derive(Debug, Default)]
+pub struct ImageConfig {
+    pub common: EntityCommonConfig,
+    pub content_type: Option<&'static str>,
+}
+
+impl ImageConfig {
+    pub(crate) fn populate(&self, config: &mut EntityConfig) {
+        self.common.populate(config);
+        config.domain = constants::HA_DOMAIN_IMAGE;
+        config.content_type = Some(self.content_type.unwrap_or(constants::HA_IMAGE_CONTENT_TYPE_PNG));
+    }
+}
+
+/// An entity that publishes binary images.
+///
+/// Images can be far larger than the device's shared publish buffer, so every image entity owns the buffer its
+/// image is kept in, see [`create_image`](crate::create_image).
+pub struct Image<'a>(Entity<'a>);
+
+impl<'a> Image<'a> {
+    pub(crate) fn new(entity: Entity<'a>) -> Self {
+        Self(entity)
+    }
+
+    /// Publishes a new image.
+    ///
+    /// The image is dropped when it does not fit the entity's buffer or when the previous image is still being
+    /// sent, the buffer is lent to the MQTT client meanwhile.
+    pub fn publish(&mut self, image: &[u8]) {
+        let stored = self.0.with_data(|data| {
+            let storage = data.storage.as_image_mut();
+            let Some(buffer) = storage.buffer.as_mut() else {
+                crate::log::warn!("image '{}' is still being sent, dropping the new one", data.config.id);
+                return false;
+            };
+            buffer.clear();
+            if buffer.extend_from_slice(image).is_err() {
+                crate::log::warn!(
+                    "image '{}' of {} bytes does not fit its {} byte buffer",
+                    data.config.id,
+                    image.len(),
+                    buffer.capacity()
+                );
+                buffer.clear();
+                return false;
+            }
+            true
+        });
+        if stored {
+            self.0.queue_publish();
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use crate::{
+    BinaryState, CommandPolicy, Entity, EntityCommonConfig, EntityConfig, LightCommand, LightState, constants,
+};
+
+/// State of an RGB light.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LightValue {
+    pub state: BinaryState,
+    pub brightness: u8,
+    pub color: [u8; 3],
+    /// Index into the configured effects, `None` when no effect is active.
+    pub effect: Option<usize>,
+}
+
+impl Default for LightValue {
+    fn default() -> Self {
+        Self { state: BinaryState::Off, brightness: 255, color: [255, 255, 255], effect: None }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LightColorMode {
+    #[default]
+    Rgb,
+    /// Dimmable only, the color of [`LightValue`] is ignored.
+    Brightness,
+}
+
+/// Configuration for a light entity using the MQTT JSON schema.
+///
+/// Commands only carry the attributes that changed, they are merged into the last known state before being handed
+/// out. The effect is represented as an index into `effects`.
+///
+/// See [`CommandPolicy`] for details on how commands are handled.
+#[derive(Debug, Default)]
+pub struct LightConfig {
+    pub common: EntityCommonConfig,
+    pub color_mode: LightColorMode,
+    pub effects: &'static [&'static str],
+    pub command_policy: CommandPolicy,
+}
+
+impl LightConfig {
+    pub(crate) fn populate(&self, config: &mut EntityConfig) {
+        self.common.populate(config);
+        config.domain = constants::HA_DOMAIN_LIGHT;
+        config.schema = Some(constants::HA_LIGHT_SCHEMA_JSON);
+        config.supported_color_modes = Some(match self.color_mode {
+            LightColorMode::Rgb => &[constants::HA_LIGHT_COLOR_MODE_RGB],
+            LightColorMode::Brightness => &[constants::HA_LIGHT_COLOR_MODE_BRIGHTNESS],
+        });
+        config.brightness = Some(true);
+        if !self.effects.is_empty() {
+            config.effect_list = Some(self.effects);
+        }
+    }
+}
+
+pub struct Light<'a>(Entity<'a>);
+
+impl<'a> Light<'a> {
+    pub(crate) fn new(entity: Entity<'a>) -> Self {
+        Self(entity)
+    }
+
+    pub fn effects(&self) -> &'static [&'static str] {
+        self.0.with_data(|data| data.config.effect_list.unwrap_or_default())
+    }
+
+    pub fn state(&self) -> Option<LightValue> {
+        self.0.with_data(|data| {
+            let storage = data.storage.as_light_mut();
+            storage.state.as_ref().map(|s| s.value)
+        })
+    }
+
+    pub fn command(&self) -> Option<LightValue> {
+        self.0.with_data(|data| {
+            let storage = data.storage.as_light_mut();
+            storage.command.as_ref().map(|s| s.value)
+        })
+    }
+
+    pub fn set(&mut self, value: LightValue) {
+        if value.effect.is_some_and(|effect| effect >= self.effects().len()) {
+            crate::log::warn!("light effect index {} is out of range, ignoring it", value.effect.unwrap());
+            return;
+        }
+
+        let publish = self.0.with_data(|data| {
+            let storage = data.storage.as_light_mut();
+            let timestamp = embassy_time::Instant::now();
+            let publish = match &storage.state {
+                Some(state) => state.value != value,
+                None => true,
+            };
+            storage.state = Some(LightState { value, timestamp });
+            storage.command = Some(LightCommand { value, timestamp });
+            publish
+        });
+        if publish {
+            self.0.queue_publish();
+        }
+    }
+
+    pub async fn wait(&mut self) -> LightValue {
+        loop {
+            self.0.wait_command().await;
+            if let Some(value) = self.command() {
+                return value;
+            }
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use crate::{CommandPolicy, Entity, EntityCommonConfig, EntityConfig, SelectCommand, SelectState, constants};
+
+/// Configuration for a select entity.
+///
+/// The entity state and commands are represented as indices into `options`.
+///
+/// See [`CommandPolicy`] for details on how commands are handled.
+#[derive(Debug, Default)]
+pub struct SelectConfig {
+    pub common: EntityCommonConfig,
+    pub options: &'static [&'static str],
+    pub command_policy: CommandPolicy,
+}
+
+impl SelectConfig {
+    pub(crate) fn populate(&self, config: &mut EntityConfig) {
+        self.common.populate(config);
+        config.domain = constants::HA_DOMAIN_SELECT;
+        config.options = Some(self.options);
+    }
+}
+
+pub struct Select<'a>(Entity<'a>);
+
+impl<'a> Select<'a> {
+    pub(crate) fn new(entity: Entity<'a>) -> Self {
+        Self(entity)
+    }
+
+    pub fn options(&self) -> &'static [&'static str] {
+        self.0.with_data(|data| data.config.options.unwrap_or_default())
+    }
+
+    pub fn state(&self) -> Option<usize> {
+        self.0.with_data(|data| {
+            let storage = data.storage.as_select_mut();
+            storage.state.as_ref().map(|s| s.value)
+        })
+    }
+
+    pub fn command(&self) -> Option<usize> {
+        self.0.with_data(|data| {
+            let storage = data.storage.as_select_mut();
+            storage.command.as_ref().map(|s| s.value)
+        })
+    }
+
+    pub async fn wait(&mut self) -> usize {
+        loop {
+            self.0.wait_command().await;
+            if let Some(value) = self.command() {
+                return value;
+            }
+        }
+    }
+
+    /// Sets the selected option by index, out of range indices are ignored.
+    pub fn set(&mut self, index: usize) {
+        if index >= self.options().len() {
+            crate::log::warn!("select option index {} is out of range, ignoring it", index);
+            return;
+        }
+
+        let publish = self.0.with_data(|data| {
+            let storage = data.storage.as_select_mut();
+            let timestamp = embassy_time::Instant::now();
+            let publish = match &storage.state {
+                Some(state) => state.value != index,
+                None => true,
+            };
+            storage.state = Some(SelectState { value: index, timestamp });
+            storage.command = Some(SelectCommand { value: index, timestamp });
+            publish
+        });
+        if publish {
+            self.0.queue_publish();
+        }
+    }
+}
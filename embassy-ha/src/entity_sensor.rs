@@ -193,4 +193,36 @@ impl<'a> Sensor<'a> {
             self.0.queue_publish();
         }
     }
+    /// Publishes a JSON object as the attributes of the sensor, shown next to its state in Home Assistant.
+    ///
+    /// Attributes are sent along with the state, so they only show up once a value was published. Only sensors
+    /// created with [`create_sensor_with_attributes`](crate::create_sensor_with_attributes) have room for them.
+    pub fn publish_attributes(&mut self, attributes: &str) {
+        let publish = self.0.with_data(|data| {
+            let id = data.config.id;
+            let storage = data.storage.as_numeric_sensor_mut();
+            let Some(buffer) = storage.attributes.as_mut() else {
+                crate::log::warn!("sensor '{}' has no attributes buffer, ignoring its attributes", id);
+                return false;
+            };
+            if attributes.len() > buffer.capacity() {
+                crate::log::warn!(
+                    "sensor '{}' attributes are longer than {} bytes, ignoring them",
+                    id,
+                    buffer.capacity()
+                );
+                return false;
+            }
+            if buffer.as_str() == attributes {
+                return false;
+            }
+            buffer.clear();
+            buffer.push_str(attributes).expect("attributes were checked against the buffer capacity");
+            storage.attributes_publish = true;
+            true
+        });
+        if publish {
+            self.0.queue_publish();
+        }
+    }
 }
@@ -0,0 +1,112 @@
+use heapless::String;
+
+use crate::{CommandPolicy, Entity, EntityCommonConfig, EntityConfig, constants};
+
+/// Maximum length of a text entity value, matches the Home Assistant default.
+pub const TEXT_MAX_LENGTH: usize = 255;
+
+/// Keeps the state and the last command of a text entity, both hold up to `N` bytes.
+///
+/// Texts own their storage so the device resources do not have to reserve [`TEXT_MAX_LENGTH`] bytes for every
+/// entity, see [`create_text`](crate::create_text).
+#[derive(Debug, Default)]
+pub struct TextBuffer<const N: usize> {
+    pub(crate) state: String<N>,
+    pub(crate) command: String<N>,
+}
+
+impl<const N: usize> TextBuffer<N> {
+    pub const fn new() -> Self {
+        Self { state: String::new(), command: String::new() }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TextMode {
+    #[default]
+    Text,
+    Password,
+}
+
+/// Configuration for a text entity.
+///
+/// See [`CommandPolicy`] for details on how commands are handled.
+#[derive(Debug, Default)]
+pub struct TextConfig {
+    pub common: EntityCommonConfig,
+    pub min: Option<u8>,
+    pub max: Option<u8>,
+    pub mode: TextMode,
+    pub pattern: Option<&'static str>,
+    pub command_policy: CommandPolicy,
+}
+
+impl TextConfig {
+    pub(crate) fn populate(&self, config: &mut EntityConfig) {
+        self.common.populate(config);
+        config.domain = constants::HA_DOMAIN_TEXT;
+        config.mode = Some(match self.mode {
+            TextMode::Text => constants::HA_TEXT_MODE_TEXT,
+            TextMode::Password => constants::HA_TEXT_MODE_PASSWORD,
+        });
+        config.min = self.min.map(f32::from);
+        config.max = Some(f32::from(self.max.unwrap_or(TEXT_MAX_LENGTH as u8)));
+        config.pattern = self.pattern;
+    }
+}
+
+pub struct Text<'a>(Entity<'a>);
+
+impl<'a> Text<'a> {
+    pub(crate) fn new(entity: Entity<'a>) -> Self {
+        Self(entity)
+    }
+
+    pub fn state(&self) -> Option<String<TEXT_MAX_LENGTH>> {
+        self.0.with_data(|data| {
+            let storage = data.storage.as_text_mut();
+            storage
+                .state_timestamp
+                .map(|_| String::try_from(storage.state.as_str()).expect("text buffers never exceed the text capacity"))
+        })
+    }
+
+    pub fn command(&self) -> Option<String<TEXT_MAX_LENGTH>> {
+        self.0.with_data(|data| {
+            let storage = data.storage.as_text_mut();
+            storage.command_timestamp.map(|_| {
+                String::try_from(storage.command.as_str()).expect("text buffers never exceed the text capacity")
+            })
+        })
+    }
+
+    pub async fn wait(&mut self) -> String<TEXT_MAX_LENGTH> {
+        loop {
+            self.0.wait_command().await;
+            if let Some(value) = self.command() {
+                return value;
+            }
+        }
+    }
+
+    /// Sets the text state, values longer than the entity's [`TextBuffer`] are truncated.
+    pub fn set(&mut self, value: &str) {
+        let publish = self.0.with_data(|data| {
+            let storage = data.storage.as_text_mut();
+            let mut end = value.len().min(storage.state.capacity());
+            while !value.is_char_boundary(end) {
+                end -= 1;
+            }
+            let value = &value[..end];
+
+            let timestamp = embassy_time::Instant::now();
+            let publish = storage.state_timestamp.is_none() || storage.state.as_str() != value;
+            storage.set_state(value, timestamp);
+            storage.set_command(value, timestamp);
+            publish
+        });
+        if publish {
+            self.0.queue_publish();
+        }
+    }
+}
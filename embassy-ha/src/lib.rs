@@ -3,10 +3,11 @@
 //!
 //! # Features
 //!
-//! - Support for multiple entity types: sensors, buttons, switches, binary sensors, numbers, device trackers
+//! - Support for multiple entity types: sensors, buttons, switches, binary sensors, numbers, texts, selects, lights, device trackers, images
 //! - Built on top of Embassy's async runtime for embedded systems
 //! - No-std compatible
 //! - Automatic MQTT discovery for Home Assistant
+//! - Subscriptions to arbitrary MQTT topics, e.g. states of other Home Assistant entities
 //! - No runtime allocation
 //!
 //! # Installation
@@ -24,9 +25,10 @@
 //! use embassy_executor::Spawner;
 //! use embassy_ha::{DeviceConfig, SensorConfig, SensorClass, StateClass};
 //! use embassy_time::Timer;
-//! use static_cell::StaticCell;
+//! use static_cell::ConstStaticCell;
 //!
-//! static HA_RESOURCES: StaticCell<embassy_ha::DeviceResources> = StaticCell::new();
+//! static HA_RESOURCES: ConstStaticCell<embassy_ha::DeviceResources> =
+//!     ConstStaticCell::new(embassy_ha::DeviceResources::new());
 //!
 //! #[embassy_executor::main]
 //! async fn main(spawner: Spawner) {
@@ -37,7 +39,7 @@
 //!
 //!     // Create a Home Assistant device
 //!     let device = embassy_ha::new(
-//!         HA_RESOURCES.init(Default::default()),
+//!         HA_RESOURCES.take(),
 //!         DeviceConfig {
 //!             device_id: "my-device",
 //!             device_name: "My Device",
@@ -88,7 +90,12 @@
 //! - `switch` - On/off switch control
 //! - `binary_sensor` - Binary state sensor
 //! - `number` - Numeric input entity
+//! - `text` - Text input entity
+//! - `select` - Option selection entity
+//! - `light` - RGB light with brightness and effects
 //! - `device_tracker` - Location tracking entity
+//! - `image` - Binary image entity
+//! - `subscription` - Messages on an arbitrary topic
 
 #![no_std]
 
@@ -105,7 +112,7 @@ use heapless::{
     Vec, VecView,
     string::{String, StringView},
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 mod mqtt;
 
@@ -136,15 +143,30 @@ pub use entity_category::*;
 mod entity_device_tracker;
 pub use entity_device_tracker::*;
 
+mod entity_image;
+pub use entity_image::*;
+
+mod entity_light;
+pub use entity_light::*;
+
 mod entity_number;
 pub use entity_number::*;
 
+mod entity_select;
+pub use entity_select::*;
+
 mod entity_sensor;
 pub use entity_sensor::*;
 
 mod entity_switch;
 pub use entity_switch::*;
 
+mod entity_text;
+pub use entity_text::*;
+
+mod subscription;
+pub use subscription::*;
+
 mod transport;
 pub use transport::Transport;
 
@@ -202,6 +224,12 @@ struct EntityDiscovery<'a> {
     #[serde(skip_serializing_if = "Option::is_none")]
     json_attributes_topic: Option<&'a str>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    image_topic: Option<&'a str>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content_type: Option<&'a str>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     unit_of_measurement: Option<&'a str>,
 
@@ -235,9 +263,27 @@ struct EntityDiscovery<'a> {
     #[serde(skip_serializing_if = "Option::is_none")]
     mode: Option<&'a str>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pattern: Option<&'a str>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    options: Option<&'a [&'a str]>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     suggested_display_precision: Option<u8>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    supported_color_modes: Option<&'a [&'a str]>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    brightness: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    effect: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    effect_list: Option<&'a [&'a str]>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     availability_topic: Option<&'a str>,
 
@@ -312,9 +358,11 @@ pub struct DeviceConfig {
     pub model: &'static str,
 }
 
+/// Everything a device needs to run, it is large so it should be placed in a static with [`DeviceResources::new`].
 pub struct DeviceResources {
     waker: AtomicWaker,
     entities: [RefCell<Option<EntityData>>; Self::ENTITY_LIMIT],
+    subscriptions: [RefCell<Option<SubscriptionData>>; Self::SUBSCRIPTION_LIMIT],
 
     mqtt_resources: mqtt::ClientResources,
     publish_buffer: Vec<u8, 2048>,
@@ -328,28 +376,38 @@ pub struct DeviceResources {
 }
 
 impl DeviceResources {
-    const ENTITY_LIMIT: usize = 16;
-}
-
-impl Default for DeviceResources {
-    fn default() -> Self {
+    /// Every slot takes about 250 bytes on 32-bit targets, values of arbitrary length (texts, sensor attributes,
+    /// images) are kept in buffers owned by their entity instead.
+    const ENTITY_LIMIT: usize = 64;
+    const SUBSCRIPTION_LIMIT: usize = 16;
+
+    /// Usable in a `const` context, e.g. with `static_cell::ConstStaticCell`, so the resources are never built on
+    /// the stack.
+    pub const fn new() -> Self {
         Self {
             waker: AtomicWaker::new(),
             entities: [const { RefCell::new(None) }; Self::ENTITY_LIMIT],
-
-            mqtt_resources: Default::default(),
-            publish_buffer: Default::default(),
-            subscribe_buffer: Default::default(),
-            discovery_buffer: Default::default(),
-            availability_topic_buffer: Default::default(),
-            discovery_topic_buffer: Default::default(),
-            state_topic_buffer: Default::default(),
-            command_topic_buffer: Default::default(),
-            attributes_topic_buffer: Default::default(),
+            subscriptions: [const { RefCell::new(None) }; Self::SUBSCRIPTION_LIMIT],
+
+            mqtt_resources: mqtt::ClientResources::new(),
+            publish_buffer: Vec::new(),
+            subscribe_buffer: Vec::new(),
+            discovery_buffer: Vec::new(),
+            availability_topic_buffer: String::new(),
+            discovery_topic_buffer: String::new(),
+            state_topic_buffer: String::new(),
+            command_topic_buffer: String::new(),
+            attributes_topic_buffer: String::new(),
         }
     }
 }
 
+impl Default for DeviceResources {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[derive(Debug, Default)]
 pub(crate) struct ButtonStorage {
     pub timestamp: Option<embassy_time::Instant>,
@@ -399,6 +457,9 @@ pub(crate) struct NumericSensorState {
 #[derive(Debug, Default)]
 pub(crate) struct NumericSensorStorage {
     pub state: Option<NumericSensorState>,
+    /// Only sensors created with [`create_sensor_with_attributes`] have one, empty until attributes are published.
+    pub attributes: Option<&'static mut StringView>,
+    pub attributes_publish: bool,
 }
 
 #[derive(Debug)]
@@ -422,6 +483,100 @@ pub(crate) struct NumberStorage {
     pub command_policy: CommandPolicy,
 }
 
+/// Values live in the entity's [`TextBuffer`], they are only valid once their timestamp is set.
+#[derive(Debug)]
+pub(crate) struct TextStorage {
+    pub state: &'static mut StringView,
+    pub state_timestamp: Option<embassy_time::Instant>,
+    pub command: &'static mut StringView,
+    pub command_timestamp: Option<embassy_time::Instant>,
+    pub command_policy: CommandPolicy,
+}
+
+impl TextStorage {
+    /// `value` must fit the buffer, longer values are truncated or rejected before.
+    pub fn set_state(&mut self, value: &str, timestamp: embassy_time::Instant) {
+        self.state.clear();
+        self.state.push_str(value).expect("text state does not fit its buffer");
+        self.state_timestamp = Some(timestamp);
+    }
+
+    pub fn set_command(&mut self, value: &str, timestamp: embassy_time::Instant) {
+        self.command.clear();
+        self.command.push_str(value).expect("text command does not fit its buffer");
+        self.command_timestamp = Some(timestamp);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct SelectState {
+    pub value: usize,
+    #[allow(unused)]
+    pub timestamp: embassy_time::Instant,
+}
+
+#[derive(Debug)]
+pub(crate) struct SelectCommand {
+    pub value: usize,
+    #[allow(unused)]
+    pub timestamp: embassy_time::Instant,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct SelectStorage {
+    pub state: Option<SelectState>,
+    pub command: Option<SelectCommand>,
+    pub command_policy: CommandPolicy,
+}
+
+#[derive(Debug)]
+pub(crate) struct LightState {
+    pub value: LightValue,
+    #[allow(unused)]
+    pub timestamp: embassy_time::Instant,
+}
+
+#[derive(Debug)]
+pub(crate) struct LightCommand {
+    pub value: LightValue,
+    #[allow(unused)]
+    pub timestamp: embassy_time::Instant,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct LightStorage {
+    pub state: Option<LightState>,
+    pub command: Option<LightCommand>,
+    pub command_policy: CommandPolicy,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct LightColorPayload {
+    r: u8,
+    g: u8,
+    b: u8,
+}
+
+#[derive(Debug, Serialize)]
+struct LightStatePayload<'a> {
+    state: &'a str,
+    brightness: u8,
+    color_mode: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    color: Option<LightColorPayload>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    effect: Option<&'a str>,
+}
+
+/// JSON schema light command, every attribute is optional and only present when it changed.
+#[derive(Debug, Deserialize)]
+struct LightCommandPayload<'a> {
+    state: Option<&'a str>,
+    brightness: Option<u8>,
+    color: Option<LightColorPayload>,
+    effect: Option<&'a str>,
+}
+
 #[derive(Debug, Serialize)]
 pub(crate) struct DeviceTrackerState {
     pub latitude: f32,
@@ -435,6 +590,12 @@ pub(crate) struct DeviceTrackerStorage {
     pub state: Option<DeviceTrackerState>,
 }
 
+#[derive(Debug, Default)]
+pub(crate) struct ImageStorage {
+    /// The last image, `None` while the run loop is sending it.
+    pub buffer: Option<&'static mut VecView<u8>>,
+}
+
 #[derive(Debug)]
 pub(crate) enum EntityStorage {
     Button(ButtonStorage),
@@ -442,7 +603,11 @@ pub(crate) enum EntityStorage {
     BinarySensor(BinarySensorStorage),
     NumericSensor(NumericSensorStorage),
     Number(NumberStorage),
+    Text(TextStorage),
+    Select(SelectStorage),
+    Light(LightStorage),
     DeviceTracker(DeviceTrackerStorage),
+    Image(ImageStorage),
 }
 
 impl EntityStorage {
@@ -481,12 +646,40 @@ impl EntityStorage {
         }
     }
 
+    pub fn as_text_mut(&mut self) -> &mut TextStorage {
+        match self {
+            EntityStorage::Text(storage) => storage,
+            _ => panic!("expected storage type to be text"),
+        }
+    }
+
+    pub fn as_select_mut(&mut self) -> &mut SelectStorage {
+        match self {
+            EntityStorage::Select(storage) => storage,
+            _ => panic!("expected storage type to be select"),
+        }
+    }
+
+    pub fn as_light_mut(&mut self) -> &mut LightStorage {
+        match self {
+            EntityStorage::Light(storage) => storage,
+            _ => panic!("expected storage type to be light"),
+        }
+    }
+
     pub fn as_device_tracker_mut(&mut self) -> &mut DeviceTrackerStorage {
         match self {
             EntityStorage::DeviceTracker(storage) => storage,
             _ => panic!("expected storage type to be device tracker"),
         }
     }
+
+    pub fn as_image_mut(&mut self) -> &mut ImageStorage {
+        match self {
+            EntityStorage::Image(storage) => storage,
+            _ => panic!("expected storage type to be image"),
+        }
+    }
 }
 
 struct EntityData {
@@ -551,6 +744,7 @@ pub struct Device<'a> {
     // resources
     waker: &'a AtomicWaker,
     entities: &'a [RefCell<Option<EntityData>>],
+    subscriptions: &'a [RefCell<Option<SubscriptionData>>],
 
     mqtt_resources: &'a mut mqtt::ClientResources,
     publish_buffer: &'a mut VecView<u8>,
@@ -568,6 +762,7 @@ pub fn new<'a>(resources: &'a mut DeviceResources, config: DeviceConfig) -> Devi
         config,
         waker: &resources.waker,
         entities: &resources.entities,
+        subscriptions: &resources.subscriptions,
 
         mqtt_resources: &mut resources.mqtt_resources,
         publish_buffer: &mut resources.publish_buffer,
@@ -605,6 +800,26 @@ pub fn create_sensor<'a>(device: &Device<'a>, id: &'static str, config: SensorCo
     Sensor::new(entity)
 }
 
+/// Creates a sensor that can also publish attributes, see [`Sensor::publish_attributes`]. They are kept in `buffer`,
+/// which has to fit the largest attributes published.
+pub fn create_sensor_with_attributes<'a>(
+    device: &Device<'a>,
+    id: &'static str,
+    config: SensorConfig,
+    buffer: &'static mut StringView,
+) -> Sensor<'a> {
+    let mut entity_config = EntityConfig { id, ..Default::default() };
+    config.populate(&mut entity_config);
+
+    buffer.clear();
+    let entity = create_entity(
+        device,
+        entity_config,
+        EntityStorage::NumericSensor(NumericSensorStorage { attributes: Some(buffer), ..Default::default() }),
+    );
+    Sensor::new(entity)
+}
+
 pub fn create_button<'a>(device: &Device<'a>, id: &'static str, config: ButtonConfig) -> Button<'a> {
     let mut entity_config = EntityConfig { id, ..Default::default() };
     config.populate(&mut entity_config);
@@ -637,6 +852,59 @@ pub fn create_switch<'a>(device: &Device<'a>, id: &'static str, config: SwitchCo
     Switch::new(entity)
 }
 
+/// Creates a text entity that keeps its values in `buffer`, Home Assistant is told to stay within its capacity.
+pub fn create_text<'a, const N: usize>(
+    device: &Device<'a>,
+    id: &'static str,
+    config: TextConfig,
+    buffer: &'static mut TextBuffer<N>,
+) -> Text<'a> {
+    const { assert!(N <= TEXT_MAX_LENGTH, "text buffers can not exceed TEXT_MAX_LENGTH") };
+
+    let mut entity_config = EntityConfig { id, ..Default::default() };
+    config.populate(&mut entity_config);
+    entity_config.max = entity_config.max.map(|max| max.min(N as f32));
+
+    buffer.state.clear();
+    buffer.command.clear();
+    let entity = create_entity(
+        device,
+        entity_config,
+        EntityStorage::Text(TextStorage {
+            state: buffer.state.as_mut_view(),
+            state_timestamp: None,
+            command: buffer.command.as_mut_view(),
+            command_timestamp: None,
+            command_policy: config.command_policy,
+        }),
+    );
+    Text::new(entity)
+}
+
+pub fn create_select<'a>(device: &Device<'a>, id: &'static str, config: SelectConfig) -> Select<'a> {
+    let mut entity_config = EntityConfig { id, ..Default::default() };
+    config.populate(&mut entity_config);
+
+    let entity = create_entity(
+        device,
+        entity_config,
+        EntityStorage::Select(SelectStorage { command_policy: config.command_policy, ..Default::default() }),
+    );
+    Select::new(entity)
+}
+
+pub fn create_light<'a>(device: &Device<'a>, id: &'static str, config: LightConfig) -> Light<'a> {
+    let mut entity_config = EntityConfig { id, ..Default::default() };
+    config.populate(&mut entity_config);
+
+    let entity = create_entity(
+        device,
+        entity_config,
+        EntityStorage::Light(LightStorage { command_policy: config.command_policy, ..Default::default() }),
+    );
+    Light::new(entity)
+}
+
 pub fn create_binary_sensor<'a>(device: &Device<'a>, id: &'static str, config: BinarySensorConfig) -> BinarySensor<'a> {
     let mut entity_config = EntityConfig { id, ..Default::default() };
     config.populate(&mut entity_config);
@@ -657,6 +925,32 @@ pub fn create_device_tracker<'a>(
     DeviceTracker::new(entity)
 }
 
+/// Creates an image entity that keeps its image in `buffer`, which has to fit the largest image published.
+pub fn create_image<'a>(
+    device: &Device<'a>,
+    id: &'static str,
+    config: ImageConfig,
+    buffer: &'static mut VecView<u8>,
+) -> Image<'a> {
+    let mut entity_config = EntityConfig { id, ..Default::default() };
+    config.populate(&mut entity_config);
+
+    buffer.clear();
+    let entity = create_entity(device, entity_config, EntityStorage::Image(ImageStorage { buffer: Some(buffer) }));
+    Image::new(entity)
+}
+
+/// Subscribes the device to messages others publish on `topic`, see [`Subscription`].
+pub fn create_subscription<'a>(device: &Device<'a>, topic: &'static str) -> Subscription<'a> {
+    let data = device
+        .subscriptions
+        .iter()
+        .find(|subscription| subscription.borrow().is_none())
+        .expect("device subscription limit reached");
+    data.replace(Some(SubscriptionData { topic, payload: None, waker: None }));
+    Subscription { data }
+}
+
 /// Runs the main Home Assistant device event loop.
 ///
 /// This function handles MQTT communication, entity discovery, and state updates. It will run
@@ -666,7 +960,7 @@ pub fn create_device_tracker<'a>(
 ///
 /// - Connects to the MQTT broker using the provided transport
 /// - Publishes discovery messages for all entities
-/// - Subscribes to command topics for controllable entities
+/// - Subscribes to command topics for controllable entities and to the topics of all [`Subscription`]s
 /// - Enters the main event loop to handle state updates and commands
 /// - Returns on the first error (connection loss, timeout, protocol error, etc.)
 ///
@@ -794,6 +1088,9 @@ pub async fn run<T: Transport>(
                 state_topic: Some(device.state_topic_buffer.as_str()),
                 command_topic: Some(device.command_topic_buffer.as_str()),
                 json_attributes_topic: Some(device.attributes_topic_buffer.as_str()),
+                image_topic: (entity_config.domain == constants::HA_DOMAIN_IMAGE)
+                    .then_some(device.state_topic_buffer.as_str()),
+                content_type: entity_config.content_type,
                 unit_of_measurement: entity_config.measurement_unit,
                 schema: entity_config.schema,
                 platform: entity_config.platform,
@@ -805,7 +1102,13 @@ pub async fn run<T: Transport>(
                 max: entity_config.max,
                 step: entity_config.step,
                 mode: entity_config.mode,
+                pattern: entity_config.pattern,
+                options: entity_config.options,
                 suggested_display_precision: entity_config.suggested_display_precision,
+                supported_color_modes: entity_config.supported_color_modes,
+                brightness: entity_config.brightness,
+                effect: entity_config.effect_list.map(|_| true),
+                effect_list: entity_config.effect_list,
                 availability_topic: Some(availability_topic),
                 payload_available: Some(AVAILABLE_PAYLOAD),
                 payload_not_available: Some(NOT_AVAILABLE_PAYLOAD),
@@ -851,6 +1154,24 @@ pub async fn run<T: Transport>(
         }
     }
 
+    for subscription in device.subscriptions {
+        let Some(topic) = subscription.borrow().as_ref().map(|data| data.topic) else {
+            break;
+        };
+        crate::log::debug!("subscribing to topic '{}'", topic);
+        match embassy_time::with_timeout(MQTT_TIMEOUT, client.subscribe(topic)).await {
+            Ok(Ok(_)) => {}
+            Ok(Err(err)) => {
+                crate::log::error!("mqtt subscribe to '{}' failed with: {:?}", topic, crate::log::Debug2Format(&err));
+                return Err(Error::new("mqtt subscription to topic failed"));
+            }
+            Err(_) => {
+                crate::log::error!("mqtt subscribe to '{}' timed out", topic);
+                return Err(Error::new("mqtt subscribe timed out"));
+            }
+        }
+    }
+
     match embassy_time::with_timeout(
         MQTT_TIMEOUT,
         client.publish_with(
@@ -877,7 +1198,7 @@ pub async fn run<T: Transport>(
         use core::fmt::Write;
 
         for entity in device.entities {
-            let publish_topic = {
+            let (publish_topic, image) = {
                 let mut entity = entity.borrow_mut();
                 let entity = match entity.as_mut() {
                     Some(entity) => entity,
@@ -891,6 +1212,12 @@ pub async fn run<T: Transport>(
                 entity.publish = false;
                 device.publish_buffer.clear();
 
+                // images are sent straight from their own buffer, it is lent to the loop until the publish is done
+                let image = match &mut entity.storage {
+                    EntityStorage::Image(storage) => storage.buffer.take_if(|buffer| !buffer.is_empty()),
+                    _ => None,
+                };
+
                 let mut publish_to_attributes = false;
                 match &entity.storage {
                     EntityStorage::Switch(SwitchStorage { state: Some(SwitchState { value, .. }), .. }) => device
@@ -912,6 +1239,47 @@ pub async fn run<T: Transport>(
                         write!(device.publish_buffer, "{}", value)
                             .expect("publish buffer too small for number state payload")
                     }
+                    EntityStorage::Text(TextStorage { state, state_timestamp: Some(_), .. }) => device
+                        .publish_buffer
+                        .extend_from_slice(state.as_bytes())
+                        .expect("publish buffer too small for text state payload"),
+                    EntityStorage::Select(SelectStorage { state: Some(SelectState { value, .. }), .. }) => {
+                        let option = entity.config.options.and_then(|options| options.get(*value)).copied();
+                        device
+                            .publish_buffer
+                            .extend_from_slice(option.unwrap_or_default().as_bytes())
+                            .expect("publish buffer too small for select state payload")
+                    }
+                    EntityStorage::Light(LightStorage { state: Some(LightState { value, .. }), .. }) => {
+                        let effect = value
+                            .effect
+                            .and_then(|effect| entity.config.effect_list.and_then(|effects| effects.get(effect)))
+                            .copied();
+                        let color_mode = entity
+                            .config
+                            .supported_color_modes
+                            .and_then(|modes| modes.first())
+                            .copied()
+                            .unwrap_or(constants::HA_LIGHT_COLOR_MODE_RGB);
+                        let payload = LightStatePayload {
+                            state: value.state.as_str(),
+                            brightness: value.brightness,
+                            color_mode,
+                            color: (color_mode == constants::HA_LIGHT_COLOR_MODE_RGB).then_some(LightColorPayload {
+                                r: value.color[0],
+                                g: value.color[1],
+                                b: value.color[2],
+                            }),
+                            effect,
+                        };
+                        device
+                            .publish_buffer
+                            .resize(device.publish_buffer.capacity(), 0)
+                            .expect("resize to capacity should never fail");
+                        let n = serde_json_core::to_slice(&payload, device.publish_buffer)
+                            .expect("publish buffer too small for light state payload");
+                        device.publish_buffer.truncate(n);
+                    }
                     EntityStorage::DeviceTracker(DeviceTrackerStorage { state: Some(tracker_state) }) => {
                         publish_to_attributes = true;
                         device
@@ -922,6 +1290,7 @@ pub async fn run<T: Transport>(
                             .expect("publish buffer too small for tracker state payload");
                         device.publish_buffer.truncate(n);
                     }
+                    EntityStorage::Image(_) if image.is_some() => {}
                     _ => {
                         if !first_iteration_push {
                             crate::log::warn!(
@@ -933,7 +1302,7 @@ pub async fn run<T: Transport>(
                     }
                 }
 
-                if publish_to_attributes {
+                let publish_topic = if publish_to_attributes {
                     let attributes_topic_display =
                         AttributesTopicDisplay { device_id: device.config.device_id, entity_id: entity.config.id };
                     device.attributes_topic_buffer.clear();
@@ -946,10 +1315,20 @@ pub async fn run<T: Transport>(
                     device.state_topic_buffer.clear();
                     write!(device.state_topic_buffer, "{state_topic_display}").expect("state topic buffer too small");
                     device.state_topic_buffer.as_str()
-                }
+                };
+                (publish_topic, image)
             };
 
-            match embassy_time::with_timeout(MQTT_TIMEOUT, client.publish(publish_topic, device.publish_buffer)).await {
+            let payload: &[u8] = match image.as_deref() {
+                Some(image) => image,
+                None => device.publish_buffer,
+            };
+            let result = embassy_time::with_timeout(MQTT_TIMEOUT, client.publish(publish_topic, payload)).await;
+            // hand the image buffer back before anything else, even a failed publish must not lose it
+            if let Some(image) = image {
+                entity.borrow_mut().as_mut().unwrap().storage.as_image_mut().buffer = Some(image);
+            }
+            match result {
                 Ok(Ok(_)) => {}
                 Ok(Err(err)) => {
                     crate::log::error!(
@@ -964,6 +1343,59 @@ pub async fn run<T: Transport>(
                     return Err(Error::new("mqtt publish timed out"));
                 }
             }
+
+            // sensor attributes go out right after the state they belong to
+            let publish_attributes = {
+                let mut entity = entity.borrow_mut();
+                match entity.as_mut() {
+                    Some(EntityData {
+                        config,
+                        storage:
+                            EntityStorage::NumericSensor(NumericSensorStorage {
+                                attributes: Some(attributes),
+                                attributes_publish,
+                                ..
+                            }),
+                        ..
+                    }) if !attributes.is_empty() && (*attributes_publish || first_iteration_push) => {
+                        *attributes_publish = false;
+                        device.publish_buffer.clear();
+                        device
+                            .publish_buffer
+                            .extend_from_slice(attributes.as_bytes())
+                            .expect("publish buffer too small for sensor attributes payload");
+                        let attributes_topic_display =
+                            AttributesTopicDisplay { device_id: device.config.device_id, entity_id: config.id };
+                        device.attributes_topic_buffer.clear();
+                        write!(device.attributes_topic_buffer, "{attributes_topic_display}")
+                            .expect("attributes topic buffer too small");
+                        true
+                    }
+                    _ => false,
+                }
+            };
+            if !publish_attributes {
+                continue;
+            }
+
+            let attributes_topic = device.attributes_topic_buffer.as_str();
+            match embassy_time::with_timeout(MQTT_TIMEOUT, client.publish(attributes_topic, device.publish_buffer))
+                .await
+            {
+                Ok(Ok(_)) => {}
+                Ok(Err(err)) => {
+                    crate::log::error!(
+                        "mqtt attributes publish on topic '{}' failed with: {:?}",
+                        attributes_topic,
+                        crate::log::Debug2Format(&err)
+                    );
+                    return Err(Error::new("mqtt publish failed"));
+                }
+                Err(_) => {
+                    crate::log::error!("mqtt attributes publish on topic '{}' timed out", attributes_topic);
+                    return Err(Error::new("mqtt publish timed out"));
+                }
+            }
         }
         first_iteration_push = false;
 
@@ -988,7 +1420,14 @@ pub async fn run<T: Transport>(
             }
         };
 
+        let subscription = device
+            .subscriptions
+            .iter()
+            .find(|subscription| subscription.borrow().as_ref().is_some_and(|data| data.topic == publish.topic));
         let entity = 'entity_search_block: {
+            if subscription.is_some() {
+                break 'entity_search_block None;
+            }
             for entity in device.entities {
                 let mut data = entity.borrow_mut();
                 let data = match data.as_mut() {
@@ -1002,13 +1441,13 @@ pub async fn run<T: Transport>(
                 write!(device.command_topic_buffer, "{command_topic_display}").expect("command topic buffer too small");
 
                 if device.command_topic_buffer.as_bytes() == publish.topic.as_bytes() {
-                    break 'entity_search_block entity;
+                    break 'entity_search_block Some(entity);
                 }
             }
             continue 'outer_loop;
         };
 
-        let mut read_buffer = [0u8; 128];
+        let mut read_buffer = [0u8; TEXT_MAX_LENGTH + 1];
         if publish.data_len > read_buffer.len() {
             crate::log::warn!(
                 "mqtt publish payload on topic {} is too large ({} bytes), ignoring it",
@@ -1041,6 +1480,14 @@ pub async fn run<T: Transport>(
             }
         };
 
+        let Some(entity) = entity else {
+            // without an entity the message arrived on one of the subscriptions
+            if let Some(subscription) = subscription {
+                subscription.borrow_mut().as_mut().unwrap().receive(command);
+            }
+            continue;
+        };
+
         let mut entity = entity.borrow_mut();
         let data = entity.as_mut().unwrap();
 
@@ -1096,6 +1543,101 @@ pub async fn run<T: Transport>(
                 }
                 number_storage.command = Some(NumberCommand { value: command, timestamp });
             }
+            EntityStorage::Text(text_storage) => {
+                if command.len() > text_storage.command.capacity() {
+                    crate::log::warn!(
+                        "text '{}' received a command longer than {} bytes, ignoring it",
+                        data.config.id,
+                        text_storage.command.capacity()
+                    );
+                    continue;
+                }
+                let timestamp = embassy_time::Instant::now();
+                if text_storage.command_policy == CommandPolicy::PublishState {
+                    data.publish = true;
+                    text_storage.set_state(command, timestamp);
+                }
+                text_storage.set_command(command, timestamp);
+            }
+            EntityStorage::Select(select_storage) => {
+                let options = data.config.options.unwrap_or_default();
+                let command = match options.iter().position(|option| *option == command) {
+                    Some(index) => index,
+                    None => {
+                        crate::log::warn!(
+                            "select '{}' received invalid command '{}', expected one of its options, ignoring it",
+                            data.config.id,
+                            command
+                        );
+                        continue;
+                    }
+                };
+                let timestamp = embassy_time::Instant::now();
+                if select_storage.command_policy == CommandPolicy::PublishState {
+                    data.publish = true;
+                    select_storage.state = Some(SelectState { value: command, timestamp });
+                }
+                select_storage.command = Some(SelectCommand { value: command, timestamp });
+            }
+            EntityStorage::Light(light_storage) => {
+                let payload = match serde_json_core::from_str::<LightCommandPayload>(command) {
+                    Ok((payload, _)) => payload,
+                    Err(_) => {
+                        crate::log::warn!(
+                            "light '{}' received invalid command '{}', expected a json object, ignoring it",
+                            data.config.id,
+                            command
+                        );
+                        continue;
+                    }
+                };
+
+                let mut value = match (&light_storage.command, &light_storage.state) {
+                    (Some(command), _) => command.value,
+                    (None, Some(state)) => state.value,
+                    (None, None) => LightValue::default(),
+                };
+                if let Some(state) = payload.state {
+                    match state.parse::<BinaryState>() {
+                        Ok(state) => value.state = state,
+                        Err(_) => {
+                            crate::log::warn!(
+                                "light '{}' received invalid state '{}', expected 'ON' or 'OFF', ignoring it",
+                                data.config.id,
+                                state
+                            );
+                            continue;
+                        }
+                    }
+                }
+                if let Some(brightness) = payload.brightness {
+                    value.brightness = brightness;
+                }
+                if let Some(color) = payload.color {
+                    value.color = [color.r, color.g, color.b];
+                }
+                if let Some(effect) = payload.effect {
+                    let effects = data.config.effect_list.unwrap_or_default();
+                    match effects.iter().position(|option| *option == effect) {
+                        Some(index) => value.effect = Some(index),
+                        None => {
+                            crate::log::warn!(
+                                "light '{}' received unknown effect '{}', ignoring it",
+                                data.config.id,
+                                effect
+                            );
+                            continue;
+                        }
+                    }
+                }
+
+                let timestamp = embassy_time::Instant::now();
+                if light_storage.command_policy == CommandPolicy::PublishState {
+                    data.publish = true;
+                    light_storage.state = Some(LightState { value, timestamp });
+                }
+                light_storage.command = Some(LightCommand { value, timestamp });
+            }
             _ => continue 'outer_loop,
         }
 
@@ -1134,12 +1676,13 @@ pub async fn run<T: Transport>(
 /// ```no_run
 /// # use embassy_executor::Spawner;
 /// # use embassy_ha::{Device, DeviceConfig};
-/// # use static_cell::StaticCell;
-/// # static HA_RESOURCES: StaticCell<embassy_ha::DeviceResources> = StaticCell::new();
+/// # use static_cell::ConstStaticCell;
+/// # static HA_RESOURCES: ConstStaticCell<embassy_ha::DeviceResources> =
+/// #     ConstStaticCell::new(embassy_ha::DeviceResources::new());
 /// #[embassy_executor::task]
 /// async fn ha_task(stack: embassy_net::Stack<'static>) {
 ///     let device = embassy_ha::new(
-///         HA_RESOURCES.init(Default::default()),
+///         HA_RESOURCES.take(),
 ///         DeviceConfig {
 ///             device_id: "my-device",
 ///             device_name: "My Device",
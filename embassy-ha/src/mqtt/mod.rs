@@ -131,8 +131,8 @@ pub struct ClientResources<
     tx_buffer: [u8; TX],
 }
 
-impl<const RX: usize, const TX: usize> Default for ClientResources<RX, TX> {
-    fn default() -> Self {
+impl<const RX: usize, const TX: usize> ClientResources<RX, TX> {
+    pub const fn new() -> Self {
         Self {
             rx_buffer: [0u8; RX],
             tx_buffer: [0u8; TX],
@@ -140,6 +140,12 @@ impl<const RX: usize, const TX: usize> Default for ClientResources<RX, TX> {
     }
 }
 
+impl<const RX: usize, const TX: usize> Default for ClientResources<RX, TX> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub struct Client<'a, T> {
     transport: T,
     rx_buffer: &'a mut [u8],
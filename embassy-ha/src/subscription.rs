@@ -0,0 +1,81 @@
+use core::{cell::RefCell, task::Waker};
+
+use heapless::String;
+
+/// Maximum length of a message received on a subscribed topic, longer messages are ignored.
+pub const SUBSCRIPTION_PAYLOAD_MAX_LENGTH: usize = 64;
+
+pub(crate) struct SubscriptionData {
+    pub topic: &'static str,
+    pub payload: Option<String<SUBSCRIPTION_PAYLOAD_MAX_LENGTH>>,
+    pub waker: Option<Waker>,
+}
+
+impl SubscriptionData {
+    pub fn receive(&mut self, payload: &str) {
+        match String::try_from(payload) {
+            Ok(payload) => self.payload = Some(payload),
+            Err(_) => {
+                crate::log::warn!(
+                    "message on subscribed topic '{}' is longer than {} bytes, ignoring it",
+                    self.topic,
+                    SUBSCRIPTION_PAYLOAD_MAX_LENGTH
+                );
+                return;
+            }
+        }
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// Messages published by others on an arbitrary MQTT topic.
+///
+/// Unlike entities, subscriptions are not announced to Home Assistant, they let the device follow state that lives
+/// elsewhere, e.g. Home Assistant entities exported with the `mqtt_statestream` integration. Retained messages are
+/// delivered right after every (re)connection.
+pub struct Subscription<'a> {
+    pub(crate) data: &'a RefCell<Option<SubscriptionData>>,
+}
+
+impl<'a> Subscription<'a> {
+    pub fn topic(&self) -> &'static str {
+        self.with_data(|data| data.topic)
+    }
+
+    /// Waits for the next message on the topic and returns its payload.
+    pub async fn wait(&mut self) -> String<SUBSCRIPTION_PAYLOAD_MAX_LENGTH> {
+        struct Fut<'a, 'b>(&'a mut Subscription<'b>);
+
+        impl<'a, 'b> core::future::Future for Fut<'a, 'b> {
+            type Output = String<SUBSCRIPTION_PAYLOAD_MAX_LENGTH>;
+
+            fn poll(
+                mut self: core::pin::Pin<&mut Self>,
+                cx: &mut core::task::Context<'_>,
+            ) -> core::task::Poll<Self::Output> {
+                let this = &mut self.as_mut().0;
+                this.with_data(|data| match data.payload.take() {
+                    Some(payload) => {
+                        data.waker = None;
+                        core::task::Poll::Ready(payload)
+                    }
+                    None => {
+                        data.waker = Some(cx.waker().clone());
+                        core::task::Poll::Pending
+                    }
+                })
+            }
+        }
+
+        Fut(self).await
+    }
+
+    fn with_data<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut SubscriptionData) -> R,
+    {
+        f(self.data.borrow_mut().as_mut().unwrap())
+    }
+}
@@ -0,0 +1,343 @@
+//! Alarm schedules and the state machine deciding when they ring.
+//!
+//! The clock is passed in as local wall time for matching the schedules plus a monotonic millisecond counter for
+//! snoozing and ringing timeouts, so the engine can be driven by any clock.
+
+use alloc::string::String;
+use core::fmt::Write as _;
+
+use serde::{Deserialize, Serialize};
+pub use serde_json_core::de::Error as JsonError;
+
+pub const DEFAULT_SNOOZE_MINUTES: u8 = 9;
+/// An alarm nobody reacts to stops ringing after this long.
+pub const RING_TIMEOUT_MS: u64 = 10 * 60 * 1000;
+/// Day initials starting on Monday, as used in the day masks formatted for display.
+pub const DAY_INITIALS: [char; 7] = ['M', 'T', 'W', 'T', 'F', 'S', 'S'];
+pub const WEEKDAYS: u8 = 0b0011111;
+pub const EVERY_DAY: u8 = 0b1111111;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Melody {
+    #[default]
+    Beep,
+    Chime,
+    Siren,
+}
+
+impl Melody {
+    pub const ALL: [Melody; 3] = [Melody::Beep, Melody::Chime, Melody::Siren];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Melody::Beep => "beep",
+            Melody::Chime => "chime",
+            Melody::Siren => "siren",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|melody| melody.name() == name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Alarm {
+    pub enabled: bool,
+    pub hour: u8,
+    pub minute: u8,
+    /// Bit 0 is Monday, an empty mask rings once and disables the alarm when dismissed.
+    pub days: u8,
+    pub label: String,
+    pub melody: Melody,
+}
+
+impl Default for Alarm {
+    fn default() -> Self {
+        Alarm { enabled: false, hour: 7, minute: 0, days: WEEKDAYS, label: String::new(), melody: Melody::default() }
+    }
+}
+
+/// Local wall time the schedules are matched against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocalTime {
+    /// Days since Monday.
+    pub weekday: u8,
+    pub hour: u8,
+    pub minute: u8,
+}
+
+impl Alarm {
+    pub fn is_one_shot(&self) -> bool {
+        self.days & EVERY_DAY == 0
+    }
+
+    pub fn rings_on(&self, weekday: u8) -> bool {
+        self.is_one_shot() || self.days & (1 << weekday) != 0
+    }
+
+    pub fn is_due(&self, now: LocalTime) -> bool {
+        self.enabled && self.hour == now.hour && self.minute == now.minute && self.rings_on(now.weekday)
+    }
+
+    /// Merges a JSON command like `{"time": "07:30", "days": "MTWTF..", "label": "..", "melody": "chime",
+    /// "enabled": true}` into the alarm, every field is optional.
+    pub fn apply_command(&mut self, payload: &str) -> Result<(), AlarmError> {
+        let (command, _) = serde_json_core::from_str::<AlarmCommand>(payload).map_err(AlarmError::InvalidJson)?;
+        let time = command.time.map(|time| parse_time(time).ok_or(AlarmError::InvalidTime)).transpose()?;
+        let days = command.days.map(|days| parse_days(days).ok_or(AlarmError::InvalidDays)).transpose()?;
+        let melody =
+            command.melody.map(|melody| Melody::from_name(melody).ok_or(AlarmError::UnknownMelody)).transpose()?;
+
+        if let Some((hour, minute)) = time {
+            self.hour = hour;
+            self.minute = minute;
+        }
+        if let Some(days) = days {
+            self.days = days;
+        }
+        if let Some(label) = command.label {
+            self.label = label.into();
+        }
+        if let Some(melody) = melody {
+            self.melody = melody;
+        }
+        if let Some(enabled) = command.enabled {
+            self.enabled = enabled;
+        }
+        Ok(())
+    }
+
+    /// Formatted like `07:30 MTWTF.. chime Wake up`, one shot alarms show `once` instead of the days.
+    pub fn description(&self) -> String {
+        let mut description = String::new();
+        write!(description, "{:02}:{:02} ", self.hour, self.minute).ok();
+        if self.is_one_shot() {
+            description.push_str("once");
+        } else {
+            for (day, initial) in DAY_INITIALS.iter().enumerate() {
+                description.push(if self.days & (1 << day) != 0 { *initial } else { '.' });
+            }
+        }
+        description.push(' ');
+        description.push_str(self.melody.name());
+        if !self.label.is_empty() {
+            description.push(' ');
+            description.push_str(&self.label);
+        }
+        description
+    }
+}
+
+#[derive(Debug)]
+pub enum AlarmError {
+    InvalidJson(JsonError),
+    InvalidTime,
+    InvalidDays,
+    UnknownMelody,
+}
+
+#[derive(Deserialize)]
+struct AlarmCommand<'a> {
+    time: Option<&'a str>,
+    days: Option<&'a str>,
+    label: Option<&'a str>,
+    melody: Option<&'a str>,
+    enabled: Option<bool>,
+}
+
+/// Parses `HH:MM` in 24 hour format.
+pub fn parse_time(time: &str) -> Option<(u8, u8)> {
+    let (hour, minute) = time.split_once(':')?;
+    let hour = hour.trim().parse::<u8>().ok().filter(|hour| *hour < 24)?;
+    let minute = minute.trim().parse::<u8>().ok().filter(|minute| *minute < 60)?;
+    Some((hour, minute))
+}
+
+/// Parses one character per day starting on Monday, `.`, `-` and `0` mark the days off so both `MTWTF..` and
+/// `1111100` work. An empty string is a one shot alarm.
+pub fn parse_days(days: &str) -> Option<u8> {
+    if days.is_empty() {
+        return Some(0);
+    }
+    if days.chars().count() != 7 {
+        return None;
+    }
+    Some(
+        days.chars()
+            .enumerate()
+            .filter(|(_, c)| !matches!(c, '.' | '-' | '0'))
+            .fold(0, |mask, (day, _)| mask | 1 << day),
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlarmStatus {
+    Idle,
+    Ringing { index: usize, since_ms: u64 },
+    Snoozed { index: usize, until_ms: u64 },
+}
+
+/// Tracks which alarm is ringing or snoozed. Each schedule fires at most once per matching minute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlarmClock {
+    status: AlarmStatus,
+    last_checked: Option<LocalTime>,
+}
+
+impl Default for AlarmClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AlarmClock {
+    pub const fn new() -> Self {
+        AlarmClock { status: AlarmStatus::Idle, last_checked: None }
+    }
+
+    pub fn status(&self) -> AlarmStatus {
+        self.status
+    }
+
+    /// Index of the alarm currently ringing.
+    pub fn ringing(&self) -> Option<usize> {
+        match self.status {
+            AlarmStatus::Ringing { index, .. } => Some(index),
+            _ => None,
+        }
+    }
+
+    /// Should be called at least once a minute, returns the index of the alarm that started ringing. A ringing alarm
+    /// nobody reacts to is dismissed after [`RING_TIMEOUT_MS`], `true` is returned alongside when that changed the
+    /// alarms.
+    pub fn check(&mut self, alarms: &mut [Alarm], now: LocalTime, now_ms: u64) -> (Option<usize>, bool) {
+        let mut changed = false;
+        match self.status {
+            AlarmStatus::Ringing { since_ms, .. } if now_ms.saturating_sub(since_ms) >= RING_TIMEOUT_MS => {
+                changed = self.dismiss(alarms);
+            }
+            AlarmStatus::Snoozed { index, until_ms } if now_ms >= until_ms => {
+                self.status = AlarmStatus::Ringing { index, since_ms: now_ms };
+                return (Some(index), changed);
+            }
+            _ => {}
+        }
+
+        if self.last_checked == Some(now) {
+            return (None, changed);
+        }
+        self.last_checked = Some(now);
+
+        // a newly due alarm takes over from a snoozed one, but never interrupts one that is ringing
+        if self.ringing().is_some() {
+            return (None, changed);
+        }
+        match alarms.iter().position(|alarm| alarm.is_due(now)) {
+            Some(index) => {
+                self.status = AlarmStatus::Ringing { index, since_ms: now_ms };
+                (Some(index), changed)
+            }
+            None => (None, changed),
+        }
+    }
+
+    pub fn snooze(&mut self, now_ms: u64, snooze_minutes: u8) {
+        if let AlarmStatus::Ringing { index, .. } = self.status {
+            self.status = AlarmStatus::Snoozed { index, until_ms: now_ms + snooze_minutes as u64 * 60 * 1000 };
+        }
+    }
+
+    /// Stops the ringing or snoozed alarm, one shot alarms get disabled. Returns `true` when the alarms changed.
+    pub fn dismiss(&mut self, alarms: &mut [Alarm]) -> bool {
+        let index = match self.status {
+            AlarmStatus::Idle => return false,
+            AlarmStatus::Ringing { index, .. } | AlarmStatus::Snoozed { index, .. } => index,
+        };
+        self.status = AlarmStatus::Idle;
+        match alarms.get_mut(index) {
+            Some(alarm) if alarm.is_one_shot() && alarm.enabled => {
+                alarm.enabled = false;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(weekday: u8, hour: u8, minute: u8) -> LocalTime {
+        LocalTime { weekday, hour, minute }
+    }
+
+    fn alarm(hour: u8, minute: u8, days: u8) -> Alarm {
+        Alarm { enabled: true, hour, minute, days, ..Default::default() }
+    }
+
+    #[test]
+    fn matches_days() {
+        let weekdays = alarm(7, 30, WEEKDAYS);
+        assert!(weekdays.is_due(at(0, 7, 30)));
+        assert!(!weekdays.is_due(at(5, 7, 30)));
+        assert!(!weekdays.is_due(at(0, 7, 31)));
+        assert!(alarm(7, 30, 0).is_due(at(6, 7, 30)));
+        assert!(!Alarm { enabled: false, ..weekdays }.is_due(at(0, 7, 30)));
+    }
+
+    #[test]
+    fn applies_commands() {
+        let mut alarm = Alarm::default();
+        alarm.apply_command(r#"{"time": "6:05", "days": "M.W.F..", "label": "Gym", "enabled": true}"#).unwrap();
+        assert_eq!((alarm.hour, alarm.minute, alarm.days), (6, 5, 0b10101));
+        assert_eq!(alarm.description(), "06:05 M.W.F.. beep Gym");
+
+        alarm.apply_command(r#"{"days": "", "melody": "siren"}"#).unwrap();
+        assert_eq!(alarm.description(), "06:05 once siren Gym");
+        assert!(alarm.enabled);
+
+        assert!(matches!(alarm.apply_command(r#"{"time": "24:00"}"#), Err(AlarmError::InvalidTime)));
+        assert!(matches!(alarm.apply_command(r#"{"days": "1111"}"#), Err(AlarmError::InvalidDays)));
+        assert!(matches!(alarm.apply_command(r#"{"melody": "bell"}"#), Err(AlarmError::UnknownMelody)));
+        assert_eq!(alarm.hour, 6);
+    }
+
+    #[test]
+    fn rings_once_per_minute() {
+        let mut alarms = [alarm(7, 0, EVERY_DAY)];
+        let mut clock = AlarmClock::new();
+        assert_eq!(clock.check(&mut alarms, at(0, 6, 59), 0), (None, false));
+        assert_eq!(clock.check(&mut alarms, at(0, 7, 0), 1_000), (Some(0), false));
+        clock.dismiss(&mut alarms);
+        assert_eq!(clock.check(&mut alarms, at(0, 7, 0), 2_000), (None, false));
+        assert_eq!(clock.status(), AlarmStatus::Idle);
+    }
+
+    #[test]
+    fn snoozes() {
+        let mut alarms = [alarm(7, 0, EVERY_DAY)];
+        let mut clock = AlarmClock::new();
+        clock.check(&mut alarms, at(0, 7, 0), 0);
+        clock.snooze(10_000, 5);
+        assert_eq!(clock.ringing(), None);
+        assert_eq!(clock.check(&mut alarms, at(0, 7, 4), 309_999), (None, false));
+        assert_eq!(clock.check(&mut alarms, at(0, 7, 5), 310_000), (Some(0), false));
+        assert_eq!(clock.ringing(), Some(0));
+    }
+
+    #[test]
+    fn one_shot_alarms_disable_themselves() {
+        let mut alarms = [alarm(7, 0, 0)];
+        let mut clock = AlarmClock::new();
+        clock.check(&mut alarms, at(3, 7, 0), 0);
+        assert!(clock.dismiss(&mut alarms));
+        assert!(!alarms[0].enabled);
+
+        alarms[0].enabled = true;
+        clock.check(&mut alarms, at(4, 7, 0), 0);
+        assert_eq!(clock.check(&mut alarms, at(4, 7, 10), RING_TIMEOUT_MS), (None, true));
+        assert!(!alarms[0].enabled);
+    }
+}
@@ -0,0 +1,385 @@
+//! Request and response types of the AWTRIX 3 HTTP API, only the subset the firmware supports.
+
+use alloc::{string::String, vec::Vec};
+use core::fmt::{self, Write as _};
+
+use serde::{
+    de::{self, DeserializeOwned, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
+pub use serde_json_core::de::Error as JsonError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Route {
+    Notify,
+    DismissNotification,
+    Custom,
+    Settings,
+    Stats,
+    /// Indicator number, starting at 1 like the endpoint names.
+    Indicator(usize),
+    Screen,
+    /// Not part of AWTRIX, lists and edits the countdowns.
+    Countdown,
+}
+
+pub fn route(path: &str) -> Option<Route> {
+    let path = path.strip_suffix('/').unwrap_or(path);
+    match path.strip_prefix("/api/")? {
+        "notify" => Some(Route::Notify),
+        "notify/dismiss" => Some(Route::DismissNotification),
+        "custom" => Some(Route::Custom),
+        "settings" => Some(Route::Settings),
+        "stats" => Some(Route::Stats),
+        "screen" => Some(Route::Screen),
+        "countdown" => Some(Route::Countdown),
+        other => match other.strip_prefix("indicator")?.parse::<usize>() {
+            Ok(index @ 1..=3) => Some(Route::Indicator(index)),
+            _ => None,
+        },
+    }
+}
+
+/// Deserializes a JSON body, trailing whitespace is accepted.
+pub fn from_json<T: DeserializeOwned>(body: &[u8]) -> Result<T, JsonError> {
+    let body = normalize_colors(body);
+    serde_json_core::from_slice(&body).map(|(value, _)| value)
+}
+
+/// serde-json-core can not deserialize untyped values, so `"color"` values given as `[r, g, b]` arrays or integers
+/// are rewritten to hex strings before the body is deserialized. Anything unexpected is copied as is and left for the
+/// deserializer to reject.
+fn normalize_colors(body: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(body.len());
+    let mut i = 0;
+    while i < body.len() {
+        if body[i] != b'"' {
+            out.push(body[i]);
+            i += 1;
+            continue;
+        }
+
+        let end = string_end(body, i);
+        out.extend_from_slice(&body[i..end]);
+        let is_color_key = &body[i..end] == b"\"color\"";
+        i = end;
+        if !is_color_key {
+            continue;
+        }
+
+        let colon = skip_whitespace(body, i);
+        if body.get(colon) != Some(&b':') {
+            continue;
+        }
+        out.extend_from_slice(&body[i..=colon]);
+        i = skip_whitespace(body, colon + 1);
+
+        let value_end = match body.get(i) {
+            Some(b'[') => body[i..].iter().position(|c| *c == b']').map(|end| i + end + 1),
+            Some(b'0'..=b'9') => {
+                Some(body[i..].iter().position(|c| !c.is_ascii_digit()).map_or(body.len(), |end| i + end))
+            }
+            _ => None,
+        };
+        let color =
+            value_end.and_then(|end| parse_color_value(core::str::from_utf8(&body[i..end]).ok()?).map(|c| (c, end)));
+        if let Some((color, end)) = color {
+            let [r, g, b] = color.0;
+            let mut hex = String::new();
+            write!(hex, "\"#{:02X}{:02X}{:02X}\"", r, g, b).ok();
+            out.extend_from_slice(hex.as_bytes());
+            i = end;
+        }
+    }
+    out
+}
+
+/// Index just past the closing quote of the string starting at `start`.
+fn string_end(body: &[u8], start: usize) -> usize {
+    let mut i = start + 1;
+    while i < body.len() {
+        match body[i] {
+            b'\\' => i += 2,
+            b'"' => return i + 1,
+            _ => i += 1,
+        }
+    }
+    body.len()
+}
+
+fn skip_whitespace(body: &[u8], start: usize) -> usize {
+    body[start.min(body.len())..].iter().position(|c| !c.is_ascii_whitespace()).map_or(body.len(), |n| start + n)
+}
+
+fn parse_color_value(value: &str) -> Option<Color> {
+    if let Some(channels) = value.strip_prefix('[').and_then(|value| value.strip_suffix(']')) {
+        let mut color = [0u8; 3];
+        let mut channels = channels.split(',');
+        for channel in color.iter_mut() {
+            *channel = channels.next()?.trim().parse().ok()?;
+        }
+        channels.next().is_none().then_some(Color(color))
+    } else {
+        value.parse::<u32>().ok().filter(|value| *value <= 0xFF_FFFF).map(Color::from_u32)
+    }
+}
+
+/// Color as accepted by AWTRIX: `"#RRGGBB"`, `"RRGGBB"`, `[r, g, b]` or a 24 bit integer, `"0"` means off.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Color(pub [u8; 3]);
+
+impl Color {
+    pub const BLACK: Color = Color([0, 0, 0]);
+
+    pub fn is_black(&self) -> bool {
+        *self == Self::BLACK
+    }
+
+    pub fn from_u32(value: u32) -> Self {
+        Color([(value >> 16) as u8, (value >> 8) as u8, value as u8])
+    }
+
+    pub fn to_u32(self) -> u32 {
+        let [r, g, b] = self.0;
+        (r as u32) << 16 | (g as u32) << 8 | b as u32
+    }
+
+    /// Parses `"#RRGGBB"`, `"RRGGBB"` or `"0"`.
+    pub fn parse_hex(value: &str) -> Option<Self> {
+        let hex = value.strip_prefix('#').unwrap_or(value);
+        if hex == "0" {
+            return Some(Self::BLACK);
+        }
+        if hex.len() != 6 {
+            return None;
+        }
+        u32::from_str_radix(hex, 16).ok().map(Self::from_u32)
+    }
+}
+
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ColorVisitor;
+
+        impl Visitor<'_> for ColorVisitor {
+            type Value = Color;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a hex color string, an [r, g, b] array or an integer")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Color, E> {
+                Color::parse_hex(value).ok_or_else(|| E::invalid_value(de::Unexpected::Str(value), &self))
+            }
+        }
+
+        deserializer.deserialize_str(ColorVisitor)
+    }
+}
+
+/// Body of `POST /api/notify`.
+#[derive(Debug, Default, PartialEq, Deserialize)]
+pub struct NotifyRequest {
+    pub text: Option<String>,
+    pub icon: Option<String>,
+    pub color: Option<Color>,
+    /// Seconds the notification is shown.
+    pub duration: Option<u16>,
+    pub hold: Option<bool>,
+}
+
+/// Body of `POST /api/custom?name=..`, an empty body or missing text removes the app.
+#[derive(Debug, Default, PartialEq, Deserialize)]
+pub struct CustomAppRequest {
+    pub text: Option<String>,
+    pub icon: Option<String>,
+    pub color: Option<Color>,
+    pub duration: Option<u16>,
+}
+
+/// Body of `POST /api/indicator1..3`.
+#[derive(Debug, Default, PartialEq, Deserialize)]
+pub struct IndicatorRequest {
+    pub color: Option<Color>,
+    /// Blink interval in milliseconds.
+    pub blink: Option<u32>,
+    /// Fade interval in milliseconds.
+    pub fade: Option<u32>,
+}
+
+/// Body of `POST /api/settings`, every setting is optional.
+#[derive(Debug, Default, PartialEq, Deserialize)]
+pub struct SettingsRequest {
+    /// Automatic page rotation.
+    #[serde(rename = "ATRANS")]
+    pub auto_transition: Option<bool>,
+    /// Transition duration in milliseconds.
+    #[serde(rename = "TSPEED")]
+    pub transition_speed: Option<u32>,
+    /// Transition effect index.
+    #[serde(rename = "TEFF")]
+    pub transition_effect: Option<u8>,
+    /// Show the time page.
+    #[serde(rename = "TIM")]
+    pub time: Option<bool>,
+    /// Show the date page.
+    #[serde(rename = "DAT")]
+    pub date: Option<bool>,
+    /// Show the battery page.
+    #[serde(rename = "BAT")]
+    pub battery: Option<bool>,
+}
+
+/// Response of `GET /api/settings`.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct Settings {
+    #[serde(rename = "ATRANS")]
+    pub auto_transition: bool,
+    #[serde(rename = "TSPEED")]
+    pub transition_speed: u32,
+    #[serde(rename = "TEFF")]
+    pub transition_effect: u8,
+    #[serde(rename = "TIM")]
+    pub time: bool,
+    #[serde(rename = "DAT")]
+    pub date: bool,
+    #[serde(rename = "BAT")]
+    pub battery: bool,
+}
+
+/// Response of `GET /api/stats`.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct Stats<'a> {
+    pub bat: u8,
+    pub ram: u32,
+    pub uptime: u64,
+    pub version: &'a str,
+    pub indicator1: bool,
+    pub indicator2: bool,
+    pub indicator3: bool,
+    pub app: &'a str,
+}
+
+/// Formats the screen content as the JSON array of 24 bit colors returned by `GET /api/screen`.
+pub fn write_screen_json(pixels: impl IntoIterator<Item = Color>, out: &mut String) {
+    out.push('[');
+    for (i, pixel) in pixels.into_iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        write!(out, "{}", pixel.to_u32()).ok();
+    }
+    out.push(']');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn routes() {
+        assert_eq!(route("/api/notify"), Some(Route::Notify));
+        assert_eq!(route("/api/notify/dismiss"), Some(Route::DismissNotification));
+        assert_eq!(route("/api/custom/"), Some(Route::Custom));
+        assert_eq!(route("/api/indicator1"), Some(Route::Indicator(1)));
+        assert_eq!(route("/api/indicator3"), Some(Route::Indicator(3)));
+        assert_eq!(route("/api/countdown"), Some(Route::Countdown));
+        assert_eq!(route("/api/indicator4"), None);
+        assert_eq!(route("/api/indicator"), None);
+        assert_eq!(route("/api/unknown"), None);
+        assert_eq!(route("/notify"), None);
+    }
+
+    #[test]
+    fn parses_colors() {
+        assert_eq!(from_json::<Color>(b"\"#FF8000\""), Ok(Color([255, 128, 0])));
+        assert_eq!(from_json::<Color>(b"\"00ff00\""), Ok(Color([0, 255, 0])));
+        assert_eq!(from_json::<Color>(b"\"0\""), Ok(Color::BLACK));
+        assert!(from_json::<Color>(b"\"#FF80\"").is_err());
+
+        let color = |body: &[u8]| from_json::<IndicatorRequest>(body).map(|request| request.color);
+        assert_eq!(color(b"{\"color\": [1, 2, 3]}"), Ok(Some(Color([1, 2, 3]))));
+        assert_eq!(color(b"{\"color\":16711680}"), Ok(Some(Color([255, 0, 0]))));
+        assert_eq!(color(b"{\"text\": \"\\\"color\\\"\", \"color\": \"#0000FF\"}"), Ok(Some(Color([0, 0, 255]))));
+        assert!(color(b"{\"color\": [1, 2]}").is_err());
+        assert!(color(b"{\"color\": [1, 2, 3, 4]}").is_err());
+        assert!(color(b"{\"color\": [1, 2, 300]}").is_err());
+        assert!(color(b"{\"color\": 16777216}").is_err());
+    }
+
+    #[test]
+    fn parses_notify() {
+        let request: NotifyRequest =
+            from_json(br##"{"text": "Hello", "icon": "bell", "color": "#FF0000", "duration": 8, "rainbow": true}"##)
+                .unwrap();
+        assert_eq!(
+            request,
+            NotifyRequest {
+                text: Some("Hello".into()),
+                icon: Some("bell".into()),
+                color: Some(Color([255, 0, 0])),
+                duration: Some(8),
+                hold: None,
+            }
+        );
+        assert!(from_json::<NotifyRequest>(b"{\"text\": 5}").is_err());
+        assert!(from_json::<NotifyRequest>(b"{\"color\": [1, 2]}").is_err());
+    }
+
+    #[test]
+    fn parses_custom_app() {
+        let request: CustomAppRequest = from_json(b"{\"text\": \"21C\", \"color\": [0, 0, 255]}\n").unwrap();
+        assert_eq!(request.text.as_deref(), Some("21C"));
+        assert_eq!(request.color, Some(Color([0, 0, 255])));
+        assert_eq!(from_json::<CustomAppRequest>(b"{}"), Ok(CustomAppRequest::default()));
+    }
+
+    #[test]
+    fn parses_indicator() {
+        let request: IndicatorRequest = from_json(b"{\"color\": \"0\"}").unwrap();
+        assert!(request.color.unwrap().is_black());
+        let request: IndicatorRequest = from_json(b"{\"color\": [0, 255, 0], \"blink\": 500}").unwrap();
+        assert_eq!(request.blink, Some(500));
+    }
+
+    #[test]
+    fn parses_settings() {
+        let request: SettingsRequest = from_json(b"{\"ATRANS\": false, \"TSPEED\": 400, \"TIM\": true}").unwrap();
+        assert_eq!(
+            request,
+            SettingsRequest {
+                auto_transition: Some(false),
+                transition_speed: Some(400),
+                time: Some(true),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn serializes_stats() {
+        let stats = Stats {
+            bat: 80,
+            ram: 1024,
+            uptime: 60,
+            version: "0.1.0",
+            indicator2: true,
+            app: "time",
+            ..Default::default()
+        };
+        let mut buffer = [0u8; 256];
+        let len = serde_json_core::to_slice(&stats, &mut buffer).unwrap();
+        assert_eq!(
+            core::str::from_utf8(&buffer[..len]).unwrap(),
+            "{\"bat\":80,\"ram\":1024,\"uptime\":60,\"version\":\"0.1.0\",\"indicator1\":false,\"indicator2\":true,\
+             \"indicator3\":false,\"app\":\"time\"}"
+        );
+    }
+
+    #[test]
+    fn writes_screen() {
+        let mut out = String::new();
+        write_screen_json([Color([255, 0, 0]), Color::BLACK, Color([0, 0, 1])], &mut out);
+        assert_eq!(out, "[16711680,0,1]");
+    }
+}
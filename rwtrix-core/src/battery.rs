@@ -0,0 +1,255 @@
+//! Charge level of the LiPo cell from its voltage, and the charge state and runtime estimated from the voltage trend.
+
+use alloc::{collections::VecDeque, string::String};
+use core::fmt::Write as _;
+
+/// Typical LiPo discharge curve under light load, millivolts to percent, highest voltage first.
+const DISCHARGE_CURVE: [(u16, u8); 21] = [
+    (4200, 100),
+    (4150, 95),
+    (4110, 90),
+    (4080, 85),
+    (4020, 80),
+    (3980, 75),
+    (3950, 70),
+    (3910, 65),
+    (3870, 60),
+    (3850, 55),
+    (3840, 50),
+    (3820, 45),
+    (3800, 40),
+    (3790, 35),
+    (3770, 30),
+    (3750, 25),
+    (3730, 20),
+    (3710, 15),
+    (3690, 10),
+    (3610, 5),
+    (3300, 0),
+];
+
+/// Charge level in percent, interpolated between the points of the discharge curve.
+pub fn percentage_from_millivolts(millivolts: u16) -> f32 {
+    let (full, _) = DISCHARGE_CURVE[0];
+    if millivolts >= full {
+        return 100.0;
+    }
+    for pair in DISCHARGE_CURVE.windows(2) {
+        let [(high_mv, high), (low_mv, low)] = [pair[0], pair[1]];
+        if millivolts >= low_mv {
+            let fraction = (millivolts - low_mv) as f32 / (high_mv - low_mv) as f32;
+            return low as f32 + fraction * (high - low) as f32;
+        }
+    }
+    0.0
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ChargeState {
+    /// Not enough samples yet, or the level does not move.
+    #[default]
+    Unknown,
+    Charging,
+    Discharging,
+    /// Connected to power with the cell topped off.
+    Full,
+}
+
+impl ChargeState {
+    pub fn name(self) -> &'static str {
+        match self {
+            ChargeState::Unknown => "unknown",
+            ChargeState::Charging => "charging",
+            ChargeState::Discharging => "discharging",
+            ChargeState::Full => "full",
+        }
+    }
+}
+
+/// Slope in percent per hour above which the cell counts as charging, and below the negated value as discharging.
+const TREND_THRESHOLD: f32 = 2.0;
+/// Level from which a cell that does not discharge counts as full.
+const FULL_PERCENTAGE: f32 = 98.0;
+/// Span the samples need to cover before a trend is reported.
+const MIN_SPAN_SECS: u64 = 120;
+const MIN_SAMPLES: usize = 5;
+const MAX_SAMPLES: usize = 120;
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    secs: u64,
+    percentage: f32,
+}
+
+/// Keeps the samples of a moving window and fits a line through them, the slope gives the charge state and the time
+/// left until the cell is empty or full.
+pub struct BatteryEstimator {
+    window_secs: u64,
+    samples: VecDeque<Sample>,
+}
+
+impl BatteryEstimator {
+    pub fn new(window_secs: u64) -> Self {
+        BatteryEstimator { window_secs, samples: VecDeque::with_capacity(MAX_SAMPLES) }
+    }
+
+    /// Adds a voltage sample taken at `secs` of a monotonic clock.
+    pub fn push(&mut self, secs: u64, millivolts: u16) {
+        while self.samples.front().is_some_and(|first| first.secs + self.window_secs < secs)
+            || self.samples.len() >= MAX_SAMPLES
+        {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(Sample { secs, percentage: percentage_from_millivolts(millivolts) });
+    }
+
+    /// Level of the fitted line at the last sample, steadier than the last sample itself.
+    pub fn percentage(&self) -> f32 {
+        let Some(last) = self.samples.back() else {
+            return 0.0;
+        };
+        match self.fit() {
+            Some((slope, offset)) => (offset + slope * self.hours_since_first(last)).clamp(0.0, 100.0),
+            None => last.percentage,
+        }
+    }
+
+    /// Change of the level in percent per hour, once the window covers enough time.
+    pub fn slope(&self) -> Option<f32> {
+        self.fit().map(|(slope, _)| slope)
+    }
+
+    pub fn state(&self) -> ChargeState {
+        match self.slope() {
+            None => ChargeState::Unknown,
+            Some(slope) if slope <= -TREND_THRESHOLD => ChargeState::Discharging,
+            Some(_) if self.percentage() >= FULL_PERCENTAGE => ChargeState::Full,
+            Some(slope) if slope >= TREND_THRESHOLD => ChargeState::Charging,
+            Some(_) => ChargeState::Unknown,
+        }
+    }
+
+    /// Minutes until the cell is empty while discharging, or full while charging.
+    pub fn minutes_remaining(&self) -> Option<u32> {
+        let slope = self.slope()?;
+        let percent_left = match self.state() {
+            ChargeState::Discharging => self.percentage(),
+            ChargeState::Charging => 100.0 - self.percentage(),
+            _ => return None,
+        };
+        Some((percent_left / slope.abs() * 60.0) as u32)
+    }
+
+    fn hours_since_first(&self, sample: &Sample) -> f32 {
+        let first = self.samples.front().map_or(sample.secs, |first| first.secs);
+        (sample.secs - first) as f32 / 3600.0
+    }
+
+    /// Least squares line through the samples as slope and offset, hours since the first sample against percent.
+    fn fit(&self) -> Option<(f32, f32)> {
+        let (first, last) = (self.samples.front()?, self.samples.back()?);
+        if self.samples.len() < MIN_SAMPLES || last.secs - first.secs < MIN_SPAN_SECS {
+            return None;
+        }
+
+        let count = self.samples.len() as f32;
+        let (mut sum_x, mut sum_y, mut sum_xx, mut sum_xy) = (0.0, 0.0, 0.0, 0.0);
+        for sample in &self.samples {
+            let x = self.hours_since_first(sample);
+            sum_x += x;
+            sum_y += sample.percentage;
+            sum_xx += x * x;
+            sum_xy += x * sample.percentage;
+        }
+        let denominator = count * sum_xx - sum_x * sum_x;
+        if denominator <= 0.0 {
+            return None;
+        }
+        let slope = (count * sum_xy - sum_x * sum_y) / denominator;
+        Some((slope, (sum_y - slope * sum_x) / count))
+    }
+}
+
+/// Formats minutes like `3h05` or `45m`.
+pub fn write_runtime(out: &mut String, minutes: u32) {
+    if minutes >= 60 {
+        write!(out, "{}h{:02}", minutes / 60, minutes % 60).ok();
+    } else {
+        write!(out, "{}m", minutes).ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Recorded every 30 seconds on battery with the display on.
+    const DISCHARGING: [u16; 30] = [
+        3913, 3909, 3912, 3910, 3908, 3909, 3906, 3907, 3908, 3904, 3906, 3902, 3905, 3902, 3900, 3902, 3898, 3899,
+        3900, 3897, 3898, 3894, 3898, 3895, 3893, 3894, 3890, 3892, 3893, 3889,
+    ];
+
+    /// Recorded every 30 seconds, plugged in before the sixth sample where the charger lifts the voltage at once.
+    const CHARGING: [u16; 30] = [
+        3801, 3802, 3800, 3802, 3801, 3901, 3904, 3903, 3902, 3905, 3904, 3906, 3904, 3907, 3906, 3906, 3908, 3908,
+        3908, 3910, 3910, 3911, 3910, 3912, 3912, 3911, 3914, 3913, 3912, 3915,
+    ];
+
+    fn replay(series: &[u16]) -> BatteryEstimator {
+        let mut estimator = BatteryEstimator::new(900);
+        for (index, millivolts) in series.iter().enumerate() {
+            estimator.push(index as u64 * 30, *millivolts);
+        }
+        estimator
+    }
+
+    #[test]
+    fn follows_the_discharge_curve() {
+        assert_eq!(percentage_from_millivolts(4250), 100.0);
+        assert_eq!(percentage_from_millivolts(3840), 50.0);
+        assert_eq!(percentage_from_millivolts(3830), 47.5);
+        assert_eq!(percentage_from_millivolts(3200), 0.0);
+    }
+
+    #[test]
+    fn estimates_time_to_empty() {
+        let estimator = replay(&DISCHARGING);
+        assert_eq!(estimator.state(), ChargeState::Discharging);
+        let minutes = estimator.minutes_remaining().unwrap();
+        assert!((240..480).contains(&minutes), "{} minutes", minutes);
+
+        assert_eq!(replay(&DISCHARGING[..4]).state(), ChargeState::Unknown);
+        assert_eq!(replay(&DISCHARGING[..4]).minutes_remaining(), None);
+    }
+
+    #[test]
+    fn detects_charging() {
+        let estimator = replay(&CHARGING);
+        assert_eq!(estimator.state(), ChargeState::Charging);
+        assert!(estimator.minutes_remaining().is_some());
+
+        let full = replay(&[4195; 20]);
+        assert_eq!(full.state(), ChargeState::Full);
+        assert_eq!(full.minutes_remaining(), None);
+    }
+
+    #[test]
+    fn drops_samples_outside_the_window() {
+        let mut estimator = BatteryEstimator::new(60);
+        for secs in 0..20 {
+            estimator.push(secs * 10, 3800);
+        }
+        assert_eq!(estimator.samples.len(), 7);
+        assert_eq!(estimator.slope(), None);
+    }
+
+    #[test]
+    fn formats_runtime() {
+        let mut out = String::new();
+        write_runtime(&mut out, 185);
+        assert_eq!(out, "3h05");
+        out.clear();
+        write_runtime(&mut out, 45);
+        assert_eq!(out, "45m");
+    }
+}
@@ -0,0 +1,290 @@
+//! Time series shown as small charts, one sample per column of the panel.
+//!
+//! Every series keeps its latest samples in a fixed ring buffer and is scaled to the range of what it holds, so even
+//! small changes fill the few rows the panel has.
+
+use alloc::{string::String, vec::Vec};
+
+use serde::Deserialize;
+pub use serde_json_core::de::Error as JsonError;
+
+/// One per column of the panel.
+pub const MAX_SAMPLES: usize = 32;
+/// The built-in series count towards this as well.
+pub const MAX_SERIES: usize = 8;
+
+/// Series pushed over MQTT go from blue for their lowest to red for their highest value.
+const PUSHED_LOW_COLOR: [u8; 3] = [0, 96, 255];
+const PUSHED_HIGH_COLOR: [u8; 3] = [255, 32, 0];
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ChartStyle {
+    #[default]
+    Bars,
+    Line,
+}
+
+impl ChartStyle {
+    pub fn name(self) -> &'static str {
+        match self {
+            ChartStyle::Bars => "bars",
+            ChartStyle::Line => "line",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        [ChartStyle::Bars, ChartStyle::Line].into_iter().find(|style| style.name() == name)
+    }
+}
+
+/// The latest samples of a series, the oldest one is overwritten once it is full.
+#[derive(Debug, Clone, Copy)]
+pub struct History {
+    samples: [f32; MAX_SAMPLES],
+    start: usize,
+    len: usize,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        History { samples: [0.0; MAX_SAMPLES], start: 0, len: 0 }
+    }
+}
+
+impl History {
+    /// Values that are not finite are dropped, they would break the scale.
+    pub fn push(&mut self, value: f32) {
+        if !value.is_finite() {
+            return;
+        }
+        if self.len < MAX_SAMPLES {
+            self.samples[(self.start + self.len) % MAX_SAMPLES] = value;
+            self.len += 1;
+        } else {
+            self.samples[self.start] = value;
+            self.start = (self.start + 1) % MAX_SAMPLES;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Oldest sample first.
+    pub fn iter(&self) -> impl Iterator<Item = f32> + '_ {
+        (0..self.len).map(|index| self.samples[(self.start + index) % MAX_SAMPLES])
+    }
+
+    pub fn last(&self) -> Option<f32> {
+        self.iter().last()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Series {
+    pub name: String,
+    pub style: ChartStyle,
+    pub low_color: [u8; 3],
+    pub high_color: [u8; 3],
+    pub history: History,
+}
+
+impl Series {
+    pub fn new(name: &str, style: ChartStyle, low_color: [u8; 3], high_color: [u8; 3]) -> Self {
+        Series { name: name.into(), style, low_color, high_color, history: History::default() }
+    }
+
+    /// Color at `fraction` of the chart height.
+    pub fn color(&self, fraction: f32) -> [u8; 3] {
+        gradient(self.low_color, self.high_color, fraction)
+    }
+}
+
+/// Maps sample values onto the rows of a chart.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Scale {
+    pub min: f32,
+    pub max: f32,
+}
+
+impl Scale {
+    /// Spans the lowest to the highest sample, `None` without samples.
+    pub fn fit(history: &History) -> Option<Scale> {
+        history.iter().fold(None, |scale, value| match scale {
+            None => Some(Scale { min: value, max: value }),
+            Some(Scale { min, max }) => Some(Scale { min: min.min(value), max: max.max(value) }),
+        })
+    }
+
+    /// Position of `value` between the minimum (0) and the maximum (1), a flat series sits in the middle.
+    pub fn fraction(&self, value: f32) -> f32 {
+        let span = self.max - self.min;
+        if span <= f32::EPSILON * self.max.abs().max(1.0) {
+            return 0.5;
+        }
+        ((value - self.min) / span).clamp(0.0, 1.0)
+    }
+
+    /// Rows a bar for `value` fills, at least one so the minimum still shows. A line runs through the top one.
+    pub fn bar_height(&self, value: f32, rows: u32) -> u32 {
+        1 + libm::roundf(self.fraction(value) * rows.saturating_sub(1) as f32) as u32
+    }
+}
+
+/// Blends from `low` at 0 to `high` at 1.
+pub fn gradient(low: [u8; 3], high: [u8; 3], fraction: f32) -> [u8; 3] {
+    let fraction = fraction.clamp(0.0, 1.0);
+    core::array::from_fn(|channel| {
+        let (low, high) = (low[channel] as f32, high[channel] as f32);
+        libm::roundf(low + (high - low) * fraction) as u8
+    })
+}
+
+#[derive(Debug)]
+pub enum ChartError {
+    InvalidJson(JsonError),
+    EmptyName,
+    UnknownStyle,
+    TooManySeries,
+}
+
+/// A sample pushed over MQTT.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SampleCommand<'a> {
+    pub name: &'a str,
+    pub value: f32,
+    /// Only changes the style when given.
+    pub style: Option<ChartStyle>,
+}
+
+#[derive(Deserialize)]
+struct RawSampleCommand<'a> {
+    name: &'a str,
+    value: f32,
+    style: Option<&'a str>,
+}
+
+/// Parses `{"name": "power", "value": 1250.5, "style": "line"}`, the style is optional.
+pub fn parse_sample(payload: &str) -> Result<SampleCommand<'_>, ChartError> {
+    let (command, _) = serde_json_core::from_str::<RawSampleCommand>(payload).map_err(ChartError::InvalidJson)?;
+    if command.name.is_empty() {
+        return Err(ChartError::EmptyName);
+    }
+    let style = match command.style {
+        Some(style) => Some(ChartStyle::from_name(style).ok_or(ChartError::UnknownStyle)?),
+        None => None,
+    };
+    Ok(SampleCommand { name: command.name, value: command.value, style })
+}
+
+/// Adds the sample of a command like `{"name": "power", "value": 1250.5}` to its series, creating the series on
+/// its first sample.
+pub fn apply_sample(series: &mut Vec<Series>, payload: &str) -> Result<(), ChartError> {
+    let sample = parse_sample(payload)?;
+    let index = match series.iter().position(|series| series.name == sample.name) {
+        Some(index) => index,
+        None if series.len() >= MAX_SERIES => return Err(ChartError::TooManySeries),
+        None => {
+            let style = sample.style.unwrap_or_default();
+            series.push(Series::new(sample.name, style, PUSHED_LOW_COLOR, PUSHED_HIGH_COLOR));
+            series.len() - 1
+        }
+    };
+    let series = &mut series[index];
+    if let Some(style) = sample.style {
+        series.style = style;
+    }
+    series.history.push(sample.value);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_latest_samples() {
+        let mut history = History::default();
+        assert!(history.is_empty());
+        assert_eq!(Scale::fit(&history), None);
+
+        for value in 0..5 {
+            history.push(value as f32);
+        }
+        history.push(f32::NAN);
+        assert_eq!(history.iter().collect::<Vec<_>>(), [0.0, 1.0, 2.0, 3.0, 4.0]);
+
+        for value in 5..40 {
+            history.push(value as f32);
+        }
+        assert_eq!(history.len(), MAX_SAMPLES);
+        assert_eq!(history.iter().next(), Some(8.0));
+        assert_eq!(history.last(), Some(39.0));
+        assert!(history.iter().zip(history.iter().skip(1)).all(|(a, b)| b == a + 1.0));
+    }
+
+    #[test]
+    fn scales_to_the_samples() {
+        let mut history = History::default();
+        for value in [20.5, 22.0, 21.0, 23.5] {
+            history.push(value);
+        }
+        let scale = Scale::fit(&history).unwrap();
+        assert_eq!(scale, Scale { min: 20.5, max: 23.5 });
+        assert_eq!(scale.bar_height(20.5, 8), 1);
+        assert_eq!(scale.bar_height(23.5, 8), 8);
+        assert_eq!(scale.bar_height(22.0, 8), 5);
+        // values from outside the fitted range stay on the chart
+        assert_eq!(scale.bar_height(100.0, 8), 8);
+        assert_eq!(scale.bar_height(-100.0, 8), 1);
+
+        let flat = Scale { min: 1250.0, max: 1250.0 };
+        assert_eq!(flat.fraction(1250.0), 0.5);
+        assert_eq!(flat.bar_height(1250.0, 8), 5);
+    }
+
+    #[test]
+    fn blends_colors_and_parses_samples() {
+        assert_eq!(gradient([0, 0, 255], [255, 0, 0], 0.0), [0, 0, 255]);
+        assert_eq!(gradient([0, 0, 255], [255, 0, 0], 0.5), [128, 0, 128]);
+        assert_eq!(gradient([0, 0, 255], [255, 0, 0], 2.0), [255, 0, 0]);
+
+        let sample = parse_sample(r#"{"name": "power", "value": 1250.5, "style": "line"}"#).unwrap();
+        assert_eq!(sample, SampleCommand { name: "power", value: 1250.5, style: Some(ChartStyle::Line) });
+        assert_eq!(parse_sample(r#"{"name": "power", "value": 3}"#).unwrap().style, None);
+        assert!(matches!(parse_sample(r#"{"name": "", "value": 3}"#), Err(ChartError::EmptyName)));
+        assert!(matches!(
+            parse_sample(r#"{"name": "power", "value": 3, "style": "pie"}"#),
+            Err(ChartError::UnknownStyle)
+        ));
+        assert!(matches!(parse_sample(r#"{"name": "power"}"#), Err(ChartError::InvalidJson(_))));
+    }
+
+    #[test]
+    fn collects_pushed_samples_per_series() {
+        let mut series = alloc::vec![Series::new("battery", ChartStyle::Line, [255, 0, 0], [0, 255, 0])];
+        apply_sample(&mut series, r#"{"name": "battery", "value": 80}"#).unwrap();
+        apply_sample(&mut series, r#"{"name": "power", "value": 1200}"#).unwrap();
+        apply_sample(&mut series, r#"{"name": "power", "value": 900, "style": "line"}"#).unwrap();
+        assert_eq!(series.len(), 2);
+        assert_eq!(series[0].history.last(), Some(80.0));
+        assert_eq!(series[0].style, ChartStyle::Line);
+        assert_eq!(series[1].history.iter().collect::<Vec<_>>(), [1200.0, 900.0]);
+        assert_eq!(series[1].style, ChartStyle::Line);
+        assert_eq!(series[1].color(0.0), PUSHED_LOW_COLOR);
+
+        for index in series.len()..MAX_SERIES {
+            let payload = alloc::format!(r#"{{"name": "series {index}", "value": 1}}"#);
+            apply_sample(&mut series, &payload).unwrap();
+        }
+        assert!(matches!(
+            apply_sample(&mut series, r#"{"name": "one more", "value": 1}"#),
+            Err(ChartError::TooManySeries)
+        ));
+        apply_sample(&mut series, r#"{"name": "power", "value": 1000}"#).unwrap();
+    }
+}
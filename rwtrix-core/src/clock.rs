@@ -0,0 +1,200 @@
+//! Settings of the clock face and the formatting that depends on them.
+
+use alloc::string::String;
+use core::fmt::Write as _;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HourFormat {
+    #[default]
+    H24,
+    /// Hours without leading zero followed by an `A` or `P` marker.
+    H12,
+}
+
+impl HourFormat {
+    pub const ALL: [HourFormat; 2] = [HourFormat::H24, HourFormat::H12];
+    pub const NAMES: [&'static str; 2] = ["24h", "12h"];
+
+    pub fn from_index(index: usize) -> Option<Self> {
+        Self::ALL.get(index).copied()
+    }
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WeekStart {
+    #[default]
+    Monday,
+    Sunday,
+}
+
+impl WeekStart {
+    pub const ALL: [WeekStart; 2] = [WeekStart::Monday, WeekStart::Sunday];
+    pub const NAMES: [&'static str; 2] = ["monday", "sunday"];
+
+    pub fn from_index(index: usize) -> Option<Self> {
+        Self::ALL.get(index).copied()
+    }
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ClockFace {
+    /// Time next to a calendar icon with the weekday bar below.
+    #[default]
+    Classic,
+    /// Large 5x8 digits over the whole panel.
+    BigDigits,
+    /// Binary coded decimal columns.
+    Binary,
+    /// The time in words, scrolling.
+    Fuzzy,
+}
+
+impl ClockFace {
+    pub const ALL: [ClockFace; 4] = [ClockFace::Classic, ClockFace::BigDigits, ClockFace::Binary, ClockFace::Fuzzy];
+    pub const NAMES: [&'static str; 4] = ["classic", "big", "binary", "fuzzy"];
+
+    pub fn from_index(index: usize) -> Option<Self> {
+        Self::ALL.get(index).copied()
+    }
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// How the colons between hours, minutes and seconds tick.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BlinkStyle {
+    /// Alternates between white and gray every second.
+    #[default]
+    Dim,
+    /// Shown every other second.
+    Blink,
+    /// Always shown in the digit color.
+    Solid,
+}
+
+impl BlinkStyle {
+    pub const ALL: [BlinkStyle; 3] = [BlinkStyle::Dim, BlinkStyle::Blink, BlinkStyle::Solid];
+    pub const NAMES: [&'static str; 3] = ["dim", "blink", "solid"];
+
+    pub fn from_index(index: usize) -> Option<Self> {
+        Self::ALL.get(index).copied()
+    }
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClockSettings {
+    pub hour_format: HourFormat,
+    pub show_seconds: bool,
+    pub week_start: WeekStart,
+    pub digit_color: [u8; 3],
+    /// Header of the calendar icon, the body stays white.
+    pub calendar_color: [u8; 3],
+    /// Marker of the current day in the weekday bar.
+    pub weekday_color: [u8; 3],
+    pub blink: BlinkStyle,
+    /// Added after the other fields were first stored, new fields go last to keep older records readable.
+    pub face: ClockFace,
+}
+
+impl Default for ClockSettings {
+    fn default() -> Self {
+        ClockSettings {
+            hour_format: HourFormat::H24,
+            show_seconds: false,
+            week_start: WeekStart::Monday,
+            digit_color: [255, 255, 0],
+            calendar_color: [255, 0, 0],
+            weekday_color: [255, 255, 255],
+            blink: BlinkStyle::Dim,
+            face: ClockFace::Classic,
+        }
+    }
+}
+
+impl ClockSettings {
+    /// Hour as shown on the panel, from 1 to 12 in the 12 hour format.
+    pub fn display_hour(&self, hour: u8) -> u8 {
+        match self.hour_format {
+            HourFormat::H24 => hour,
+            HourFormat::H12 => (hour + 11) % 12 + 1,
+        }
+    }
+
+    /// Formats the time like `07:05`, `07:05:09`, `7:05P` or `7:05:09P`.
+    pub fn write_time(&self, out: &mut String, hour: u8, minute: u8, second: u8) {
+        match self.hour_format {
+            HourFormat::H24 => write!(out, "{:02}:{:02}", hour, minute).ok(),
+            HourFormat::H12 => write!(out, "{}:{:02}", self.display_hour(hour), minute).ok(),
+        };
+        if self.show_seconds {
+            write!(out, ":{:02}", second).ok();
+        }
+        if self.hour_format == HourFormat::H12 {
+            out.push(if hour < 12 { 'A' } else { 'P' });
+        }
+    }
+
+    /// Position of a weekday, counted from Monday, in the weekday bar.
+    pub fn weekday_slot(&self, weekday_from_monday: u8) -> u8 {
+        match self.week_start {
+            WeekStart::Monday => weekday_from_monday,
+            WeekStart::Sunday => (weekday_from_monday + 1) % 7,
+        }
+    }
+}
+
+/// Formats a color as `#RRGGBB`.
+pub fn write_color(out: &mut String, color: [u8; 3]) {
+    write!(out, "#{:02X}{:02X}{:02X}", color[0], color[1], color[2]).ok();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(settings: &ClockSettings, hour: u8, minute: u8, second: u8) -> String {
+        let mut out = String::new();
+        settings.write_time(&mut out, hour, minute, second);
+        out
+    }
+
+    #[test]
+    fn formats_time() {
+        let mut settings = ClockSettings::default();
+        assert_eq!(time(&settings, 7, 5, 9), "07:05");
+        settings.show_seconds = true;
+        assert_eq!(time(&settings, 23, 59, 9), "23:59:09");
+
+        settings.hour_format = HourFormat::H12;
+        assert_eq!(time(&settings, 0, 30, 0), "12:30:00A");
+        settings.show_seconds = false;
+        assert_eq!(time(&settings, 12, 0, 0), "12:00P");
+        assert_eq!(time(&settings, 19, 45, 0), "7:45P");
+    }
+
+    #[test]
+    fn orders_weekdays() {
+        let mut settings = ClockSettings::default();
+        assert_eq!(settings.weekday_slot(0), 0);
+        assert_eq!(settings.weekday_slot(6), 6);
+        settings.week_start = WeekStart::Sunday;
+        assert_eq!(settings.weekday_slot(6), 0);
+        assert_eq!(settings.weekday_slot(0), 1);
+    }
+}
@@ -0,0 +1,208 @@
+//! Alternative renderings of the time page that do not need the text font of the firmware.
+//!
+//! Everything draws into any [`DrawTarget`] of [`Rgb888`] pixels, 32x8 like the panel.
+
+use alloc::string::String;
+
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::*,
+    primitives::{PrimitiveStyle, Rectangle},
+};
+
+use crate::clock::{ClockSettings, HourFormat};
+
+const PANEL_WIDTH: i32 = 32;
+
+/// 5x8 digits, one byte per row with the leftmost pixel in bit 4.
+const BIG_DIGITS: [[u8; 8]; 10] = [
+    [0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110],
+    [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+    [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b11111],
+    [0b11110, 0b00001, 0b00001, 0b01110, 0b00001, 0b00001, 0b00001, 0b11110],
+    [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010, 0b00010],
+    [0b11111, 0b10000, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110],
+    [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b10001, 0b01110],
+    [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000, 0b01000],
+    [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b10001, 0b01110],
+    [0b01110, 0b10001, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100],
+];
+const BIG_DIGIT_WIDTH: i32 = 5;
+const BIG_DIGIT_GAP: i32 = 2;
+/// Two digits, the colon and the two digits after it.
+const BIG_TIME_WIDTH: i32 = 4 * BIG_DIGIT_WIDTH + 5 * BIG_DIGIT_GAP - 1;
+
+fn digit_color(settings: &ClockSettings) -> Rgb888 {
+    let [r, g, b] = settings.digit_color;
+    Rgb888::new(r, g, b)
+}
+
+fn draw_big_digit<D: DrawTarget<Color = Rgb888>>(target: &mut D, digit: u8, left: i32, color: Rgb888) {
+    let rows = BIG_DIGITS[digit as usize % 10];
+    let pixels = rows.iter().enumerate().flat_map(|(y, row)| {
+        (0..BIG_DIGIT_WIDTH)
+            .filter(move |x| row & (1 << (BIG_DIGIT_WIDTH - 1 - x)) != 0)
+            .map(move |x| Pixel(Point::new(left + x, y as i32), color))
+    });
+    target.draw_iter(pixels).ok();
+}
+
+/// `HH:MM` in 5x8 digits over the whole panel. The leading zero of the hour is left out in the 12 hour format.
+pub fn draw_big_digits<D: DrawTarget<Color = Rgb888>>(
+    target: &mut D,
+    settings: &ClockSettings,
+    hour: u8,
+    minute: u8,
+    colon_visible: bool,
+) {
+    let color = digit_color(settings);
+    let hour = settings.display_hour(hour);
+    let mut x = (PANEL_WIDTH - BIG_TIME_WIDTH) / 2;
+    let step = BIG_DIGIT_WIDTH + BIG_DIGIT_GAP;
+
+    if hour >= 10 || settings.hour_format == HourFormat::H24 {
+        draw_big_digit(target, hour / 10, x, color);
+    }
+    x += step;
+    draw_big_digit(target, hour % 10, x, color);
+    x += step;
+    if colon_visible {
+        target.draw_iter([2, 5].map(|y| Pixel(Point::new(x, y), color))).ok();
+    }
+    x += 1 + BIG_DIGIT_GAP;
+    draw_big_digit(target, minute / 10, x, color);
+    draw_big_digit(target, minute % 10, x + step, color);
+}
+
+/// Binary coded decimal columns for the tens and units of the hours, minutes and, if enabled, seconds. Bits are drawn
+/// bottom up every other row, only the bits a digit can use get a dot.
+pub fn draw_binary<D: DrawTarget<Color = Rgb888>>(
+    target: &mut D,
+    settings: &ClockSettings,
+    hour: u8,
+    minute: u8,
+    second: u8,
+    off_color: Rgb888,
+) {
+    let on_color = digit_color(settings);
+    let hour = settings.display_hour(hour);
+    let group_count = if settings.show_seconds { 3 } else { 2 };
+
+    // each group is two 3 pixel wide columns with a gap of one, groups are three pixels apart
+    let mut x = (PANEL_WIDTH - (group_count as i32 * 10 - 3)) / 2;
+    for (value, tens_bits) in [(hour, 2), (minute, 3), (second, 3)].into_iter().take(group_count) {
+        for (digit, bits) in [(value / 10, tens_bits), (value % 10, 4)] {
+            for bit in 0..bits {
+                let color = if digit & (1 << bit) != 0 { on_color } else { off_color };
+                Rectangle::new(Point::new(x, 7 - 2 * bit), Size::new(3, 1))
+                    .into_styled(PrimitiveStyle::with_fill(color))
+                    .draw(target)
+                    .ok();
+            }
+            x += 4;
+        }
+        x += 2;
+    }
+}
+
+const HOUR_WORDS: [&str; 12] =
+    ["TWELVE", "ONE", "TWO", "THREE", "FOUR", "FIVE", "SIX", "SEVEN", "EIGHT", "NINE", "TEN", "ELEVEN"];
+
+/// The time in words rounded to five minutes, like `QUARTER PAST TEN` or `TWENTY TO THREE`.
+pub fn fuzzy_time(hour: u8, minute: u8) -> String {
+    let steps = (minute as usize + 2) / 5;
+    let hour = if steps > 6 { hour as usize + 1 } else { hour as usize };
+    let hour = HOUR_WORDS[hour % 12];
+
+    let mut text = String::new();
+    let prefix = match steps {
+        0 | 12 => {
+            text.push_str(hour);
+            text.push_str(" O'CLOCK");
+            return text;
+        }
+        1 | 11 => "FIVE",
+        2 | 10 => "TEN",
+        3 | 9 => "QUARTER",
+        4 | 8 => "TWENTY",
+        5 | 7 => "TWENTY FIVE",
+        _ => "HALF",
+    };
+    text.push_str(prefix);
+    text.push_str(if steps > 6 { " TO " } else { " PAST " });
+    text.push_str(hour);
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics::mock_display::MockDisplay;
+
+    use super::*;
+
+    fn settings() -> ClockSettings {
+        ClockSettings { digit_color: [255, 255, 255], ..ClockSettings::default() }
+    }
+
+    fn canvas() -> MockDisplay<Rgb888> {
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+        display
+    }
+
+    #[test]
+    fn draws_big_digits() {
+        let mut display = canvas();
+        draw_big_digits(&mut display, &settings(), 9, 41, true);
+        display.assert_pattern(&[
+            "  WWW    WWW         W     W    ",
+            " W   W  W   W       WW    WW    ",
+            " W   W  W   W  W   W W     W    ",
+            " W   W  W   W     W  W     W    ",
+            " W   W   WWWW     WWWWW    W    ",
+            " W   W      W  W     W     W    ",
+            " W   W     W         W     W    ",
+            "  WWW    WW          W    WWW   ",
+        ]);
+
+        let mut display = canvas();
+        let settings = ClockSettings { hour_format: HourFormat::H12, ..settings() };
+        draw_big_digits(&mut display, &settings, 21, 0, false);
+        display.assert_pattern(&[
+            "         WWW       WWW    WWW   ",
+            "        W   W     W   W  W   W  ",
+            "        W   W     W   W  W   W  ",
+            "        W   W     W   W  W   W  ",
+            "         WWWW     W   W  W   W  ",
+            "            W     W   W  W   W  ",
+            "           W      W   W  W   W  ",
+            "         WW        WWW    WWW   ",
+        ]);
+    }
+
+    #[test]
+    fn draws_binary() {
+        let mut display = canvas();
+        draw_binary(&mut display, &settings(), 19, 38, 0, Rgb888::BLUE);
+        display.assert_pattern(&[
+            "                                ",
+            "           WWW       WWW        ",
+            "                                ",
+            "           BBB   BBB BBB        ",
+            "                                ",
+            "       BBB BBB   WWW BBB        ",
+            "                                ",
+            "       WWW WWW   WWW BBB        ",
+        ]);
+    }
+
+    #[test]
+    fn words_the_time() {
+        assert_eq!(fuzzy_time(10, 0), "TEN O'CLOCK");
+        assert_eq!(fuzzy_time(10, 13), "QUARTER PAST TEN");
+        assert_eq!(fuzzy_time(14, 31), "HALF PAST TWO");
+        assert_eq!(fuzzy_time(14, 33), "TWENTY FIVE TO THREE");
+        assert_eq!(fuzzy_time(23, 58), "TWELVE O'CLOCK");
+        assert_eq!(fuzzy_time(0, 5), "FIVE PAST TWELVE");
+    }
+}
@@ -0,0 +1,251 @@
+//! Named target dates counted down to, or up from.
+//!
+//! Dates are kept as seconds since 1970-01-01 00:00 in local wall time, the same clock the RTC of the firmware
+//! keeps, so no time zone handling is needed here.
+
+use alloc::{string::String, vec::Vec};
+use core::fmt::Write as _;
+
+use serde::{Deserialize, Serialize};
+pub use serde_json_core::de::Error as JsonError;
+
+pub const MAX_COUNTDOWNS: usize = 8;
+
+const SECS_PER_DAY: i64 = 24 * 60 * 60;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CountdownMode {
+    /// Time left until the date.
+    #[default]
+    Until,
+    /// Time elapsed since the date.
+    Since,
+}
+
+impl CountdownMode {
+    pub fn name(self) -> &'static str {
+        match self {
+            CountdownMode::Until => "until",
+            CountdownMode::Since => "since",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        [CountdownMode::Until, CountdownMode::Since].into_iter().find(|mode| mode.name() == name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Countdown {
+    pub name: String,
+    /// Local wall time in seconds since the epoch.
+    pub target: i64,
+    pub mode: CountdownMode,
+}
+
+impl Countdown {
+    /// Remaining or elapsed time like `123d 4h`, `5h 30m` or `12m 5s`. Dates counted down to that passed show
+    /// `done`, dates counted up from that are still ahead show `soon`.
+    pub fn write_value(&self, out: &mut String, now: i64) {
+        let secs = match self.mode {
+            CountdownMode::Until => self.target - now,
+            CountdownMode::Since => now - self.target,
+        };
+        match (secs, self.mode) {
+            (..=0, CountdownMode::Until) => out.push_str("done"),
+            (..0, CountdownMode::Since) => out.push_str("soon"),
+            (secs, _) => write_duration(out, secs),
+        }
+    }
+}
+
+pub fn write_duration(out: &mut String, secs: i64) {
+    let (days, hours, minutes) = (secs / SECS_PER_DAY, secs / 3600 % 24, secs / 60 % 60);
+    if days > 0 {
+        write!(out, "{}d {}h", days, hours).ok();
+    } else if hours > 0 {
+        write!(out, "{}h {}m", hours, minutes).ok();
+    } else {
+        write!(out, "{}m {}s", minutes, secs % 60).ok();
+    }
+}
+
+#[derive(Debug)]
+pub enum CountdownError {
+    InvalidJson(JsonError),
+    EmptyName,
+    InvalidDate,
+    UnknownMode,
+    TooManyCountdowns,
+}
+
+/// Countdown command as pushed over MQTT or HTTP, a missing or empty `date` deletes the countdown.
+#[derive(Deserialize)]
+struct CountdownCommand<'a> {
+    name: &'a str,
+    date: Option<&'a str>,
+    mode: Option<&'a str>,
+}
+
+/// Adds, updates or removes a countdown according to a JSON command like
+/// `{"name": "Release", "date": "2026-12-24 18:00", "mode": "until"}`.
+pub fn apply_command(countdowns: &mut Vec<Countdown>, payload: &str) -> Result<(), CountdownError> {
+    let (command, _) = serde_json_core::from_str::<CountdownCommand>(payload).map_err(CountdownError::InvalidJson)?;
+    if command.name.is_empty() {
+        return Err(CountdownError::EmptyName);
+    }
+    let position = countdowns.iter().position(|countdown| countdown.name == command.name);
+
+    let date = match command.date {
+        Some(date) if !date.is_empty() => date,
+        _ => {
+            if let Some(position) = position {
+                countdowns.remove(position);
+            }
+            return Ok(());
+        }
+    };
+    let target = parse_datetime(date).ok_or(CountdownError::InvalidDate)?;
+    let mode = match command.mode {
+        Some(mode) => CountdownMode::from_name(mode).ok_or(CountdownError::UnknownMode)?,
+        None => position.map(|position| countdowns[position].mode).unwrap_or_default(),
+    };
+
+    let countdown = Countdown { name: command.name.into(), target, mode };
+    match position {
+        Some(position) => countdowns[position] = countdown,
+        None if countdowns.len() >= MAX_COUNTDOWNS => return Err(CountdownError::TooManyCountdowns),
+        None => countdowns.push(countdown),
+    }
+    Ok(())
+}
+
+/// Parses `YYYY-MM-DD` optionally followed by ` HH:MM` or `THH:MM`, the time defaults to midnight.
+pub fn parse_datetime(value: &str) -> Option<i64> {
+    let value = value.trim();
+    let (date, time) = match value.split_once([' ', 'T']) {
+        Some((date, time)) => (date, Some(time)),
+        None => (value, None),
+    };
+
+    let mut parts = date.splitn(3, '-');
+    let year = parts.next()?.parse::<i64>().ok()?;
+    let month = parts.next()?.parse::<u32>().ok()?;
+    let day = parts.next()?.parse::<u32>().ok()?;
+    let days = days_from_civil(year, month, day);
+    // rejects the days a month does not have
+    if civil_from_days(days) != (year, month, day) {
+        return None;
+    }
+
+    let (hour, minute) = match time {
+        Some(time) => {
+            let (hour, minute) = time.split_once(':')?;
+            (
+                hour.parse::<i64>().ok().filter(|hour| *hour < 24)?,
+                minute.parse::<i64>().ok().filter(|minute| *minute < 60)?,
+            )
+        }
+        None => (0, 0),
+    };
+    Some(days * SECS_PER_DAY + hour * 3600 + minute * 60)
+}
+
+/// Formats as `YYYY-MM-DD HH:MM`.
+pub fn write_datetime(out: &mut String, secs: i64) {
+    let (year, month, day) = civil_from_days(secs.div_euclid(SECS_PER_DAY));
+    let time = secs.rem_euclid(SECS_PER_DAY);
+    write!(out, "{:04}-{:02}-{:02} {:02}:{:02}", year, month, day, time / 3600, time / 60 % 60).ok();
+}
+
+/// Days since 1970-01-01 of a proleptic Gregorian date, from Howard Hinnant's date algorithms.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return i64::MIN;
+    }
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month = month as i64;
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days.saturating_add(719468);
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 } as u32;
+    let year = year_of_era + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(countdown: &Countdown, now: i64) -> String {
+        let mut out = String::new();
+        countdown.write_value(&mut out, now);
+        out
+    }
+
+    #[test]
+    fn parses_dates() {
+        assert_eq!(parse_datetime("1970-01-01"), Some(0));
+        assert_eq!(parse_datetime("2000-03-01 12:30"), Some(951_913_800));
+        assert_eq!(parse_datetime("2024-02-29T00:01"), Some(1_709_164_860));
+        assert_eq!(parse_datetime("2023-02-29"), None);
+        assert_eq!(parse_datetime("2024-13-01"), None);
+        assert_eq!(parse_datetime("2024-01-01 24:00"), None);
+        assert_eq!(parse_datetime("tomorrow"), None);
+
+        let mut out = String::new();
+        write_datetime(&mut out, 1_709_164_860);
+        assert_eq!(out, "2024-02-29 00:01");
+    }
+
+    #[test]
+    fn formats_remaining_and_elapsed_time() {
+        let release = Countdown { name: "Release".into(), target: 10 * SECS_PER_DAY, mode: CountdownMode::Until };
+        assert_eq!(value(&release, 0), "10d 0h");
+        assert_eq!(value(&release, 10 * SECS_PER_DAY - 5 * 3600 - 90), "5h 1m");
+        assert_eq!(value(&release, 10 * SECS_PER_DAY - 65), "1m 5s");
+        assert_eq!(value(&release, 10 * SECS_PER_DAY), "done");
+
+        let since = Countdown { mode: CountdownMode::Since, ..release };
+        assert_eq!(value(&since, 0), "soon");
+        assert_eq!(value(&since, 12 * SECS_PER_DAY + 7200), "2d 2h");
+    }
+
+    #[test]
+    fn applies_commands() {
+        let mut countdowns = Vec::new();
+        apply_command(&mut countdowns, r#"{"name": "Holiday", "date": "2026-07-01"}"#).unwrap();
+        apply_command(&mut countdowns, r#"{"name": "Birthday", "date": "1990-05-17", "mode": "since"}"#).unwrap();
+        assert_eq!(countdowns.len(), 2);
+        assert_eq!(countdowns[1].mode, CountdownMode::Since);
+
+        apply_command(&mut countdowns, r#"{"name": "Birthday", "date": "1990-05-18"}"#).unwrap();
+        assert_eq!(countdowns[1].mode, CountdownMode::Since);
+        assert_eq!(countdowns[1].target, parse_datetime("1990-05-18").unwrap());
+
+        assert!(matches!(
+            apply_command(&mut countdowns, r#"{"name": "Holiday", "date": "2026-07-32"}"#),
+            Err(CountdownError::InvalidDate)
+        ));
+        assert!(matches!(
+            apply_command(&mut countdowns, r#"{"name": "Holiday", "date": "2026-07-01", "mode": "ago"}"#),
+            Err(CountdownError::UnknownMode)
+        ));
+
+        apply_command(&mut countdowns, r#"{"name": "Holiday"}"#).unwrap();
+        assert_eq!(countdowns.len(), 1);
+        assert_eq!(countdowns[0].name, "Birthday");
+    }
+}
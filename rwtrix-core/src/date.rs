@@ -0,0 +1,208 @@
+//! Date formats of the date page with month and weekday names per language.
+//!
+//! Names are upper case since the panel font is easier to read that way, Cyrillic only has upper case glyphs anyway.
+
+use alloc::string::String;
+use core::fmt::Write as _;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+    Polish,
+    Czech,
+    Russian,
+    Ukrainian,
+}
+
+impl Language {
+    pub const ALL: [Language; 6] = [
+        Language::English,
+        Language::German,
+        Language::Polish,
+        Language::Czech,
+        Language::Russian,
+        Language::Ukrainian,
+    ];
+    pub const NAMES: [&'static str; 6] = ["en", "de", "pl", "cs", "ru", "uk"];
+
+    pub fn from_index(index: usize) -> Option<Self> {
+        Self::ALL.get(index).copied()
+    }
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Weekday abbreviations starting on Monday.
+    pub fn weekdays(self) -> &'static [&'static str; 7] {
+        match self {
+            Language::English => &["MON", "TUE", "WED", "THU", "FRI", "SAT", "SUN"],
+            Language::German => &["MO", "DI", "MI", "DO", "FR", "SA", "SO"],
+            Language::Polish => &["PON", "WTO", "ŚRO", "CZW", "PIĄ", "SOB", "NIE"],
+            Language::Czech => &["PO", "ÚT", "ST", "ČT", "PÁ", "SO", "NE"],
+            Language::Russian => &["ПН", "ВТ", "СР", "ЧТ", "ПТ", "СБ", "ВС"],
+            Language::Ukrainian => &["ПН", "ВТ", "СР", "ЧТ", "ПТ", "СБ", "НД"],
+        }
+    }
+
+    pub fn months(self) -> &'static [&'static str; 12] {
+        match self {
+            Language::English => &["JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC"],
+            Language::German => &["JAN", "FEB", "MÄR", "APR", "MAI", "JUN", "JUL", "AUG", "SEP", "OKT", "NOV", "DEZ"],
+            Language::Polish => &["STY", "LUT", "MAR", "KWI", "MAJ", "CZE", "LIP", "SIE", "WRZ", "PAŹ", "LIS", "GRU"],
+            Language::Czech => &["LED", "ÚNO", "BŘE", "DUB", "KVĚ", "ČVN", "ČVC", "SRP", "ZÁŘ", "ŘÍJ", "LIS", "PRO"],
+            Language::Russian => &["ЯНВ", "ФЕВ", "МАР", "АПР", "МАЙ", "ИЮН", "ИЮЛ", "АВГ", "СЕН", "ОКТ", "НОЯ", "ДЕК"],
+            Language::Ukrainian => {
+                &["СІЧ", "ЛЮТ", "БЕР", "КВІ", "ТРА", "ЧЕР", "ЛИП", "СЕР", "ВЕР", "ЖОВ", "ЛИС", "ГРУ"]
+            }
+        }
+    }
+}
+
+/// Order of the day, month and year.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DateOrder {
+    #[default]
+    DayMonthYear,
+    MonthDayYear,
+    YearMonthDay,
+}
+
+impl DateOrder {
+    pub const ALL: [DateOrder; 3] = [DateOrder::DayMonthYear, DateOrder::MonthDayYear, DateOrder::YearMonthDay];
+    pub const NAMES: [&'static str; 3] = ["dmy", "mdy", "ymd"];
+
+    pub fn from_index(index: usize) -> Option<Self> {
+        Self::ALL.get(index).copied()
+    }
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Separator {
+    #[default]
+    Dot,
+    Slash,
+    Dash,
+    Space,
+}
+
+impl Separator {
+    pub const ALL: [Separator; 4] = [Separator::Dot, Separator::Slash, Separator::Dash, Separator::Space];
+    pub const NAMES: [&'static str; 4] = [".", "/", "-", "space"];
+
+    pub fn from_index(index: usize) -> Option<Self> {
+        Self::ALL.get(index).copied()
+    }
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn as_char(self) -> char {
+        match self {
+            Separator::Dot => '.',
+            Separator::Slash => '/',
+            Separator::Dash => '-',
+            Separator::Space => ' ',
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DateSettings {
+    pub language: Language,
+    pub order: DateOrder,
+    pub separator: Separator,
+    /// Month name instead of the month number, the year is left out then.
+    pub month_names: bool,
+    /// Weekday name in front of the date.
+    pub weekday: bool,
+}
+
+impl DateSettings {
+    /// Formats like `13.10.25`, `10/13/25`, `PON 13 PAŹ` or `MON OCT 13`, the month is 1 based and the weekday counted
+    /// from Monday.
+    pub fn write_date(&self, out: &mut String, year: i32, month: u8, day: u8, weekday_from_monday: u8) {
+        if self.weekday {
+            out.push_str(self.language.weekdays()[weekday_from_monday as usize % 7]);
+            out.push(' ');
+        }
+
+        let separator = self.separator.as_char();
+        let mut first = true;
+        let mut part = |out: &mut String, value: core::fmt::Arguments| {
+            if !first {
+                out.push(separator);
+            }
+            first = false;
+            out.write_fmt(value).ok();
+        };
+        let month_name = self.language.months()[(month as usize + 11) % 12];
+        let year = year.rem_euclid(100);
+
+        let parts: &[u8] = match self.order {
+            DateOrder::DayMonthYear => b"dmy",
+            DateOrder::MonthDayYear => b"mdy",
+            DateOrder::YearMonthDay => b"ymd",
+        };
+        for field in parts {
+            match field {
+                b'd' => part(out, format_args!("{:02}", day)),
+                b'm' if self.month_names => part(out, format_args!("{}", month_name)),
+                b'm' => part(out, format_args!("{:02}", month)),
+                _ if self.month_names => {}
+                _ => part(out, format_args!("{:02}", year)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(settings: &DateSettings) -> String {
+        let mut out = String::new();
+        // Monday the 13th of October 2025
+        settings.write_date(&mut out, 2025, 10, 13, 0);
+        out
+    }
+
+    #[test]
+    fn formats_numeric_dates() {
+        let mut settings = DateSettings::default();
+        assert_eq!(date(&settings), "13.10.25");
+        settings.order = DateOrder::MonthDayYear;
+        settings.separator = Separator::Slash;
+        assert_eq!(date(&settings), "10/13/25");
+        settings.order = DateOrder::YearMonthDay;
+        settings.separator = Separator::Dash;
+        assert_eq!(date(&settings), "25-10-13");
+    }
+
+    #[test]
+    fn formats_named_dates() {
+        let mut settings = DateSettings {
+            language: Language::Polish,
+            separator: Separator::Space,
+            month_names: true,
+            weekday: true,
+            ..DateSettings::default()
+        };
+        assert_eq!(date(&settings), "PON 13 PAŹ");
+        settings.language = Language::English;
+        settings.order = DateOrder::MonthDayYear;
+        assert_eq!(date(&settings), "MON OCT 13");
+        settings.language = Language::Russian;
+        settings.order = DateOrder::DayMonthYear;
+        assert_eq!(date(&settings), "ПН 13 ОКТ");
+    }
+}
@@ -0,0 +1,232 @@
+//! Widgets showing the state of arbitrary Home Assistant entities.
+//!
+//! Every widget follows an MQTT topic, usually one the `mqtt_statestream` integration publishes an entity's state on,
+//! and shows its latest payload with a unit, an optional icon and a color picked by the value.
+
+use alloc::{string::String, vec::Vec};
+
+use serde::{Deserialize, Serialize};
+pub use serde_json_core::de::Error as JsonError;
+
+pub const MAX_WIDGETS: usize = 8;
+pub const MAX_RULES: usize = 4;
+
+/// Shown until the first message arrived on the topic.
+const NO_VALUE: &str = "--";
+
+/// Picks the widget color, numeric states by a threshold and any other state by an exact match.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColorRule {
+    /// Numeric states at or above this use the color, the rule with the highest matching threshold wins.
+    pub min: Option<f32>,
+    pub state: Option<String>,
+    pub color: [u8; 3],
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntityWidget {
+    pub name: String,
+    pub topic: String,
+    pub icon: Option<String>,
+    /// Appended to numeric states.
+    pub unit: String,
+    /// Used when no rule matches.
+    pub color: [u8; 3],
+    pub rules: Vec<ColorRule>,
+}
+
+impl EntityWidget {
+    /// The state as shown, `--` while nothing was received yet.
+    pub fn write_value(&self, out: &mut String, payload: Option<&str>) {
+        let Some(state) = payload.map(state) else {
+            out.push_str(NO_VALUE);
+            return;
+        };
+        out.push_str(state);
+        if state.parse::<f32>().is_ok() {
+            out.push_str(&self.unit);
+        }
+    }
+
+    pub fn color(&self, payload: Option<&str>) -> [u8; 3] {
+        let Some(state) = payload.map(state) else {
+            return self.color;
+        };
+        if let Some(rule) = self.rules.iter().find(|rule| rule.state.as_deref() == Some(state)) {
+            return rule.color;
+        }
+        let Ok(value) = state.parse::<f32>() else {
+            return self.color;
+        };
+        self.rules
+            .iter()
+            .filter_map(|rule| rule.min.filter(|min| value >= *min).map(|min| (min, rule.color)))
+            .max_by(|(a, _), (b, _)| a.total_cmp(b))
+            .map_or(self.color, |(_, color)| color)
+    }
+}
+
+/// The state within a payload, `mqtt_statestream` publishes them JSON encoded so strings come quoted.
+pub fn state(payload: &str) -> &str {
+    let payload = payload.trim();
+    payload.strip_prefix('"').and_then(|state| state.strip_suffix('"')).unwrap_or(payload)
+}
+
+#[derive(Debug)]
+pub enum EntityWidgetError {
+    InvalidJson(JsonError),
+    EmptyName,
+    TooManyWidgets,
+    TooManyRules,
+}
+
+#[derive(Deserialize)]
+struct ColorRuleCommand<'a> {
+    min: Option<f32>,
+    state: Option<&'a str>,
+    color: [u8; 3],
+}
+
+/// Widget command as pushed over MQTT, a missing or empty `topic` deletes the widget.
+#[derive(Deserialize)]
+struct EntityWidgetCommand<'a> {
+    name: &'a str,
+    topic: Option<&'a str>,
+    icon: Option<&'a str>,
+    unit: Option<&'a str>,
+    color: Option<[u8; 3]>,
+    #[serde(borrow)]
+    rules: Option<Vec<ColorRuleCommand<'a>>>,
+}
+
+/// Adds, updates or removes a widget according to a JSON command like `{"name": "Outside", "topic":
+/// "homeassistant/sensor/outside/state", "icon": "temp", "unit": "C", "rules": [{"min": 25, "color": [255, 0, 0]}]}`.
+pub fn apply_command(widgets: &mut Vec<EntityWidget>, payload: &str) -> Result<(), EntityWidgetError> {
+    let (command, _) =
+        serde_json_core::from_str::<EntityWidgetCommand>(payload).map_err(EntityWidgetError::InvalidJson)?;
+    if command.name.is_empty() {
+        return Err(EntityWidgetError::EmptyName);
+    }
+    let position = widgets.iter().position(|widget| widget.name == command.name);
+
+    let topic = match command.topic {
+        Some(topic) if !topic.is_empty() => topic,
+        _ => {
+            if let Some(position) = position {
+                widgets.remove(position);
+            }
+            return Ok(());
+        }
+    };
+    let rules = command.rules.unwrap_or_default();
+    if rules.len() > MAX_RULES {
+        return Err(EntityWidgetError::TooManyRules);
+    }
+
+    let widget = EntityWidget {
+        name: command.name.into(),
+        topic: topic.into(),
+        icon: command.icon.filter(|icon| !icon.is_empty()).map(Into::into),
+        unit: command.unit.unwrap_or_default().into(),
+        color: command.color.unwrap_or([255, 255, 255]),
+        rules: rules
+            .into_iter()
+            .map(|rule| ColorRule { min: rule.min, state: rule.state.map(Into::into), color: rule.color })
+            .collect(),
+    };
+    match position {
+        Some(position) => widgets[position] = widget,
+        None if widgets.len() >= MAX_WIDGETS => return Err(EntityWidgetError::TooManyWidgets),
+        None => widgets.push(widget),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(widget: &EntityWidget, payload: Option<&str>) -> String {
+        let mut out = String::new();
+        widget.write_value(&mut out, payload);
+        out
+    }
+
+    #[test]
+    fn applies_commands() {
+        let mut widgets = Vec::new();
+        apply_command(
+            &mut widgets,
+            r#"{"name": "Outside", "topic": "homeassistant/sensor/outside/state", "unit": "C",
+                "rules": [{"min": 25, "color": [255, 0, 0]}, {"min": 0, "color": [0, 255, 0]}]}"#,
+        )
+        .unwrap();
+        apply_command(&mut widgets, r#"{"name": "Door", "topic": "homeassistant/binary_sensor/door/state"}"#).unwrap();
+        assert_eq!(widgets.len(), 2);
+        assert_eq!(widgets[0].rules.len(), 2);
+        assert_eq!(widgets[1].color, [255, 255, 255]);
+
+        apply_command(&mut widgets, r#"{"name": "Door", "topic": "door", "icon": "door"}"#).unwrap();
+        assert_eq!(widgets[1].topic, "door");
+        assert_eq!(widgets[1].icon.as_deref(), Some("door"));
+
+        assert!(matches!(
+            apply_command(&mut widgets, r#"{"name": "", "topic": "door"}"#),
+            Err(EntityWidgetError::EmptyName)
+        ));
+        assert!(matches!(
+            apply_command(
+                &mut widgets,
+                r#"{"name": "Door", "topic": "door", "rules": [{"min": 1, "color": [0, 0, 0]},
+                    {"min": 2, "color": [0, 0, 0]}, {"min": 3, "color": [0, 0, 0]},
+                    {"min": 4, "color": [0, 0, 0]}, {"min": 5, "color": [0, 0, 0]}]}"#
+            ),
+            Err(EntityWidgetError::TooManyRules)
+        ));
+
+        apply_command(&mut widgets, r#"{"name": "Outside"}"#).unwrap();
+        assert_eq!(widgets.len(), 1);
+        assert_eq!(widgets[0].name, "Door");
+    }
+
+    #[test]
+    fn formats_states() {
+        let widget = EntityWidget {
+            name: "Power".into(),
+            topic: "power".into(),
+            icon: None,
+            unit: "W".into(),
+            color: [255, 255, 255],
+            rules: Vec::new(),
+        };
+        assert_eq!(value(&widget, None), "--");
+        assert_eq!(value(&widget, Some("\"1250.5\"")), "1250.5W");
+        assert_eq!(value(&widget, Some(" 42\n")), "42W");
+        assert_eq!(value(&widget, Some("\"unavailable\"")), "unavailable");
+    }
+
+    #[test]
+    fn picks_colors_by_threshold_and_state() {
+        let rule =
+            |min: Option<f32>, state: Option<&str>, color| ColorRule { min, state: state.map(Into::into), color };
+        let widget = EntityWidget {
+            name: "Outside".into(),
+            topic: "outside".into(),
+            icon: None,
+            unit: "C".into(),
+            color: [255, 255, 255],
+            rules: alloc::vec![
+                rule(Some(0.0), None, [0, 255, 0]),
+                rule(Some(25.0), None, [255, 0, 0]),
+                rule(None, Some("on"), [255, 128, 0]),
+            ],
+        };
+        assert_eq!(widget.color(None), [255, 255, 255]);
+        assert_eq!(widget.color(Some("-3.5")), [255, 255, 255]);
+        assert_eq!(widget.color(Some("0")), [0, 255, 0]);
+        assert_eq!(widget.color(Some("24.9")), [0, 255, 0]);
+        assert_eq!(widget.color(Some("\"31\"")), [255, 0, 0]);
+        assert_eq!(widget.color(Some("\"on\"")), [255, 128, 0]);
+        assert_eq!(widget.color(Some("off")), [255, 255, 255]);
+    }
+}
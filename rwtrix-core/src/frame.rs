@@ -0,0 +1,124 @@
+//! Decides when the display loop renders the next frame.
+//!
+//! Pages and overlays tell how soon they change again, the loop sleeps until the most urgent of those, and anything
+//! that changes the picture right away, like a button press, asks for a frame at once.
+
+/// Rate of scrolling text and animations.
+pub const ANIMATION_FPS: u32 = 30;
+/// Interval of content that only changes on its own, like settings or states polled from other tasks.
+pub const IDLE_INTERVAL_MS: u64 = 1000;
+
+/// When a page or overlay needs to be drawn again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Redraw {
+    /// At a steady rate, frames keep their spacing even when one is late.
+    Fps(u32),
+    /// Once after the given time, the content does not change before then.
+    AfterMs(u64),
+}
+
+impl Redraw {
+    /// Nothing on screen moves, only polled state can change.
+    pub const IDLE: Redraw = Redraw::AfterMs(IDLE_INTERVAL_MS);
+    pub const ANIMATION: Redraw = Redraw::Fps(ANIMATION_FPS);
+
+    pub fn interval_ms(self) -> u64 {
+        match self {
+            Redraw::Fps(fps) => 1000 / fps.max(1) as u64,
+            Redraw::AfterMs(ms) => ms,
+        }
+    }
+
+    /// The more urgent of the two.
+    pub fn min(self, other: Redraw) -> Redraw {
+        if other.interval_ms() < self.interval_ms() {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct FrameScheduler {
+    /// Time of the next frame, `None` when one is due right away.
+    next_frame_ms: Option<u64>,
+}
+
+impl FrameScheduler {
+    pub fn new() -> Self {
+        FrameScheduler { next_frame_ms: None }
+    }
+
+    /// Asks for a frame as soon as possible.
+    pub fn request(&mut self) {
+        self.next_frame_ms = None;
+    }
+
+    pub fn is_due(&self, now_ms: u64) -> bool {
+        self.next_frame_ms.is_none_or(|next| now_ms >= next)
+    }
+
+    /// Plans the frame after the one rendered at `now_ms`. Steady rates keep the cadence of the previous frames, a
+    /// frame that is late by more than an interval is not caught up with a burst.
+    pub fn rendered(&mut self, now_ms: u64, redraw: Redraw) {
+        let interval = redraw.interval_ms().max(1);
+        let next = match (redraw, self.next_frame_ms) {
+            (Redraw::Fps(_), Some(scheduled)) if scheduled + interval > now_ms => scheduled + interval,
+            _ => now_ms + interval,
+        };
+        self.next_frame_ms = Some(next);
+    }
+
+    /// How long to sleep before the next frame, at most `max_ms` so input is still polled in between.
+    pub fn sleep_ms(&self, now_ms: u64, max_ms: u64) -> u64 {
+        match self.next_frame_ms {
+            None => 0,
+            Some(next) => next.saturating_sub(now_ms).min(max_ms),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_the_most_urgent_redraw() {
+        assert_eq!(Redraw::IDLE.min(Redraw::ANIMATION), Redraw::ANIMATION);
+        assert_eq!(Redraw::AfterMs(20).min(Redraw::Fps(30)), Redraw::AfterMs(20));
+        assert_eq!(Redraw::Fps(0).interval_ms(), 1000);
+    }
+
+    #[test]
+    fn keeps_a_steady_cadence() {
+        let mut scheduler = FrameScheduler::new();
+        assert!(scheduler.is_due(0));
+        scheduler.rendered(0, Redraw::Fps(50));
+        assert!(!scheduler.is_due(19));
+        assert_eq!(scheduler.sleep_ms(5, 10), 10);
+        assert_eq!(scheduler.sleep_ms(15, 10), 5);
+
+        // rendered a bit late, the next frame stays on the 20 ms grid
+        assert!(scheduler.is_due(23));
+        scheduler.rendered(23, Redraw::Fps(50));
+        assert_eq!(scheduler.sleep_ms(23, 100), 17);
+
+        // far behind, no burst of frames to catch up
+        scheduler.rendered(200, Redraw::Fps(50));
+        assert_eq!(scheduler.sleep_ms(200, 100), 20);
+    }
+
+    #[test]
+    fn waits_for_static_content_until_asked() {
+        let mut scheduler = FrameScheduler::new();
+        scheduler.rendered(1000, Redraw::AfterMs(400));
+        assert!(!scheduler.is_due(1399));
+        assert!(scheduler.is_due(1400));
+
+        scheduler.rendered(1400, Redraw::IDLE);
+        scheduler.request();
+        assert!(scheduler.is_due(1401));
+        assert_eq!(scheduler.sleep_ms(1401, 10), 0);
+    }
+}
@@ -0,0 +1,245 @@
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+
+use super::{draw_pixel, Button, Rng, HEIGHT, WIDTH};
+
+const BRICK_WIDTH: i32 = 4;
+const BRICK_ROWS: usize = 3;
+/// Every brick of a row as one bit.
+const FULL_ROW: u8 = u8::MAX >> (8 - (WIDTH / BRICK_WIDTH) as u32);
+const BRICK_COLORS: [Rgb888; BRICK_ROWS] = [Rgb888::RED, Rgb888::new(255, 128, 0), Rgb888::YELLOW];
+const PADDLE_WIDTH: i32 = 6;
+const PADDLE_Y: i32 = HEIGHT - 1;
+const START_STEP_MS: u64 = 130;
+const MIN_STEP_MS: u64 = 60;
+/// Every cleared wall makes the ball this much faster.
+const SPEEDUP_MS: u64 = 15;
+
+/// Left and Right move the paddle for as long as they are held. The ball leaves the paddle towards the side it hits,
+/// a cleared wall is built again and the ball gets faster.
+pub struct Breakout {
+    bricks: [u8; BRICK_ROWS],
+    paddle: i32,
+    held: Option<Button>,
+    ball: Point,
+    velocity: Point,
+    level: u32,
+    score: u32,
+    over: bool,
+    rng: Rng,
+}
+
+impl Breakout {
+    pub fn new(rng: Rng) -> Self {
+        let mut breakout = Breakout {
+            bricks: [FULL_ROW; BRICK_ROWS],
+            paddle: (WIDTH - PADDLE_WIDTH) / 2,
+            held: None,
+            ball: Point::zero(),
+            velocity: Point::zero(),
+            level: 0,
+            score: 0,
+            over: false,
+            rng,
+        };
+        breakout.serve();
+        breakout
+    }
+
+    /// Puts the ball on top of the paddle, heading up to a random side.
+    fn serve(&mut self) {
+        self.ball = Point::new(self.paddle + PADDLE_WIDTH / 2, PADDLE_Y - 1);
+        self.velocity = Point::new(if self.rng.below(2) == 0 { -1 } else { 1 }, -1);
+    }
+
+    pub fn press(&mut self, button: Button) {
+        if button != Button::Select {
+            self.held = Some(button);
+            self.move_paddle();
+        }
+    }
+
+    pub fn release(&mut self, button: Button) {
+        if self.held == Some(button) {
+            self.held = None;
+        }
+    }
+
+    fn move_paddle(&mut self) {
+        let offset = match self.held {
+            Some(Button::Left) => -1,
+            Some(Button::Right) => 1,
+            _ => 0,
+        };
+        self.paddle = (self.paddle + offset).clamp(0, WIDTH - PADDLE_WIDTH);
+    }
+
+    fn has_brick(&self, point: Point) -> bool {
+        (0..BRICK_ROWS as i32).contains(&point.y)
+            && (0..WIDTH).contains(&point.x)
+            && self.bricks[point.y as usize] & (1 << (point.x / BRICK_WIDTH)) != 0
+    }
+
+    fn remove_brick(&mut self, point: Point) {
+        self.bricks[point.y as usize] &= !(1 << (point.x / BRICK_WIDTH));
+        self.score += 1;
+    }
+
+    fn is_free(&self, point: Point) -> bool {
+        (0..WIDTH).contains(&point.x) && point.y >= 0 && !self.has_brick(point)
+    }
+
+    pub fn step(&mut self) {
+        if self.over {
+            return;
+        }
+        self.move_paddle();
+
+        let Point { x, y } = self.ball;
+        if !(0..WIDTH).contains(&(x + self.velocity.x)) {
+            self.velocity.x = -self.velocity.x;
+        }
+        if y + self.velocity.y < 0 {
+            self.velocity.y = -self.velocity.y;
+        }
+        // a brick above or below wins over one to the side, which wins over one on the diagonal
+        let vertical = Point::new(x, y + self.velocity.y);
+        let horizontal = Point::new(x + self.velocity.x, y);
+        let diagonal = self.ball + self.velocity;
+        if self.has_brick(vertical) {
+            self.remove_brick(vertical);
+            self.velocity.y = -self.velocity.y;
+        } else if self.has_brick(horizontal) {
+            self.remove_brick(horizontal);
+            self.velocity.x = -self.velocity.x;
+        } else if self.has_brick(diagonal) {
+            self.remove_brick(diagonal);
+            self.velocity = -self.velocity;
+        }
+
+        if self.velocity.y > 0 && y + 1 == PADDLE_Y {
+            let hit = x + self.velocity.x - self.paddle;
+            if (0..PADDLE_WIDTH).contains(&hit) {
+                self.velocity.y = -1;
+                // the outer thirds of the paddle send the ball towards their side
+                if hit < PADDLE_WIDTH / 3 {
+                    self.velocity.x = -1;
+                } else if hit >= PADDLE_WIDTH - PADDLE_WIDTH / 3 {
+                    self.velocity.x = 1;
+                }
+            }
+        }
+
+        // after a bounce into a corner the ball waits a step for the next bounce
+        let next = self.ball + self.velocity;
+        if self.is_free(next) {
+            self.ball = next;
+        }
+        if self.ball.y >= PADDLE_Y {
+            self.over = true;
+        } else if self.bricks.iter().all(|row| *row == 0) {
+            self.level += 1;
+            self.bricks = [FULL_ROW; BRICK_ROWS];
+            self.serve();
+        }
+    }
+
+    pub fn step_ms(&self) -> u64 {
+        START_STEP_MS.saturating_sub(self.level as u64 * SPEEDUP_MS).max(MIN_STEP_MS)
+    }
+
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.over
+    }
+
+    pub fn draw<T: DrawTarget<Color = Rgb888>>(&self, target: &mut T) {
+        for (y, color) in BRICK_COLORS.iter().enumerate() {
+            // the last column of every brick stays dark so the bricks can be told apart
+            for x in (0..WIDTH).filter(|x| x % BRICK_WIDTH != BRICK_WIDTH - 1) {
+                if self.has_brick(Point::new(x, y as i32)) {
+                    draw_pixel(target, x, y as i32, *color);
+                }
+            }
+        }
+        for x in self.paddle..self.paddle + PADDLE_WIDTH {
+            draw_pixel(target, x, PADDLE_Y, Rgb888::new(0, 128, 255));
+        }
+        draw_pixel(target, self.ball.x, self.ball.y, Rgb888::WHITE);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounces_off_bricks_and_the_paddle() {
+        let mut breakout = Breakout::new(Rng::new(3));
+        breakout.ball = Point::new(10, 3);
+        breakout.velocity = Point::new(1, -1);
+        breakout.step();
+        // the brick right above was hit, the ball turned around
+        assert_eq!(breakout.score(), 1);
+        assert!(!breakout.has_brick(Point::new(8, 2)));
+        assert_eq!(breakout.velocity, Point::new(1, 1));
+        assert_eq!(breakout.ball, Point::new(11, 4));
+
+        breakout.paddle = 11;
+        breakout.step();
+        breakout.step();
+        assert_eq!(breakout.ball, Point::new(13, PADDLE_Y - 1));
+        breakout.step();
+        // landed in the middle third, keeps going right
+        assert_eq!(breakout.velocity, Point::new(1, -1));
+        assert_eq!(breakout.ball, Point::new(14, PADDLE_Y - 2));
+
+        breakout.ball = Point::new(5, PADDLE_Y - 1);
+        breakout.velocity = Point::new(-1, 1);
+        breakout.paddle = 4;
+        breakout.step();
+        // the left edge sends it back to the left
+        assert_eq!(breakout.velocity, Point::new(-1, -1));
+        assert!(!breakout.is_over());
+    }
+
+    #[test]
+    fn moves_the_paddle_while_held() {
+        let mut breakout = Breakout::new(Rng::new(3));
+        let start = breakout.paddle;
+        breakout.press(Button::Left);
+        assert_eq!(breakout.paddle, start - 1);
+        breakout.step();
+        breakout.step();
+        assert_eq!(breakout.paddle, start - 3);
+        breakout.release(Button::Left);
+        breakout.step();
+        assert_eq!(breakout.paddle, start - 3);
+        for _ in 0..WIDTH {
+            breakout.press(Button::Right);
+        }
+        assert_eq!(breakout.paddle, WIDTH - PADDLE_WIDTH);
+    }
+
+    #[test]
+    fn ends_when_the_ball_is_missed_and_rebuilds_cleared_walls() {
+        let mut breakout = Breakout::new(Rng::new(3));
+        breakout.bricks = [0, 0, 1];
+        breakout.ball = Point::new(1, 3);
+        breakout.velocity = Point::new(-1, -1);
+        breakout.step();
+        assert_eq!(breakout.score(), 1);
+        assert_eq!(breakout.bricks, [FULL_ROW; BRICK_ROWS]);
+        assert_eq!(breakout.ball.y, PADDLE_Y - 1);
+        assert!(breakout.step_ms() < START_STEP_MS);
+
+        breakout.paddle = 0;
+        breakout.ball = Point::new(20, 5);
+        breakout.velocity = Point::new(1, 1);
+        breakout.step();
+        breakout.step();
+        assert!(breakout.is_over());
+    }
+}
@@ -0,0 +1,209 @@
+//! Small games played on the panel with the three buttons.
+//!
+//! The games only know about button presses and releases, a step function the caller runs at the interval the game
+//! asks for, and a draw target, so they play the same on the device and in the tests.
+
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+use serde::{Deserialize, Serialize};
+
+mod breakout;
+mod runner;
+mod snake;
+
+pub use breakout::Breakout;
+pub use runner::Runner;
+pub use snake::Snake;
+
+/// The whole panel is the playing field.
+pub const WIDTH: i32 = crate::layers::WIDTH as i32;
+pub const HEIGHT: i32 = crate::layers::HEIGHT as i32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Button {
+    Left,
+    Select,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameKind {
+    Snake,
+    Breakout,
+    Runner,
+}
+
+impl GameKind {
+    pub const ALL: [GameKind; 3] = [GameKind::Snake, GameKind::Breakout, GameKind::Runner];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GameKind::Snake => "snake",
+            GameKind::Breakout => "breakout",
+            GameKind::Runner => "runner",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        GameKind::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+}
+
+pub enum Game {
+    Snake(Snake),
+    Breakout(Breakout),
+    Runner(Runner),
+}
+
+impl Game {
+    /// Starts a new round, `seed` decides where food, bricks and gaps show up.
+    pub fn new(kind: GameKind, seed: u32) -> Self {
+        let rng = Rng::new(seed);
+        match kind {
+            GameKind::Snake => Game::Snake(Snake::new(rng)),
+            GameKind::Breakout => Game::Breakout(Breakout::new(rng)),
+            GameKind::Runner => Game::Runner(Runner::new(rng)),
+        }
+    }
+
+    pub fn kind(&self) -> GameKind {
+        match self {
+            Game::Snake(_) => GameKind::Snake,
+            Game::Breakout(_) => GameKind::Breakout,
+            Game::Runner(_) => GameKind::Runner,
+        }
+    }
+
+    pub fn press(&mut self, button: Button) {
+        match self {
+            Game::Snake(game) => game.press(button),
+            Game::Breakout(game) => game.press(button),
+            Game::Runner(game) => game.press(button),
+        }
+    }
+
+    pub fn release(&mut self, button: Button) {
+        match self {
+            Game::Breakout(game) => game.release(button),
+            Game::Snake(_) | Game::Runner(_) => {}
+        }
+    }
+
+    /// Advances the game by one step, nothing happens once it is over.
+    pub fn step(&mut self) {
+        match self {
+            Game::Snake(game) => game.step(),
+            Game::Breakout(game) => game.step(),
+            Game::Runner(game) => game.step(),
+        }
+    }
+
+    /// Time until the next step, the games speed up as the score goes up.
+    pub fn step_ms(&self) -> u64 {
+        match self {
+            Game::Snake(game) => game.step_ms(),
+            Game::Breakout(game) => game.step_ms(),
+            Game::Runner(game) => game.step_ms(),
+        }
+    }
+
+    pub fn score(&self) -> u32 {
+        match self {
+            Game::Snake(game) => game.score(),
+            Game::Breakout(game) => game.score(),
+            Game::Runner(game) => game.score(),
+        }
+    }
+
+    pub fn is_over(&self) -> bool {
+        match self {
+            Game::Snake(game) => game.is_over(),
+            Game::Breakout(game) => game.is_over(),
+            Game::Runner(game) => game.is_over(),
+        }
+    }
+
+    pub fn draw<T: DrawTarget<Color = Rgb888>>(&self, target: &mut T) {
+        match self {
+            Game::Snake(game) => game.draw(target),
+            Game::Breakout(game) => game.draw(target),
+            Game::Runner(game) => game.draw(target),
+        }
+    }
+}
+
+/// Best score of every game.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HighScores {
+    pub snake: u32,
+    pub breakout: u32,
+    pub runner: u32,
+}
+
+impl HighScores {
+    pub fn get(&self, kind: GameKind) -> u32 {
+        match kind {
+            GameKind::Snake => self.snake,
+            GameKind::Breakout => self.breakout,
+            GameKind::Runner => self.runner,
+        }
+    }
+
+    /// Keeps `score` if it beats the high score of the game, returns whether it did.
+    pub fn record(&mut self, kind: GameKind, score: u32) -> bool {
+        let high_score = match kind {
+            GameKind::Snake => &mut self.snake,
+            GameKind::Breakout => &mut self.breakout,
+            GameKind::Runner => &mut self.runner,
+        };
+        if score <= *high_score {
+            return false;
+        }
+        *high_score = score;
+        true
+    }
+}
+
+/// Xorshift, plenty for placing food and gaps.
+#[derive(Debug, Clone, Copy)]
+pub struct Rng(u32);
+
+impl Rng {
+    pub fn new(seed: u32) -> Self {
+        // xorshift never leaves zero
+        Rng(seed.max(1))
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0
+    }
+
+    /// Uniform enough in `0..max`.
+    pub fn below(&mut self, max: u32) -> u32 {
+        self.next_u32() % max.max(1)
+    }
+}
+
+fn draw_pixel<T: DrawTarget<Color = Rgb888>>(target: &mut T, x: i32, y: i32, color: Rgb888) {
+    target.draw_iter([Pixel(Point::new(x, y), color)]).ok();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_best_scores() {
+        let mut high_scores = HighScores::default();
+        assert!(high_scores.record(GameKind::Snake, 12));
+        assert!(!high_scores.record(GameKind::Snake, 12));
+        assert!(!high_scores.record(GameKind::Snake, 3));
+        assert!(!high_scores.record(GameKind::Runner, 0));
+        assert!(high_scores.record(GameKind::Runner, 4));
+        assert_eq!(high_scores, HighScores { snake: 12, breakout: 0, runner: 4 });
+        assert_eq!(high_scores.get(GameKind::Runner), 4);
+        assert_eq!(GameKind::from_name("breakout"), Some(GameKind::Breakout));
+    }
+}
@@ -0,0 +1,189 @@
+use alloc::collections::VecDeque;
+
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+
+use super::{draw_pixel, Button, Rng, HEIGHT, WIDTH};
+
+/// Heights and speeds are kept in sixteenths of a pixel.
+const SUBPIXELS: i32 = 16;
+const GRAVITY: i32 = 2;
+const FLAP: i32 = -10;
+const MAX_FALL: i32 = SUBPIXELS;
+const BIRD_X: i32 = 5;
+const PIPE_WIDTH: i32 = 2;
+const GAP: i32 = 4;
+/// Columns from one pipe to the next.
+const PIPE_SPACING: i32 = 12;
+const STEP_MS: u64 = 40;
+/// Steps per column the pipes move, fewer as the score goes up.
+const START_PIPE_STEPS: u32 = 3;
+/// Passing this many pipes takes one step off.
+const SPEEDUP_PIPES: u32 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Pipe {
+    x: i32,
+    gap_top: i32,
+}
+
+/// Any button flaps, the bird falls otherwise. Touching a pipe or the ground ends the game, every pipe passed counts.
+pub struct Runner {
+    y: i32,
+    velocity: i32,
+    pipes: VecDeque<Pipe>,
+    /// Steps since the pipes last moved.
+    steps: u32,
+    score: u32,
+    over: bool,
+    rng: Rng,
+}
+
+impl Runner {
+    pub fn new(rng: Rng) -> Self {
+        let mut runner = Runner {
+            y: HEIGHT / 2 * SUBPIXELS,
+            velocity: 0,
+            pipes: VecDeque::new(),
+            steps: 0,
+            score: 0,
+            over: false,
+            rng,
+        };
+        runner.add_pipe(WIDTH);
+        runner
+    }
+
+    fn add_pipe(&mut self, x: i32) {
+        let gap_top = self.rng.below((HEIGHT - GAP + 1) as u32) as i32;
+        self.pipes.push_back(Pipe { x, gap_top });
+    }
+
+    pub fn press(&mut self, _button: Button) {
+        if !self.over {
+            self.velocity = FLAP;
+        }
+    }
+
+    fn row(&self) -> i32 {
+        self.y.div_euclid(SUBPIXELS)
+    }
+
+    fn pipe_steps(&self) -> u32 {
+        START_PIPE_STEPS.saturating_sub(self.score / SPEEDUP_PIPES).max(1)
+    }
+
+    pub fn step(&mut self) {
+        if self.over {
+            return;
+        }
+        self.velocity = (self.velocity + GRAVITY).min(MAX_FALL);
+        // the sky is no limit, the bird just stops there
+        self.y = (self.y + self.velocity).max(0);
+        if self.y == 0 {
+            self.velocity = self.velocity.max(0);
+        }
+
+        self.steps += 1;
+        if self.steps >= self.pipe_steps() {
+            self.steps = 0;
+            for pipe in &mut self.pipes {
+                pipe.x -= 1;
+            }
+            self.score += self.pipes.iter().filter(|pipe| pipe.x + PIPE_WIDTH == BIRD_X).count() as u32;
+            self.pipes.retain(|pipe| pipe.x + PIPE_WIDTH > 0);
+            if self.pipes.back().is_none_or(|pipe| pipe.x <= WIDTH - PIPE_SPACING) {
+                self.add_pipe(WIDTH);
+            }
+        }
+
+        let row = self.row();
+        let hits_pipe = self.pipes.iter().any(|pipe| {
+            (pipe.x..pipe.x + PIPE_WIDTH).contains(&BIRD_X) && !(pipe.gap_top..pipe.gap_top + GAP).contains(&row)
+        });
+        self.over = row >= HEIGHT || hits_pipe;
+    }
+
+    pub fn step_ms(&self) -> u64 {
+        STEP_MS
+    }
+
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.over
+    }
+
+    pub fn draw<T: DrawTarget<Color = Rgb888>>(&self, target: &mut T) {
+        for pipe in &self.pipes {
+            for x in pipe.x..pipe.x + PIPE_WIDTH {
+                for y in (0..HEIGHT).filter(|y| !(pipe.gap_top..pipe.gap_top + GAP).contains(y)) {
+                    draw_pixel(target, x, y, Rgb888::new(0, 180, 0));
+                }
+            }
+        }
+        draw_pixel(target, BIRD_X, self.row().min(HEIGHT - 1), Rgb888::YELLOW);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn falls_without_flapping() {
+        let mut runner = Runner::new(Rng::new(9));
+        runner.pipes.clear();
+        let mut steps = 0;
+        while !runner.is_over() {
+            runner.step();
+            steps += 1;
+            assert!(steps < 100, "never hit the ground");
+        }
+        assert_eq!(runner.row(), HEIGHT);
+        let y = runner.y;
+        runner.press(Button::Select);
+        runner.step();
+        assert_eq!(runner.y, y);
+    }
+
+    #[test]
+    fn flapping_keeps_it_up() {
+        let mut runner = Runner::new(Rng::new(9));
+        runner.pipes.clear();
+        for step in 0..200 {
+            if step % 9 == 0 {
+                runner.press(Button::Left);
+            }
+            runner.step();
+            runner.pipes.clear();
+        }
+        assert!(!runner.is_over());
+        assert!((0..HEIGHT).contains(&runner.row()));
+    }
+
+    #[test]
+    fn scores_pipes_passed_through_the_gap() {
+        let mut runner = Runner::new(Rng::new(9));
+        runner.pipes = [Pipe { x: BIRD_X + 1, gap_top: 0 }].into();
+        runner.y = 2 * SUBPIXELS;
+        for _ in 0..4 * START_PIPE_STEPS {
+            runner.velocity = 0;
+            runner.y = 2 * SUBPIXELS;
+            runner.step();
+            assert!(!runner.is_over());
+        }
+        assert_eq!(runner.score(), 1);
+
+        let mut runner = Runner::new(Rng::new(9));
+        runner.pipes = [Pipe { x: BIRD_X + 1, gap_top: 0 }].into();
+        runner.y = 6 * SUBPIXELS;
+        runner.velocity = -GRAVITY;
+        for _ in 0..START_PIPE_STEPS {
+            runner.step();
+        }
+        assert!(runner.is_over());
+        assert_eq!(runner.score(), 0);
+    }
+}
@@ -0,0 +1,173 @@
+use alloc::collections::VecDeque;
+
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+
+use super::{draw_pixel, Button, Rng, HEIGHT, WIDTH};
+
+const START_LENGTH: usize = 3;
+const START_STEP_MS: u64 = 220;
+const MIN_STEP_MS: u64 = 90;
+/// Every piece of food makes the next steps this much faster.
+const SPEEDUP_MS: u64 = 8;
+/// Turns pressed faster than the snake moves are kept for the next steps.
+const MAX_PENDING_TURNS: usize = 2;
+
+/// Left and Right turn the snake relative to where it heads, the field wraps around at the edges.
+pub struct Snake {
+    /// Head first.
+    body: VecDeque<Point>,
+    direction: Point,
+    pending_turns: VecDeque<Button>,
+    food: Point,
+    score: u32,
+    over: bool,
+    rng: Rng,
+}
+
+impl Snake {
+    pub fn new(rng: Rng) -> Self {
+        let body = (0..START_LENGTH as i32).map(|i| Point::new(WIDTH / 4 - i, HEIGHT / 2)).collect();
+        let mut snake = Snake {
+            body,
+            direction: Point::new(1, 0),
+            pending_turns: VecDeque::new(),
+            food: Point::zero(),
+            score: 0,
+            over: false,
+            rng,
+        };
+        snake.place_food();
+        snake
+    }
+
+    pub fn press(&mut self, button: Button) {
+        if button != Button::Select && self.pending_turns.len() < MAX_PENDING_TURNS {
+            self.pending_turns.push_back(button);
+        }
+    }
+
+    pub fn step(&mut self) {
+        if self.over {
+            return;
+        }
+        // y grows downwards, so turning right maps (x, y) to (-y, x)
+        match self.pending_turns.pop_front() {
+            Some(Button::Left) => self.direction = Point::new(self.direction.y, -self.direction.x),
+            Some(Button::Right) => self.direction = Point::new(-self.direction.y, self.direction.x),
+            _ => {}
+        }
+        let head = self.body[0] + self.direction;
+        let head = Point::new(head.x.rem_euclid(WIDTH), head.y.rem_euclid(HEIGHT));
+        let eats = head == self.food;
+        if !eats {
+            self.body.pop_back();
+        }
+        if self.body.contains(&head) {
+            self.over = true;
+            return;
+        }
+        self.body.push_front(head);
+        if eats {
+            self.score += 1;
+            self.place_food();
+        }
+    }
+
+    /// Picks one of the free cells, the game is won once there is none left.
+    fn place_food(&mut self) {
+        let free = (WIDTH * HEIGHT) as u32 - self.body.len() as u32;
+        if free == 0 {
+            self.over = true;
+            return;
+        }
+        let index = self.rng.below(free) as usize;
+        self.food = (0..WIDTH * HEIGHT)
+            .map(|cell| Point::new(cell % WIDTH, cell / WIDTH))
+            .filter(|cell| !self.body.contains(cell))
+            .nth(index)
+            .unwrap_or_default();
+    }
+
+    pub fn step_ms(&self) -> u64 {
+        START_STEP_MS.saturating_sub(self.score as u64 * SPEEDUP_MS).max(MIN_STEP_MS)
+    }
+
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.over
+    }
+
+    pub fn draw<T: DrawTarget<Color = Rgb888>>(&self, target: &mut T) {
+        draw_pixel(target, self.food.x, self.food.y, Rgb888::RED);
+        for (i, part) in self.body.iter().enumerate() {
+            let color = if i == 0 { Rgb888::new(128, 255, 64) } else { Rgb888::new(0, 160, 0) };
+            draw_pixel(target, part.x, part.y, color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moves_turns_and_wraps() {
+        let mut snake = Snake::new(Rng::new(7));
+        snake.food = Point::new(0, 0);
+        snake.step();
+        assert_eq!(snake.body[0], Point::new(WIDTH / 4 + 1, HEIGHT / 2));
+        assert_eq!(snake.body.len(), START_LENGTH);
+
+        snake.press(Button::Left);
+        snake.press(Button::Left);
+        snake.press(Button::Left);
+        snake.step();
+        assert_eq!(snake.direction, Point::new(0, -1));
+        snake.step();
+        assert_eq!(snake.direction, Point::new(-1, 0));
+        // only two turns are kept
+        snake.step();
+        assert_eq!(snake.direction, Point::new(-1, 0));
+
+        snake.press(Button::Right);
+        for _ in 0..HEIGHT {
+            snake.step();
+        }
+        // went up through the top edge and came back from the bottom
+        assert_eq!(snake.body[0].y, HEIGHT / 2 - 1);
+        assert!(!snake.is_over());
+    }
+
+    #[test]
+    fn grows_when_eating() {
+        let mut snake = Snake::new(Rng::new(7));
+        let step_ms = snake.step_ms();
+        snake.food = snake.body[0] + snake.direction;
+        snake.step();
+        assert_eq!(snake.score(), 1);
+        assert_eq!(snake.body.len(), START_LENGTH + 1);
+        assert!(!snake.body.contains(&snake.food));
+        assert!(snake.step_ms() < step_ms);
+    }
+
+    #[test]
+    fn ends_when_running_into_itself() {
+        let mut snake = Snake::new(Rng::new(7));
+        snake.body = (0..6).map(|i| Point::new(10 - i, 4)).collect();
+        snake.food = Point::new(0, 0);
+        snake.press(Button::Right);
+        snake.press(Button::Right);
+        snake.step();
+        snake.step();
+        assert!(!snake.is_over());
+        snake.press(Button::Right);
+        snake.step();
+        assert!(snake.is_over());
+        let body = snake.body.clone();
+        snake.step();
+        assert_eq!(snake.body, body);
+    }
+}
@@ -0,0 +1,236 @@
+//! Minimal HTTP/1.1 request parsing and response writing, enough for a small JSON API.
+
+use alloc::vec::Vec;
+use core::fmt::Write as _;
+
+/// Largest request, headers and body together, the server buffers.
+pub const MAX_REQUEST_SIZE: usize = 2048;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    Get,
+    Post,
+    Put,
+    Delete,
+    Other,
+}
+
+impl Method {
+    fn parse(method: &str) -> Self {
+        match method {
+            "GET" => Method::Get,
+            "POST" => Method::Post,
+            "PUT" => Method::Put,
+            "DELETE" => Method::Delete,
+            _ => Method::Other,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    /// More data is needed, the headers or the body did not fully arrive yet.
+    Incomplete,
+    /// The request line or headers are malformed.
+    Invalid,
+    /// `Content-Length` announces a body that does not fit in [`MAX_REQUEST_SIZE`].
+    TooLarge,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request<'a> {
+    pub method: Method,
+    pub path: &'a str,
+    pub query: Option<&'a str>,
+    pub body: &'a [u8],
+}
+
+impl<'a> Request<'a> {
+    /// Value of the query parameter `name`, `+` and percent escapes are not decoded.
+    pub fn query_param(&self, name: &str) -> Option<&'a str> {
+        self.query?.split('&').find_map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (key == name).then_some(value)
+        })
+    }
+
+    pub fn body_str(&self) -> Option<&'a str> {
+        core::str::from_utf8(self.body).ok()
+    }
+}
+
+/// Parses a complete request from `buffer`, returns [`ParseError::Incomplete`] until the headers and the whole body
+/// announced by `Content-Length` are available. Requests that would not fit in [`MAX_REQUEST_SIZE`] are rejected with
+/// [`ParseError::TooLarge`] as soon as their headers arrived.
+pub fn parse_request(buffer: &[u8]) -> Result<Request<'_>, ParseError> {
+    let header_end = buffer.windows(4).position(|window| window == b"\r\n\r\n").ok_or(ParseError::Incomplete)?;
+    let head = core::str::from_utf8(&buffer[..header_end]).map_err(|_| ParseError::Invalid)?;
+    let mut lines = head.split("\r\n");
+
+    let request_line = lines.next().ok_or(ParseError::Invalid)?;
+    let mut parts = request_line.split(' ');
+    let method = Method::parse(parts.next().ok_or(ParseError::Invalid)?);
+    let target = parts.next().filter(|target| target.starts_with('/')).ok_or(ParseError::Invalid)?;
+    if !parts.next().is_some_and(|version| version.starts_with("HTTP/1.")) {
+        return Err(ParseError::Invalid);
+    }
+    let (path, query) = match target.split_once('?') {
+        Some((path, query)) => (path, Some(query)),
+        None => (target, None),
+    };
+
+    let mut content_length = 0;
+    for line in lines {
+        let (name, value) = line.split_once(':').ok_or(ParseError::Invalid)?;
+        if name.trim().eq_ignore_ascii_case("content-length") {
+            content_length = value.trim().parse::<usize>().map_err(|_| ParseError::Invalid)?;
+        }
+    }
+
+    let body_start = header_end + 4;
+    let body_end =
+        body_start.checked_add(content_length).filter(|end| *end <= MAX_REQUEST_SIZE).ok_or(ParseError::TooLarge)?;
+    let body = buffer.get(body_start..body_end).ok_or(ParseError::Incomplete)?;
+
+    Ok(Request { method, path, query, body })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusCode {
+    Ok,
+    BadRequest,
+    NotFound,
+    MethodNotAllowed,
+    PayloadTooLarge,
+    InternalServerError,
+    ServiceUnavailable,
+}
+
+impl StatusCode {
+    pub fn code(self) -> u16 {
+        match self {
+            StatusCode::Ok => 200,
+            StatusCode::BadRequest => 400,
+            StatusCode::NotFound => 404,
+            StatusCode::MethodNotAllowed => 405,
+            StatusCode::PayloadTooLarge => 413,
+            StatusCode::InternalServerError => 500,
+            StatusCode::ServiceUnavailable => 503,
+        }
+    }
+
+    pub fn reason(self) -> &'static str {
+        match self {
+            StatusCode::Ok => "OK",
+            StatusCode::BadRequest => "Bad Request",
+            StatusCode::NotFound => "Not Found",
+            StatusCode::MethodNotAllowed => "Method Not Allowed",
+            StatusCode::PayloadTooLarge => "Payload Too Large",
+            StatusCode::InternalServerError => "Internal Server Error",
+            StatusCode::ServiceUnavailable => "Service Unavailable",
+        }
+    }
+}
+
+pub struct Response<'a> {
+    pub status: StatusCode,
+    pub content_type: &'static str,
+    pub body: &'a [u8],
+}
+
+impl<'a> Response<'a> {
+    pub fn text(status: StatusCode, body: &'a str) -> Self {
+        Response { status, content_type: "text/plain", body: body.as_bytes() }
+    }
+
+    pub fn json(body: &'a [u8]) -> Self {
+        Response { status: StatusCode::Ok, content_type: "application/json", body }
+    }
+
+    /// Serializes the response, the connection is always closed after it.
+    pub fn write_to(&self, out: &mut Vec<u8>) {
+        let mut head = alloc::string::String::new();
+        write!(
+            head,
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            self.status.code(),
+            self.status.reason(),
+            self.content_type,
+            self.body.len()
+        )
+        .ok();
+        out.extend_from_slice(head.as_bytes());
+        out.extend_from_slice(self.body);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_get_with_query() {
+        let request = parse_request(b"GET /api/custom?name=weather&x=1 HTTP/1.1\r\nHost: rwtrix\r\n\r\n").unwrap();
+        assert_eq!(request.method, Method::Get);
+        assert_eq!(request.path, "/api/custom");
+        assert_eq!(request.query_param("name"), Some("weather"));
+        assert_eq!(request.query_param("x"), Some("1"));
+        assert_eq!(request.query_param("missing"), None);
+        assert!(request.body.is_empty());
+    }
+
+    #[test]
+    fn parses_post_body() {
+        let raw = b"POST /api/notify HTTP/1.1\r\ncontent-length: 15\r\n\r\n{\"text\":\"hi!\"}\n";
+        let request = parse_request(raw).unwrap();
+        assert_eq!(request.method, Method::Post);
+        assert_eq!(request.path, "/api/notify");
+        assert_eq!(request.query, None);
+        assert_eq!(request.body_str(), Some("{\"text\":\"hi!\"}\n"));
+    }
+
+    #[test]
+    fn waits_for_headers_and_body() {
+        assert_eq!(parse_request(b"POST /api/notify HTTP/1.1\r\nContent-Le"), Err(ParseError::Incomplete));
+        assert_eq!(
+            parse_request(b"POST /api/notify HTTP/1.1\r\nContent-Length: 10\r\n\r\n{\"te"),
+            Err(ParseError::Incomplete)
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_requests() {
+        assert_eq!(parse_request(b"GET\r\n\r\n"), Err(ParseError::Invalid));
+        assert_eq!(parse_request(b"GET api HTTP/1.1\r\n\r\n"), Err(ParseError::Invalid));
+        assert_eq!(parse_request(b"GET / SPDY\r\n\r\n"), Err(ParseError::Invalid));
+        assert_eq!(parse_request(b"GET / HTTP/1.1\r\nbroken header\r\n\r\n"), Err(ParseError::Invalid));
+        assert_eq!(parse_request(b"POST / HTTP/1.1\r\nContent-Length: x\r\n\r\n"), Err(ParseError::Invalid));
+    }
+
+    #[test]
+    fn rejects_bodies_larger_than_the_buffer() {
+        assert_eq!(
+            parse_request(b"POST /api/notify HTTP/1.1\r\nContent-Length: 4294967295\r\n\r\n"),
+            Err(ParseError::TooLarge)
+        );
+        let overflowing = alloc::format!("POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n", usize::MAX);
+        assert_eq!(parse_request(overflowing.as_bytes()), Err(ParseError::TooLarge));
+        // headers and body together have to fit
+        let head_len = "POST / HTTP/1.1\r\nContent-Length: 2048\r\n\r\n".len();
+        let too_large =
+            alloc::format!("POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n", MAX_REQUEST_SIZE - head_len + 1);
+        assert_eq!(parse_request(too_large.as_bytes()), Err(ParseError::TooLarge));
+        let fitting = alloc::format!("POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n", MAX_REQUEST_SIZE - head_len);
+        assert_eq!(parse_request(fitting.as_bytes()), Err(ParseError::Incomplete));
+    }
+
+    #[test]
+    fn writes_response() {
+        let mut out = Vec::new();
+        Response::json(b"{}").write_to(&mut out);
+        assert_eq!(
+            core::str::from_utf8(&out).unwrap(),
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{}"
+        );
+    }
+}
@@ -0,0 +1,259 @@
+//! In memory layers the display is composed of.
+//!
+//! Pages, overlays and the status pixels each draw into their own layer, the layers are blended from the bottom up
+//! into the frame that goes to the panel. Every pixel carries an alpha and every layer an opacity, so a translucent
+//! notification lets the page shine through and the status pixels no longer overwrite what the page drew.
+
+use core::convert::Infallible;
+
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+
+pub const WIDTH: usize = 32;
+pub const HEIGHT: usize = 8;
+
+/// The layers from the bottom to the top.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayerId {
+    /// Below the page, shows wherever the page leaves pixels transparent.
+    Background,
+    Page,
+    /// Menus, notifications and the ringing alarm.
+    Overlay,
+    Status,
+}
+
+impl LayerId {
+    pub const ALL: [LayerId; 4] = [LayerId::Background, LayerId::Page, LayerId::Overlay, LayerId::Status];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgba {
+    pub color: Rgb888,
+    /// 0 is fully transparent, 255 fully opaque.
+    pub alpha: u8,
+}
+
+impl Rgba {
+    pub const TRANSPARENT: Rgba = Rgba { color: Rgb888::BLACK, alpha: 0 };
+}
+
+/// Blends `top` with `alpha` over `bottom`.
+fn blend(bottom: Rgb888, top: Rgb888, alpha: u8) -> Rgb888 {
+    let mix =
+        |bottom: u8, top: u8| ((top as u32 * alpha as u32 + bottom as u32 * (255 - alpha as u32) + 127) / 255) as u8;
+    Rgb888::new(mix(bottom.r(), top.r()), mix(bottom.g(), top.g()), mix(bottom.b(), top.b()))
+}
+
+/// A framebuffer with an alpha per pixel, drawing into it makes the pixels opaque.
+pub struct Layer {
+    pixels: [Rgba; WIDTH * HEIGHT],
+    opacity: u8,
+}
+
+impl Default for Layer {
+    fn default() -> Self {
+        Layer::new()
+    }
+}
+
+impl Layer {
+    pub const fn new() -> Self {
+        Layer { pixels: [Rgba::TRANSPARENT; WIDTH * HEIGHT], opacity: 255 }
+    }
+
+    /// Makes every pixel transparent and the layer fully opaque again.
+    pub fn reset(&mut self) {
+        self.pixels.fill(Rgba::TRANSPARENT);
+        self.opacity = 255;
+    }
+
+    pub fn opacity(&self) -> u8 {
+        self.opacity
+    }
+
+    /// Scales the alpha of every pixel of the layer, used for fading it in and out.
+    pub fn set_opacity(&mut self, opacity: u8) {
+        self.opacity = opacity;
+    }
+
+    pub fn pixel(&self, point: Point) -> Option<Rgba> {
+        Self::index(point).map(|index| self.pixels[index])
+    }
+
+    pub fn set_pixel(&mut self, point: Point, pixel: Rgba) {
+        if let Some(index) = Self::index(point) {
+            self.pixels[index] = pixel;
+        }
+    }
+
+    /// A target that draws into this layer with the given alpha instead of opaque pixels.
+    pub fn translucent(&mut self, alpha: u8) -> Translucent<'_> {
+        Translucent { layer: self, alpha }
+    }
+
+    fn index(point: Point) -> Option<usize> {
+        let (x, y) = (usize::try_from(point.x).ok()?, usize::try_from(point.y).ok()?);
+        (x < WIDTH && y < HEIGHT).then_some(y * WIDTH + x)
+    }
+}
+
+impl OriginDimensions for Layer {
+    fn size(&self) -> Size {
+        Size::new(WIDTH as u32, HEIGHT as u32)
+    }
+}
+
+impl DrawTarget for Layer {
+    type Color = Rgb888;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            self.set_pixel(point, Rgba { color, alpha: 255 });
+        }
+        Ok(())
+    }
+}
+
+/// Draws into a [`Layer`] with a fixed alpha.
+pub struct Translucent<'a> {
+    layer: &'a mut Layer,
+    alpha: u8,
+}
+
+impl OriginDimensions for Translucent<'_> {
+    fn size(&self) -> Size {
+        self.layer.size()
+    }
+}
+
+impl DrawTarget for Translucent<'_> {
+    type Color = Rgb888;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            self.layer.set_pixel(point, Rgba { color, alpha: self.alpha });
+        }
+        Ok(())
+    }
+}
+
+/// All layers of the display, composed on top of black.
+#[derive(Default)]
+pub struct LayerStack {
+    layers: [Layer; 4],
+}
+
+impl LayerStack {
+    pub const fn new() -> Self {
+        LayerStack { layers: [Layer::new(), Layer::new(), Layer::new(), Layer::new()] }
+    }
+
+    pub fn layer(&self, id: LayerId) -> &Layer {
+        &self.layers[id.index()]
+    }
+
+    pub fn layer_mut(&mut self, id: LayerId) -> &mut Layer {
+        &mut self.layers[id.index()]
+    }
+
+    /// Clears every layer before the next frame is drawn.
+    pub fn reset(&mut self) {
+        self.layers.iter_mut().for_each(Layer::reset);
+    }
+
+    /// Final color of a pixel with all layers blended.
+    pub fn pixel(&self, point: Point) -> Rgb888 {
+        self.layers.iter().fold(Rgb888::BLACK, |bottom, layer| match layer.pixel(point) {
+            Some(Rgba { color, alpha }) => {
+                let alpha = (alpha as u32 * layer.opacity as u32 / 255) as u8;
+                match alpha {
+                    0 => bottom,
+                    255 => color,
+                    alpha => blend(bottom, color, alpha),
+                }
+            }
+            None => bottom,
+        })
+    }
+
+    /// The composed frame, ready to be drawn into the panel.
+    pub fn compose(&self) -> impl Iterator<Item = Pixel<Rgb888>> + '_ {
+        (0..HEIGHT as i32)
+            .flat_map(|y| (0..WIDTH as i32).map(move |x| Point::new(x, y)))
+            .map(|point| Pixel(point, self.pixel(point)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics::{
+        mock_display::MockDisplay,
+        primitives::{PrimitiveStyle, Rectangle},
+    };
+
+    use super::*;
+
+    #[test]
+    fn upper_layers_only_cover_what_they_draw() {
+        let mut stack = LayerStack::new();
+        stack.layer_mut(LayerId::Page).clear(Rgb888::RED).ok();
+        Pixel(Point::new(31, 0), Rgb888::GREEN).draw(stack.layer_mut(LayerId::Status)).ok();
+        Rectangle::new(Point::new(0, 0), Size::new(2, 8))
+            .into_styled(PrimitiveStyle::with_fill(Rgb888::BLUE))
+            .draw(stack.layer_mut(LayerId::Overlay))
+            .ok();
+
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+        display.draw_iter(stack.compose()).ok();
+        display.assert_pattern(&[
+            "BBRRRRRRRRRRRRRRRRRRRRRRRRRRRRRG",
+            "BBRRRRRRRRRRRRRRRRRRRRRRRRRRRRRR",
+            "BBRRRRRRRRRRRRRRRRRRRRRRRRRRRRRR",
+            "BBRRRRRRRRRRRRRRRRRRRRRRRRRRRRRR",
+            "BBRRRRRRRRRRRRRRRRRRRRRRRRRRRRRR",
+            "BBRRRRRRRRRRRRRRRRRRRRRRRRRRRRRR",
+            "BBRRRRRRRRRRRRRRRRRRRRRRRRRRRRRR",
+            "BBRRRRRRRRRRRRRRRRRRRRRRRRRRRRRR",
+        ]);
+
+        stack.reset();
+        assert_eq!(stack.pixel(Point::new(31, 0)), Rgb888::BLACK);
+    }
+
+    #[test]
+    fn blends_pixel_alpha_and_layer_opacity() {
+        let mut stack = LayerStack::new();
+        stack.layer_mut(LayerId::Page).clear(Rgb888::new(200, 0, 0)).ok();
+        stack.layer_mut(LayerId::Overlay).translucent(128).clear(Rgb888::new(0, 0, 100)).ok();
+        assert_eq!(stack.pixel(Point::new(5, 5)), Rgb888::new(100, 0, 50));
+
+        // half the opacity halves the alpha of every pixel of the layer
+        stack.layer_mut(LayerId::Overlay).clear(Rgb888::new(0, 0, 100)).ok();
+        stack.layer_mut(LayerId::Overlay).set_opacity(128);
+        assert_eq!(stack.pixel(Point::new(5, 5)), Rgb888::new(100, 0, 50));
+
+        stack.layer_mut(LayerId::Overlay).set_opacity(0);
+        assert_eq!(stack.pixel(Point::new(5, 5)), Rgb888::new(200, 0, 0));
+    }
+
+    #[test]
+    fn ignores_pixels_outside_the_panel() {
+        let mut layer = Layer::new();
+        layer.draw_iter([Pixel(Point::new(-1, 0), Rgb888::RED), Pixel(Point::new(32, 7), Rgb888::RED)]).ok();
+        assert_eq!(layer.pixel(Point::new(-1, 0)), None);
+        assert!(layer.pixels.iter().all(|pixel| *pixel == Rgba::TRANSPARENT));
+    }
+}
@@ -0,0 +1,26 @@
+//! Hardware independent parts of the rwtrix firmware.
+//!
+//! Everything in here builds for the host as well, run the tests with
+//! `cargo test --target x86_64-unknown-linux-gnu` (or your host triple) from this directory.
+#![cfg_attr(not(test), no_std)]
+
+extern crate alloc;
+
+pub mod alarm;
+pub mod awtrix;
+pub mod battery;
+pub mod chart;
+pub mod clock;
+pub mod clock_face;
+pub mod countdown;
+pub mod date;
+pub mod entity_page;
+pub mod frame;
+pub mod games;
+pub mod http;
+pub mod layers;
+pub mod night;
+pub mod pomodoro;
+pub mod realtime;
+pub mod snapshot;
+pub mod stopwatch;
@@ -0,0 +1,326 @@
+//! Night mode: when the display goes dark and what it shows meanwhile.
+//!
+//! Night starts inside a daily time window, between sunset and sunrise, or once the room is darker than a light
+//! threshold, whichever comes first. It can also be forced from Home Assistant.
+
+use alloc::{string::String, vec::Vec};
+use core::fmt::Write as _;
+
+use embedded_graphics::pixelcolor::{Rgb888, RgbColor as _};
+use serde::{Deserialize, Serialize};
+
+/// Longest time a button press keeps the display awake at night.
+pub const MAX_WAKE_SECS: u16 = 300;
+/// Percent the light has to rise above the threshold before night ends, so the panel lighting up the room does not
+/// flip the mode back and forth.
+const LIGHT_HYSTERESIS: f32 = 5.0;
+const MINUTES_PER_DAY: i32 = 24 * 60;
+
+/// Time window of the night.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NightWindow {
+    /// Only the light threshold or forcing starts the night.
+    #[default]
+    Off,
+    /// Between the start and the end minute of the day.
+    Schedule,
+    /// Between sunset and sunrise at the configured location.
+    Sun,
+}
+
+impl NightWindow {
+    pub const ALL: [NightWindow; 3] = [NightWindow::Off, NightWindow::Schedule, NightWindow::Sun];
+    pub const NAMES: [&'static str; 3] = ["off", "schedule", "sun"];
+
+    pub fn from_index(index: usize) -> Option<Self> {
+        Self::ALL.get(index).copied()
+    }
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// What the display does at night.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NightAction {
+    /// Keeps rotating the night pages at the night brightness.
+    #[default]
+    Dim,
+    /// Only a red clock at the night brightness, without status pixels.
+    Clock,
+    /// Panel fully off.
+    Off,
+}
+
+impl NightAction {
+    pub const ALL: [NightAction; 3] = [NightAction::Dim, NightAction::Clock, NightAction::Off];
+    pub const NAMES: [&'static str; 3] = ["dim", "clock", "off"];
+
+    pub fn from_index(index: usize) -> Option<Self> {
+        Self::ALL.get(index).copied()
+    }
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NightSettings {
+    /// Night regardless of the window and the light.
+    pub forced: bool,
+    pub window: NightWindow,
+    /// Minute of the day the scheduled night starts.
+    pub start_minute: u16,
+    /// Minute of the day the scheduled night ends, before the start when the night spans midnight.
+    pub end_minute: u16,
+    /// Location used for sunset and sunrise, in degrees north and east.
+    pub latitude: f32,
+    pub longitude: f32,
+    /// Ambient light in percent below which it is night, 0 ignores the light sensor.
+    pub light_threshold: u8,
+    pub action: NightAction,
+    pub brightness: u8,
+    /// Pages kept by the dim action, empty keeps them all.
+    pub pages: Vec<String>,
+    /// How long a button press wakes the display up, 0 keeps it dark.
+    pub wake_secs: u16,
+}
+
+impl Default for NightSettings {
+    fn default() -> Self {
+        NightSettings {
+            forced: false,
+            window: NightWindow::Off,
+            start_minute: 22 * 60,
+            end_minute: 7 * 60,
+            latitude: 52.23,
+            longitude: 21.01,
+            light_threshold: 0,
+            action: NightAction::Dim,
+            brightness: 1,
+            pages: Vec::new(),
+            wake_secs: 15,
+        }
+    }
+}
+
+impl NightSettings {
+    /// Whether `minute` of the day falls into the night window, `sun` is today's sunrise and sunset minute.
+    pub fn in_window(&self, minute: u16, sun: Option<(u16, u16)>) -> bool {
+        match self.window {
+            NightWindow::Off => false,
+            NightWindow::Schedule => in_range(minute, self.start_minute, self.end_minute),
+            // no sunrise or sunset during the polar day and night, the window is left to the light sensor then
+            NightWindow::Sun => sun.is_some_and(|(sunrise, sunset)| in_range(minute, sunset, sunrise)),
+        }
+    }
+
+    /// Whether the dim action keeps rotating through the page.
+    pub fn shows_page(&self, name: &str) -> bool {
+        self.pages.is_empty() || self.pages.iter().any(|page| page == name)
+    }
+
+    /// Formats the schedule like `22:00-07:00`.
+    pub fn write_schedule(&self, out: &mut String) {
+        let [start, end] = [self.start_minute, self.end_minute];
+        write!(out, "{:02}:{:02}-{:02}:{:02}", start / 60, start % 60, end / 60, end % 60).ok();
+    }
+
+    /// Formats the location like `52.23,21.01`.
+    pub fn write_location(&self, out: &mut String) {
+        write!(out, "{:.2},{:.2}", self.latitude, self.longitude).ok();
+    }
+
+    /// Formats the night pages as a comma separated list.
+    pub fn write_pages(&self, out: &mut String) {
+        for (i, page) in self.pages.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            out.push_str(page);
+        }
+    }
+}
+
+/// Start <= minute < end, wrapping around midnight when the end comes first.
+fn in_range(minute: u16, start: u16, end: u16) -> bool {
+    if start <= end {
+        (start..end).contains(&minute)
+    } else {
+        minute >= start || minute < end
+    }
+}
+
+fn parse_minute(text: &str) -> Option<u16> {
+    let (hour, minute) = text.trim().split_once(':')?;
+    let (hour, minute) = (hour.parse::<u16>().ok()?, minute.parse::<u16>().ok()?);
+    (hour < 24 && minute < 60).then_some(hour * 60 + minute)
+}
+
+/// Parses `22:00-07:00` into the start and end minute of the day.
+pub fn parse_schedule(text: &str) -> Option<(u16, u16)> {
+    let (start, end) = text.split_once('-')?;
+    Some((parse_minute(start)?, parse_minute(end)?))
+}
+
+/// Parses `52.23,21.01` into latitude and longitude.
+pub fn parse_location(text: &str) -> Option<(f32, f32)> {
+    let (latitude, longitude) = text.split_once(',')?;
+    let (latitude, longitude) = (latitude.trim().parse::<f32>().ok()?, longitude.trim().parse::<f32>().ok()?);
+    ((-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude)).then_some((latitude, longitude))
+}
+
+/// Parses a comma separated page list, blanks are skipped.
+pub fn parse_pages(text: &str) -> Vec<String> {
+    text.split(',').map(str::trim).filter(|page| !page.is_empty()).map(String::from).collect()
+}
+
+/// Local minute of the day of sunrise and sunset, `None` while the sun stays above or below the horizon all day.
+///
+/// Uses the NOAA approximation of the equation of time and the declination, good to a few minutes.
+pub fn sun_times(day_of_year: u16, latitude: f32, longitude: f32, utc_offset_minutes: i32) -> Option<(u16, u16)> {
+    use core::f32::consts::PI;
+
+    let gamma = 2.0 * PI / 365.0 * (day_of_year as f32 - 1.0);
+    let (sin1, cos1) = libm::sincosf(gamma);
+    let (sin2, cos2) = libm::sincosf(2.0 * gamma);
+    let (sin3, cos3) = libm::sincosf(3.0 * gamma);
+    let equation_of_time = 229.18 * (0.000075 + 0.001868 * cos1 - 0.032077 * sin1 - 0.014615 * cos2 - 0.040849 * sin2);
+    let declination = 0.006918 - 0.399912 * cos1 + 0.070257 * sin1 - 0.006758 * cos2 + 0.000907 * sin2
+        - 0.002697 * cos3
+        + 0.00148 * sin3;
+
+    // the sun's upper edge touches the horizon 0.833 degrees below it because of refraction
+    let latitude = latitude.to_radians();
+    let cos_hour_angle = libm::cosf(90.833f32.to_radians()) / (libm::cosf(latitude) * libm::cosf(declination))
+        - libm::tanf(latitude) * libm::tanf(declination);
+    if !(-1.0..=1.0).contains(&cos_hour_angle) {
+        return None;
+    }
+    let hour_angle = libm::acosf(cos_hour_angle).to_degrees();
+
+    let local = |degrees: f32| {
+        let utc = 720.0 - 4.0 * degrees - equation_of_time;
+        (libm::roundf(utc) as i32 + utc_offset_minutes).rem_euclid(MINUTES_PER_DAY) as u16
+    };
+    Some((local(longitude + hour_angle), local(longitude - hour_angle)))
+}
+
+/// Tracks the light sensor with some hysteresis and combines it with the window.
+#[derive(Debug, Default)]
+pub struct NightDetector {
+    dark: bool,
+}
+
+impl NightDetector {
+    pub fn new() -> Self {
+        NightDetector { dark: false }
+    }
+
+    /// Whether it is night at `minute` of the day with the ambient light at `light_percent`.
+    pub fn update(
+        &mut self,
+        settings: &NightSettings,
+        minute: u16,
+        sun: Option<(u16, u16)>,
+        light_percent: f32,
+    ) -> bool {
+        let threshold = settings.light_threshold as f32;
+        self.dark = match settings.light_threshold {
+            0 => false,
+            _ if self.dark => light_percent < threshold + LIGHT_HYSTERESIS,
+            _ => light_percent < threshold,
+        };
+        settings.forced || self.dark || settings.in_window(minute, sun)
+    }
+}
+
+/// Turns a color into red of the same intensity, red light disturbs the sleep the least.
+pub fn to_red(color: Rgb888) -> Rgb888 {
+    Rgb888::new(color.r().max(color.g()).max(color.b()), 0, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(actual: u16, hour: u16, minute: u16) {
+        let expected = hour * 60 + minute;
+        assert!(actual.abs_diff(expected) <= 5, "{:02}:{:02}", actual / 60, actual % 60);
+    }
+
+    #[test]
+    fn computes_sunrise_and_sunset() {
+        // Warsaw on the solstices, summer time in June
+        let (sunrise, sunset) = sun_times(172, 52.23, 21.01, 120).unwrap();
+        assert_near(sunrise, 4, 14);
+        assert_near(sunset, 21, 1);
+        let (sunrise, sunset) = sun_times(355, 52.23, 21.01, 60).unwrap();
+        assert_near(sunrise, 7, 43);
+        assert_near(sunset, 15, 25);
+
+        // no night in Longyearbyen in June
+        assert_eq!(sun_times(172, 78.22, 15.65, 120), None);
+    }
+
+    #[test]
+    fn windows_span_midnight() {
+        let mut settings = NightSettings { window: NightWindow::Schedule, ..Default::default() };
+        assert!(settings.in_window(23 * 60, None));
+        assert!(settings.in_window(6 * 60 + 59, None));
+        assert!(!settings.in_window(7 * 60, None));
+        assert!(!settings.in_window(12 * 60, None));
+
+        (settings.start_minute, settings.end_minute) = (60, 5 * 60);
+        assert!(settings.in_window(3 * 60, None));
+        assert!(!settings.in_window(23 * 60, None));
+
+        settings.window = NightWindow::Sun;
+        let sun = Some((6 * 60, 20 * 60));
+        assert!(settings.in_window(21 * 60, sun));
+        assert!(!settings.in_window(12 * 60, sun));
+        assert!(!settings.in_window(21 * 60, None));
+    }
+
+    #[test]
+    fn light_threshold_has_hysteresis() {
+        let settings = NightSettings { light_threshold: 10, ..Default::default() };
+        let mut detector = NightDetector::new();
+        assert!(!detector.update(&settings, 720, None, 12.0));
+        assert!(detector.update(&settings, 720, None, 8.0));
+        // the panel lights up the room a little
+        assert!(detector.update(&settings, 720, None, 13.0));
+        assert!(!detector.update(&settings, 720, None, 16.0));
+
+        let forced = NightSettings { forced: true, ..Default::default() };
+        assert!(detector.update(&forced, 720, None, 100.0));
+    }
+
+    #[test]
+    fn parses_settings_texts() {
+        assert_eq!(parse_schedule("22:30-6:45"), Some((22 * 60 + 30, 6 * 60 + 45)));
+        assert_eq!(parse_schedule("24:00-07:00"), None);
+        assert_eq!(parse_location("52.23, 21.01"), Some((52.23, 21.01)));
+        assert_eq!(parse_location("95,0"), None);
+        assert_eq!(parse_pages(" time, ,date"), ["time", "date"]);
+
+        let settings = NightSettings { pages: parse_pages("time,date"), ..Default::default() };
+        let mut out = String::new();
+        settings.write_schedule(&mut out);
+        out.push(' ');
+        settings.write_location(&mut out);
+        out.push(' ');
+        settings.write_pages(&mut out);
+        assert_eq!(out, "22:00-07:00 52.23,21.01 time,date");
+        assert!(settings.shows_page("date"));
+        assert!(!settings.shows_page("timer"));
+    }
+
+    #[test]
+    fn recolors_to_red() {
+        assert_eq!(to_red(Rgb888::new(20, 200, 90)), Rgb888::new(200, 0, 0));
+        assert_eq!(to_red(Rgb888::BLACK), Rgb888::BLACK);
+    }
+}
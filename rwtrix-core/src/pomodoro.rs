@@ -0,0 +1,280 @@
+//! Pomodoro timer: work sessions separated by short breaks, with a long break after every few sessions.
+//!
+//! Time is passed in as a monotonic millisecond counter so the engine can be driven by any clock.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PomodoroSettings {
+    pub work_secs: u32,
+    pub short_break_secs: u32,
+    pub long_break_secs: u32,
+    /// Work sessions before a long break replaces the short one.
+    pub sessions_before_long_break: u8,
+}
+
+impl Default for PomodoroSettings {
+    fn default() -> Self {
+        PomodoroSettings {
+            work_secs: 25 * 60,
+            short_break_secs: 5 * 60,
+            long_break_secs: 15 * 60,
+            sessions_before_long_break: 4,
+        }
+    }
+}
+
+impl PomodoroSettings {
+    pub fn duration_secs(&self, phase: Phase) -> u32 {
+        match phase {
+            Phase::Work => self.work_secs,
+            Phase::ShortBreak => self.short_break_secs,
+            Phase::LongBreak => self.long_break_secs,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Work,
+    ShortBreak,
+    LongBreak,
+}
+
+impl Phase {
+    pub fn name(self) -> &'static str {
+        match self {
+            Phase::Work => "work",
+            Phase::ShortBreak => "short_break",
+            Phase::LongBreak => "long_break",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RunState {
+    /// The phase did not start yet, or was stopped.
+    Stopped,
+    Running {
+        ends_at_ms: u64,
+    },
+    Paused {
+        remaining_ms: u64,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pomodoro {
+    settings: PomodoroSettings,
+    phase: Phase,
+    /// Work sessions completed since the last long break.
+    sessions: u8,
+    /// Work sessions completed since the timer was last stopped.
+    completed: u32,
+    run: RunState,
+}
+
+impl Pomodoro {
+    pub fn new(settings: PomodoroSettings) -> Self {
+        Pomodoro { settings, phase: Phase::Work, sessions: 0, completed: 0, run: RunState::Stopped }
+    }
+
+    pub fn settings(&self) -> &PomodoroSettings {
+        &self.settings
+    }
+
+    /// New phase lengths apply from the next phase on, a stopped timer picks them up right away.
+    pub fn set_settings(&mut self, settings: PomodoroSettings) {
+        self.settings = settings;
+    }
+
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    pub fn completed_sessions(&self) -> u32 {
+        self.completed
+    }
+
+    /// Work sessions done towards the next long break.
+    pub fn sessions_in_cycle(&self) -> u8 {
+        self.sessions
+    }
+
+    pub fn is_running(&self) -> bool {
+        matches!(self.run, RunState::Running { .. })
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.run == RunState::Stopped
+    }
+
+    pub fn remaining_ms(&self, now_ms: u64) -> u64 {
+        match self.run {
+            RunState::Stopped => self.phase_duration_ms(),
+            RunState::Running { ends_at_ms } => ends_at_ms.saturating_sub(now_ms),
+            RunState::Paused { remaining_ms } => remaining_ms,
+        }
+    }
+
+    /// Starts the current phase, or resumes it when paused.
+    pub fn start(&mut self, now_ms: u64) {
+        let remaining_ms = self.remaining_ms(now_ms);
+        self.run = RunState::Running { ends_at_ms: now_ms + remaining_ms };
+    }
+
+    pub fn pause(&mut self, now_ms: u64) {
+        if self.is_running() {
+            self.run = RunState::Paused { remaining_ms: self.remaining_ms(now_ms) };
+        }
+    }
+
+    pub fn toggle(&mut self, now_ms: u64) {
+        if self.is_running() {
+            self.pause(now_ms);
+        } else {
+            self.start(now_ms);
+        }
+    }
+
+    /// Stops the timer and goes back to the first work session.
+    pub fn stop(&mut self) {
+        self.phase = Phase::Work;
+        self.sessions = 0;
+        self.completed = 0;
+        self.run = RunState::Stopped;
+    }
+
+    /// Ends the current phase early, the next one starts right away if the timer was running.
+    pub fn skip(&mut self, now_ms: u64) {
+        let running = self.is_running();
+        self.advance();
+        self.run = RunState::Stopped;
+        if running {
+            self.start(now_ms);
+        }
+    }
+
+    /// Jumps to `phase` and starts it.
+    pub fn start_phase(&mut self, phase: Phase, now_ms: u64) {
+        self.phase = phase;
+        self.run = RunState::Stopped;
+        self.start(now_ms);
+    }
+
+    /// Advances to the next phase once the running one is over, returns the phase that just ended.
+    pub fn tick(&mut self, now_ms: u64) -> Option<Phase> {
+        let RunState::Running { ends_at_ms } = self.run else {
+            return None;
+        };
+        if now_ms < ends_at_ms {
+            return None;
+        }
+
+        let ended = self.phase;
+        self.advance();
+        // the next phase starts when the previous one was due, not when the tick happened to run
+        self.run = RunState::Running { ends_at_ms: ends_at_ms + self.phase_duration_ms() };
+        Some(ended)
+    }
+
+    fn advance(&mut self) {
+        self.phase = match self.phase {
+            Phase::Work => {
+                self.completed += 1;
+                self.sessions += 1;
+                if self.sessions >= self.settings.sessions_before_long_break.max(1) {
+                    self.sessions = 0;
+                    Phase::LongBreak
+                } else {
+                    Phase::ShortBreak
+                }
+            }
+            Phase::ShortBreak | Phase::LongBreak => Phase::Work,
+        };
+    }
+
+    fn phase_duration_ms(&self) -> u64 {
+        self.settings.duration_secs(self.phase) as u64 * 1000
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> PomodoroSettings {
+        PomodoroSettings { work_secs: 10, short_break_secs: 2, long_break_secs: 5, sessions_before_long_break: 2 }
+    }
+
+    #[test]
+    fn counts_down_while_running() {
+        let mut pomodoro = Pomodoro::new(settings());
+        assert_eq!(pomodoro.remaining_ms(0), 10_000);
+        pomodoro.start(1_000);
+        assert_eq!(pomodoro.remaining_ms(4_000), 7_000);
+        assert_eq!(pomodoro.tick(10_999), None);
+        assert_eq!(pomodoro.phase(), Phase::Work);
+    }
+
+    #[test]
+    fn cycles_through_phases() {
+        let mut pomodoro = Pomodoro::new(settings());
+        pomodoro.start(0);
+
+        assert_eq!(pomodoro.tick(10_000), Some(Phase::Work));
+        assert_eq!(pomodoro.phase(), Phase::ShortBreak);
+        assert_eq!(pomodoro.tick(12_000), Some(Phase::ShortBreak));
+        assert_eq!(pomodoro.phase(), Phase::Work);
+        assert_eq!(pomodoro.tick(22_000), Some(Phase::Work));
+        assert_eq!(pomodoro.phase(), Phase::LongBreak);
+        assert_eq!(pomodoro.completed_sessions(), 2);
+        assert_eq!(pomodoro.tick(27_000), Some(Phase::LongBreak));
+        assert_eq!(pomodoro.phase(), Phase::Work);
+        assert!(pomodoro.is_running());
+    }
+
+    #[test]
+    fn late_ticks_do_not_drift() {
+        let mut pomodoro = Pomodoro::new(settings());
+        pomodoro.start(0);
+        assert_eq!(pomodoro.tick(10_500), Some(Phase::Work));
+        assert_eq!(pomodoro.remaining_ms(10_500), 1_500);
+    }
+
+    #[test]
+    fn pauses_and_resumes() {
+        let mut pomodoro = Pomodoro::new(settings());
+        pomodoro.start(0);
+        pomodoro.pause(3_000);
+        assert!(!pomodoro.is_running());
+        assert_eq!(pomodoro.remaining_ms(60_000), 7_000);
+        assert_eq!(pomodoro.tick(60_000), None);
+        pomodoro.toggle(60_000);
+        assert_eq!(pomodoro.remaining_ms(61_000), 6_000);
+    }
+
+    #[test]
+    fn skips_and_stops() {
+        let mut pomodoro = Pomodoro::new(settings());
+        pomodoro.start(0);
+        pomodoro.skip(1_000);
+        assert_eq!(pomodoro.phase(), Phase::ShortBreak);
+        assert_eq!(pomodoro.remaining_ms(1_000), 2_000);
+        assert!(pomodoro.is_running());
+
+        pomodoro.stop();
+        assert!(pomodoro.is_stopped());
+        assert_eq!(pomodoro.phase(), Phase::Work);
+        assert_eq!(pomodoro.completed_sessions(), 0);
+        assert_eq!(pomodoro.remaining_ms(5_000), 10_000);
+    }
+
+    #[test]
+    fn starts_a_given_phase() {
+        let mut pomodoro = Pomodoro::new(settings());
+        pomodoro.start_phase(Phase::LongBreak, 100);
+        assert_eq!(pomodoro.phase(), Phase::LongBreak);
+        assert_eq!(pomodoro.remaining_ms(100), 5_000);
+    }
+}
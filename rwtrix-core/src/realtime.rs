@@ -0,0 +1,205 @@
+//! Realtime pixel protocols, DDP (as sent by xLights, WLED and Hyperion) and E1.31 / sACN.
+//!
+//! Both protocols carry plain 8 bit RGB channel data, pixel `n` of the stream maps to channels `3n..3n+3`.
+
+pub const DDP_PORT: u16 = 4048;
+pub const E131_PORT: u16 = 5568;
+/// Channels used per E1.31 universe, 170 full RGB pixels like WLED and xLights do by default.
+pub const E131_CHANNELS_PER_UNIVERSE: usize = 510;
+
+const DDP_HEADER_LEN: usize = 10;
+const DDP_TIMECODE_LEN: usize = 4;
+const DDP_VERSION_MASK: u8 = 0xC0;
+const DDP_VERSION_1: u8 = 0x40;
+const DDP_FLAG_TIMECODE: u8 = 0x10;
+const DDP_FLAG_QUERY: u8 = 0x02;
+const DDP_FLAG_PUSH: u8 = 0x01;
+const DDP_ID_STATUS: u8 = 251;
+
+const E131_ACN_ID: &[u8; 12] = b"ASC-E1.17\0\0\0";
+const E131_VECTOR_ROOT_DATA: u32 = 0x0000_0004;
+const E131_VECTOR_FRAMING_DATA: u32 = 0x0000_0002;
+const E131_VECTOR_DMP_SET_PROPERTY: u8 = 0x02;
+const E131_OPTION_PREVIEW: u8 = 0x80;
+const E131_OPTION_TERMINATED: u8 = 0x40;
+const E131_DATA_OFFSET: usize = 126;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PacketError {
+    TooShort,
+    /// Not a packet of the expected protocol.
+    InvalidHeader,
+    /// Valid, but nothing the display has to act on (queries, preview data, other universes...).
+    Ignored,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Packet<'a> {
+    /// Channel data starting at byte `offset` of the frame. The frame should be shown once `push` is set.
+    Data { offset: usize, data: &'a [u8], push: bool },
+    /// The sender stopped streaming, the display can go back to its pages right away.
+    Terminate,
+}
+
+pub fn parse_ddp(packet: &[u8]) -> Result<Packet<'_>, PacketError> {
+    if packet.len() < DDP_HEADER_LEN {
+        return Err(PacketError::TooShort);
+    }
+    let flags = packet[0];
+    if flags & DDP_VERSION_MASK != DDP_VERSION_1 {
+        return Err(PacketError::InvalidHeader);
+    }
+    if flags & DDP_FLAG_QUERY != 0 || packet[3] >= DDP_ID_STATUS {
+        return Err(PacketError::Ignored);
+    }
+
+    let offset = u32::from_be_bytes([packet[4], packet[5], packet[6], packet[7]]) as usize;
+    let len = u16::from_be_bytes([packet[8], packet[9]]) as usize;
+    let start = if flags & DDP_FLAG_TIMECODE != 0 { DDP_HEADER_LEN + DDP_TIMECODE_LEN } else { DDP_HEADER_LEN };
+    let data = packet.get(start..start + len).ok_or(PacketError::TooShort)?;
+
+    Ok(Packet::Data { offset, data, push: flags & DDP_FLAG_PUSH != 0 })
+}
+
+/// Parses an E1.31 data packet, `start_universe` is the universe holding the first pixel. A frame of `frame_len`
+/// channels spans several universes, it is pushed with the universe holding its last channel so half updated frames
+/// are never shown.
+pub fn parse_e131(packet: &[u8], start_universe: u16, frame_len: usize) -> Result<Packet<'_>, PacketError> {
+    if packet.len() < E131_DATA_OFFSET {
+        return Err(PacketError::TooShort);
+    }
+    let u16_at = |at: usize| u16::from_be_bytes([packet[at], packet[at + 1]]);
+    let u32_at = |at: usize| u32::from_be_bytes([packet[at], packet[at + 1], packet[at + 2], packet[at + 3]]);
+
+    if u16_at(0) != 0x0010 || &packet[4..16] != E131_ACN_ID || u32_at(18) != E131_VECTOR_ROOT_DATA {
+        return Err(PacketError::InvalidHeader);
+    }
+    if u32_at(40) != E131_VECTOR_FRAMING_DATA || packet[117] != E131_VECTOR_DMP_SET_PROPERTY {
+        return Err(PacketError::InvalidHeader);
+    }
+
+    let options = packet[112];
+    if options & E131_OPTION_TERMINATED != 0 {
+        return Ok(Packet::Terminate);
+    }
+    let universe = u16_at(113);
+    // start code 0 is dimmer data, everything else (text, system information...) is of no use here
+    if options & E131_OPTION_PREVIEW != 0 || universe < start_universe || packet[125] != 0 {
+        return Err(PacketError::Ignored);
+    }
+
+    // the property count includes the start code
+    let channels = (u16_at(123) as usize).checked_sub(1).ok_or(PacketError::InvalidHeader)?;
+    let data = packet.get(E131_DATA_OFFSET..E131_DATA_OFFSET + channels).ok_or(PacketError::TooShort)?;
+    let data = &data[..data.len().min(E131_CHANNELS_PER_UNIVERSE)];
+    let offset = (universe - start_universe) as usize * E131_CHANNELS_PER_UNIVERSE;
+    if offset >= frame_len {
+        return Err(PacketError::Ignored);
+    }
+
+    Ok(Packet::Data { offset, data, push: offset + E131_CHANNELS_PER_UNIVERSE >= frame_len })
+}
+
+/// Copies channel data into an RGB `frame`, whatever falls outside of it is dropped. Returns the number of channels
+/// written.
+pub fn apply(frame: &mut [u8], offset: usize, data: &[u8]) -> usize {
+    let Some(target) = frame.get_mut(offset..) else {
+        return 0;
+    };
+    let len = target.len().min(data.len());
+    target[..len].copy_from_slice(&data[..len]);
+    len
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ddp(flags: u8, offset: u32, data: &[u8]) -> Vec<u8> {
+        let mut packet = vec![flags, 1, 0x0B, 1];
+        packet.extend_from_slice(&offset.to_be_bytes());
+        packet.extend_from_slice(&(data.len() as u16).to_be_bytes());
+        packet.extend_from_slice(data);
+        packet
+    }
+
+    fn e131(universe: u16, options: u8, data: &[u8]) -> Vec<u8> {
+        let mut packet = vec![0u8; E131_DATA_OFFSET];
+        packet[0..2].copy_from_slice(&0x0010u16.to_be_bytes());
+        packet[4..16].copy_from_slice(E131_ACN_ID);
+        packet[18..22].copy_from_slice(&E131_VECTOR_ROOT_DATA.to_be_bytes());
+        packet[40..44].copy_from_slice(&E131_VECTOR_FRAMING_DATA.to_be_bytes());
+        packet[112] = options;
+        packet[113..115].copy_from_slice(&universe.to_be_bytes());
+        packet[117] = E131_VECTOR_DMP_SET_PROPERTY;
+        packet[118] = 0xA1;
+        packet[121..123].copy_from_slice(&1u16.to_be_bytes());
+        packet[123..125].copy_from_slice(&(data.len() as u16 + 1).to_be_bytes());
+        packet.extend_from_slice(data);
+        packet
+    }
+
+    #[test]
+    fn parses_ddp() {
+        let packet = ddp(0x41, 6, &[1, 2, 3]);
+        assert_eq!(parse_ddp(&packet), Ok(Packet::Data { offset: 6, data: &[1, 2, 3], push: true }));
+
+        let packet = ddp(0x40, 0, &[9; 4]);
+        assert_eq!(parse_ddp(&packet), Ok(Packet::Data { offset: 0, data: &[9; 4], push: false }));
+    }
+
+    #[test]
+    fn parses_ddp_with_timecode() {
+        let mut packet = ddp(0x51, 0, &[]);
+        packet[8..10].copy_from_slice(&3u16.to_be_bytes());
+        packet.extend_from_slice(&[0xAA; DDP_TIMECODE_LEN]);
+        packet.extend_from_slice(&[4, 5, 6]);
+        assert_eq!(parse_ddp(&packet), Ok(Packet::Data { offset: 0, data: &[4, 5, 6], push: true }));
+    }
+
+    #[test]
+    fn rejects_bad_ddp() {
+        assert_eq!(parse_ddp(&[0x41, 0, 0]), Err(PacketError::TooShort));
+        assert_eq!(parse_ddp(&ddp(0x81, 0, &[1, 2, 3])), Err(PacketError::InvalidHeader));
+        assert_eq!(parse_ddp(&ddp(0x43, 0, &[])), Err(PacketError::Ignored));
+
+        let mut truncated = ddp(0x41, 0, &[1, 2, 3]);
+        truncated.pop();
+        assert_eq!(parse_ddp(&truncated), Err(PacketError::TooShort));
+    }
+
+    #[test]
+    fn parses_e131() {
+        // a 32x8 frame takes two universes, only the second one pushes it
+        let packet = e131(1, 0, &[10, 20, 30]);
+        assert_eq!(parse_e131(&packet, 1, 768), Ok(Packet::Data { offset: 0, data: &[10, 20, 30], push: false }));
+
+        let packet = e131(3, 0, &[1; 6]);
+        assert_eq!(parse_e131(&packet, 2, 768), Ok(Packet::Data { offset: 510, data: &[1; 6], push: true }));
+        assert_eq!(parse_e131(&packet, 1, 768), Err(PacketError::Ignored));
+
+        let packet = e131(1, 0, &[1; 6]);
+        assert_eq!(parse_e131(&packet, 1, 6), Ok(Packet::Data { offset: 0, data: &[1; 6], push: true }));
+
+        assert_eq!(parse_e131(&e131(1, E131_OPTION_TERMINATED, &[]), 1, 768), Ok(Packet::Terminate));
+    }
+
+    #[test]
+    fn rejects_bad_e131() {
+        assert_eq!(parse_e131(&[0; 20], 1, 768), Err(PacketError::TooShort));
+        assert_eq!(parse_e131(&e131(1, E131_OPTION_PREVIEW, &[1, 2, 3]), 1, 768), Err(PacketError::Ignored));
+        assert_eq!(parse_e131(&e131(1, 0, &[1, 2, 3]), 2, 768), Err(PacketError::Ignored));
+
+        let mut packet = e131(1, 0, &[1, 2, 3]);
+        packet[4] = b'X';
+        assert_eq!(parse_e131(&packet, 1, 768), Err(PacketError::InvalidHeader));
+    }
+
+    #[test]
+    fn clips_applied_data() {
+        let mut frame = [0u8; 6];
+        assert_eq!(apply(&mut frame, 3, &[1, 2, 3, 4, 5, 6]), 3);
+        assert_eq!(frame, [0, 0, 0, 1, 2, 3]);
+        assert_eq!(apply(&mut frame, 10, &[1]), 0);
+    }
+}
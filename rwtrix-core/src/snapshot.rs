@@ -0,0 +1,244 @@
+//! PNG snapshots of what the panel shows.
+//!
+//! The 32x8 framebuffer is tiny, but a dashboard wants it larger, so every pixel is scaled up into a square of pixels.
+//! The upscaled image repeats itself a lot, a single fixed Huffman deflate block that only looks back one pixel and
+//! one row already shrinks it to a few hundred bytes without the memory a real compressor would need.
+
+use alloc::vec::Vec;
+
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+/// Bytes per pixel of 8 bit RGB.
+const BYTES_PER_PIXEL: usize = 3;
+
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+
+const LENGTH_BASE: [u16; 29] =
+    [3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258];
+const LENGTH_EXTRA: [u8; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145,
+    8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] =
+    [0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13];
+
+/// Encodes a framebuffer of `width` pixels per row as an RGB PNG, every pixel scaled to `scale` x `scale` pixels.
+pub fn encode_png(pixels: &[Rgb888], width: usize, scale: usize) -> Vec<u8> {
+    let height = pixels.len() / width;
+    let image = Scaled { pixels, width, scale };
+
+    let mut png = Vec::new();
+    png.extend_from_slice(&SIGNATURE);
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&((width * scale) as u32).to_be_bytes());
+    header.extend_from_slice(&((height * scale) as u32).to_be_bytes());
+    // 8 bits per channel, RGB, deflate, adaptive filtering, no interlacing
+    header.extend_from_slice(&[8, 2, 0, 0, 0]);
+    write_chunk(&mut png, b"IHDR", &header);
+    write_chunk(&mut png, b"IDAT", &zlib(&image, height * scale));
+    write_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+/// The scanlines of the upscaled image as PNG expects them, each starting with its filter type, produced on the fly
+/// instead of kept in memory.
+struct Scaled<'a> {
+    pixels: &'a [Rgb888],
+    width: usize,
+    scale: usize,
+}
+
+impl Scaled<'_> {
+    fn stride(&self) -> usize {
+        1 + self.width * self.scale * BYTES_PER_PIXEL
+    }
+
+    fn byte(&self, index: usize) -> u8 {
+        let (row, column) = (index / self.stride(), index % self.stride());
+        // every scanline uses filter type 0, the bytes are stored as they are
+        let Some(column) = column.checked_sub(1) else {
+            return 0;
+        };
+        let x = column / BYTES_PER_PIXEL / self.scale;
+        let y = row / self.scale;
+        let color = self.pixels[y * self.width + x];
+        [color.r(), color.g(), color.b()][column % BYTES_PER_PIXEL]
+    }
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, &byte| {
+        (0..8).fold(crc ^ byte as u32, |crc, _| if crc & 1 != 0 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 })
+    })
+}
+
+/// Wraps the deflated scanlines in a zlib stream.
+fn zlib(image: &Scaled, rows: usize) -> Vec<u8> {
+    let len = rows * image.stride();
+    let mut bits = BitWriter::default();
+    // 32K window, no preset dictionary, the check bits make the header a multiple of 31
+    bits.out.extend_from_slice(&[0x78, 0x01]);
+
+    // a single final block with the fixed Huffman codes
+    bits.write(1, 1);
+    bits.write(1, 2);
+    let mut index = 0;
+    while index < len {
+        match longest_match(image, index, len) {
+            Some((length, distance)) => {
+                bits.write_length(length);
+                bits.write_distance(distance);
+                index += length;
+            }
+            None => {
+                bits.write_literal(image.byte(index) as u16);
+                index += 1;
+            }
+        }
+    }
+    bits.write_literal(256);
+    let mut out = bits.finish();
+
+    let (a, b) = (0..len).fold((1u32, 0u32), |(a, b), index| {
+        let a = (a + image.byte(index) as u32) % 65521;
+        (a, (b + a) % 65521)
+    });
+    out.extend_from_slice(&((b << 16) | a).to_be_bytes());
+    out
+}
+
+/// The longest repetition of the previous pixel or the previous row starting at `index`.
+fn longest_match(image: &Scaled, index: usize, len: usize) -> Option<(usize, usize)> {
+    [BYTES_PER_PIXEL, image.stride()]
+        .into_iter()
+        .filter(|&distance| distance <= index)
+        .map(|distance| {
+            let length = (0..MAX_MATCH.min(len - index))
+                .take_while(|&offset| image.byte(index + offset) == image.byte(index + offset - distance))
+                .count();
+            (length, distance)
+        })
+        .filter(|&(length, _)| length >= MIN_MATCH)
+        .max_by_key(|&(length, _)| length)
+}
+
+/// Writes the least significant bit first, as deflate does.
+#[derive(Default)]
+struct BitWriter {
+    out: Vec<u8>,
+    buffer: u32,
+    count: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, bits: u32) {
+        self.buffer |= value << self.count;
+        self.count += bits;
+        while self.count >= 8 {
+            self.out.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    /// Huffman codes are packed starting with their most significant bit.
+    fn write_code(&mut self, code: u32, bits: u32) {
+        self.write(code.reverse_bits() >> (32 - bits), bits);
+    }
+
+    fn write_literal(&mut self, value: u16) {
+        let value = value as u32;
+        match value {
+            0..=143 => self.write_code(0x30 + value, 8),
+            144..=255 => self.write_code(0x190 + value - 144, 9),
+            256..=279 => self.write_code(value - 256, 7),
+            _ => self.write_code(0xc0 + value - 280, 8),
+        }
+    }
+
+    fn write_length(&mut self, length: usize) {
+        let code = LENGTH_BASE.iter().rposition(|&base| base as usize <= length).unwrap();
+        self.write_literal(257 + code as u16);
+        self.write((length - LENGTH_BASE[code] as usize) as u32, LENGTH_EXTRA[code] as u32);
+    }
+
+    fn write_distance(&mut self, distance: usize) {
+        let code = DISTANCE_BASE.iter().rposition(|&base| base as usize <= distance).unwrap();
+        self.write_code(code as u32, 5);
+        self.write((distance - DISTANCE_BASE[code] as usize) as u32, DISTANCE_EXTRA[code] as u32);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.out.push(self.buffer as u8);
+        }
+        self.out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layers::{HEIGHT, WIDTH};
+
+    fn decode(png: &[u8]) -> (png::OutputInfo, Vec<u8>) {
+        let mut reader = png::Decoder::new(png).read_info().unwrap();
+        let mut data = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut data).unwrap();
+        data.truncate(info.buffer_size());
+        (info, data)
+    }
+
+    #[test]
+    fn decodes_to_the_upscaled_framebuffer() {
+        let pixels: Vec<Rgb888> = (0..WIDTH * HEIGHT)
+            .map(|index| match index % 5 {
+                0 => Rgb888::new(index as u8, 255 - index as u8, 7),
+                1 | 2 => Rgb888::RED,
+                3 => Rgb888::new(0, 0, 0x90),
+                _ => Rgb888::WHITE,
+            })
+            .collect();
+
+        let (info, data) = decode(&encode_png(&pixels, WIDTH, 4));
+        assert_eq!((info.width, info.height), (WIDTH as u32 * 4, HEIGHT as u32 * 4));
+        assert_eq!(info.color_type, png::ColorType::Rgb);
+        for (index, rgb) in data.chunks(3).enumerate() {
+            let (x, y) = (index % (WIDTH * 4), index / (WIDTH * 4));
+            let expected = pixels[y / 4 * WIDTH + x / 4];
+            assert_eq!(rgb, [expected.r(), expected.g(), expected.b()], "pixel {x},{y}");
+        }
+    }
+
+    #[test]
+    fn keeps_the_size_at_scale_one() {
+        let pixels = [Rgb888::RED, Rgb888::GREEN, Rgb888::BLUE, Rgb888::BLACK, Rgb888::WHITE, Rgb888::YELLOW];
+        let (info, data) = decode(&encode_png(&pixels, 3, 1));
+        assert_eq!((info.width, info.height), (3, 2));
+        assert_eq!(&data[..6], [255, 0, 0, 0, 255, 0]);
+        assert_eq!(&data[15..], [255, 255, 0]);
+    }
+
+    #[test]
+    fn compresses_repeating_pixels() {
+        let png = encode_png(&[Rgb888::BLACK; WIDTH * HEIGHT], WIDTH, 8);
+        // almost 50 KB of scanlines
+        assert!(png.len() < 1024, "{} bytes", png.len());
+        let (_, data) = decode(&png);
+        assert!(data.iter().all(|&byte| byte == 0));
+    }
+}
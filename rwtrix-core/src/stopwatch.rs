@@ -0,0 +1,175 @@
+//! Stopwatch with lap recording.
+//!
+//! Time is passed in as a monotonic millisecond counter so the stopwatch keeps running no matter how often it is
+//! looked at.
+
+use alloc::{string::String, vec::Vec};
+use core::fmt::Write as _;
+
+/// Oldest laps are dropped once this many were recorded.
+pub const MAX_LAPS: usize = 20;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Stopwatch {
+    /// Time accumulated before the current run.
+    accumulated_ms: u64,
+    started_at_ms: Option<u64>,
+    /// Lap durations, oldest first.
+    laps: Vec<u64>,
+    /// Number of laps recorded since the last reset, including dropped ones.
+    lap_count: usize,
+    /// Total time when the last lap was recorded.
+    last_lap_total_ms: u64,
+}
+
+impl Stopwatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.started_at_ms.is_some()
+    }
+
+    pub fn elapsed_ms(&self, now_ms: u64) -> u64 {
+        self.accumulated_ms + self.started_at_ms.map_or(0, |started_at_ms| now_ms.saturating_sub(started_at_ms))
+    }
+
+    pub fn start(&mut self, now_ms: u64) {
+        if self.started_at_ms.is_none() {
+            self.started_at_ms = Some(now_ms);
+        }
+    }
+
+    pub fn stop(&mut self, now_ms: u64) {
+        self.accumulated_ms = self.elapsed_ms(now_ms);
+        self.started_at_ms = None;
+    }
+
+    pub fn toggle(&mut self, now_ms: u64) {
+        if self.is_running() {
+            self.stop(now_ms);
+        } else {
+            self.start(now_ms);
+        }
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Records the time since the previous lap, ignored while stopped. Returns the recorded lap duration.
+    pub fn lap(&mut self, now_ms: u64) -> Option<u64> {
+        if !self.is_running() {
+            return None;
+        }
+        let total_ms = self.elapsed_ms(now_ms);
+        let lap_ms = total_ms - self.last_lap_total_ms;
+        self.last_lap_total_ms = total_ms;
+        if self.laps.len() == MAX_LAPS {
+            self.laps.remove(0);
+        }
+        self.laps.push(lap_ms);
+        self.lap_count += 1;
+        Some(lap_ms)
+    }
+
+    /// Recorded lap durations, oldest first.
+    pub fn laps(&self) -> &[u64] {
+        &self.laps
+    }
+
+    /// Lap number of `laps()[index]`, starting at 1 and counting the dropped laps too.
+    pub fn lap_number(&self, index: usize) -> usize {
+        self.lap_count - self.laps.len() + index + 1
+    }
+
+    /// Laps as a JSON object like `{"laps":["00:12.34","01:02.50"],"first_lap":1}`.
+    pub fn laps_json(&self) -> String {
+        let mut json = String::from("{\"laps\":[");
+        for (i, lap_ms) in self.laps.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            json.push('"');
+            write_precise(&mut json, *lap_ms);
+            json.push('"');
+        }
+        write!(json, "],\"first_lap\":{}}}", self.lap_number(0)).ok();
+        json
+    }
+}
+
+/// Formats as `MM:SS.c` below an hour and `H:MM:SS` above, at most 7 characters so it fits the panel next to an
+/// icon.
+pub fn write_elapsed(out: &mut String, ms: u64) {
+    let secs = ms / 1000;
+    if secs >= 3600 {
+        write!(out, "{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60).ok();
+    } else {
+        write!(out, "{:02}:{:02}.{}", secs / 60, secs % 60, ms % 1000 / 100).ok();
+    }
+}
+
+/// Formats as `MM:SS.cc`, minutes keep counting past the hour.
+pub fn write_precise(out: &mut String, ms: u64) {
+    let secs = ms / 1000;
+    write!(out, "{:02}:{:02}.{:02}", secs / 60, secs % 60, ms % 1000 / 10).ok();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn elapsed(ms: u64) -> String {
+        let mut out = String::new();
+        write_elapsed(&mut out, ms);
+        out
+    }
+
+    #[test]
+    fn keeps_time_across_pauses() {
+        let mut stopwatch = Stopwatch::new();
+        stopwatch.start(1_000);
+        assert_eq!(stopwatch.elapsed_ms(3_500), 2_500);
+        stopwatch.stop(4_000);
+        assert_eq!(stopwatch.elapsed_ms(60_000), 3_000);
+        stopwatch.toggle(10_000);
+        assert_eq!(stopwatch.elapsed_ms(11_000), 4_000);
+        stopwatch.reset();
+        assert_eq!(stopwatch.elapsed_ms(20_000), 0);
+        assert!(!stopwatch.is_running());
+    }
+
+    #[test]
+    fn records_laps() {
+        let mut stopwatch = Stopwatch::new();
+        assert_eq!(stopwatch.lap(0), None);
+        stopwatch.start(0);
+        assert_eq!(stopwatch.lap(12_340), Some(12_340));
+        stopwatch.stop(20_000);
+        stopwatch.start(30_000);
+        assert_eq!(stopwatch.lap(80_500), Some(58_160));
+        assert_eq!(stopwatch.laps_json(), r#"{"laps":["00:12.34","00:58.16"],"first_lap":1}"#);
+    }
+
+    #[test]
+    fn drops_oldest_laps() {
+        let mut stopwatch = Stopwatch::new();
+        stopwatch.start(0);
+        for i in 1..=MAX_LAPS as u64 + 2 {
+            stopwatch.lap(i * 1_000);
+        }
+        assert_eq!(stopwatch.laps().len(), MAX_LAPS);
+        assert_eq!(stopwatch.lap_number(0), 3);
+        assert_eq!(stopwatch.lap_number(MAX_LAPS - 1), MAX_LAPS + 2);
+    }
+
+    #[test]
+    fn formats_elapsed_time() {
+        assert_eq!(elapsed(0), "00:00.0");
+        assert_eq!(elapsed(83_456), "01:23.4");
+        assert_eq!(elapsed(3_599_999), "59:59.9");
+        assert_eq!(elapsed(3_723_000), "1:02:03");
+    }
+}
@@ -9,7 +9,7 @@
 
 use embedded_graphics_core::{
     draw_target::DrawTarget,
-    geometry::{OriginDimensions, Size},
+    geometry::{OriginDimensions, Point, Size},
     pixelcolor::{Rgb888, RgbColor},
     Pixel,
 };
@@ -30,6 +30,8 @@ pub struct SmartLedMatrix<T, L, const N: usize> {
     layout: L,
     content: [RGB8; N],
     brightness: u8,
+    /// Content and brightness as last written to the LEDs.
+    flushed: Option<([RGB8; N], u8)>,
 }
 
 impl<T, L, const N: usize> core::fmt::Debug for SmartLedMatrix<T, L, N> {
@@ -52,22 +54,43 @@ impl<T, L, const N: usize> SmartLedMatrix<T, L, N> {
     }
 }
 
+impl<T, L: Layout, const N: usize> SmartLedMatrix<T, L, N> {
+    /// Returns the color drawn at `point`, without brightness or gamma correction applied.
+    pub fn pixel(&self, point: Point) -> Option<Rgb888> {
+        let color = self.content.get(self.layout.map(point)?)?;
+        Some(Rgb888::new(color.r, color.g, color.b))
+    }
+}
+
 impl<T: SmartLedsWrite, L: Layout, const N: usize> SmartLedMatrix<T, L, N>
 where
     <T as SmartLedsWrite>::Color: From<RGB8>,
 {
     pub fn new(writer: T, layout: L) -> Self {
-        Self { writer, layout, content: [RGB8::default(); N], brightness: 255 }
+        Self { writer, layout, content: [RGB8::default(); N], brightness: 255, flushed: None }
     }
 
     pub fn flush(&mut self) -> Result<(), T::Error> {
+        self.flushed = None;
         let iter = brightness(self.content.as_slice().iter().cloned(), self.brightness);
         self.writer.write(iter)
     }
     pub fn flush_with_gamma(&mut self) -> Result<(), T::Error> {
+        self.flushed = Some((self.content, self.brightness));
         let iter = brightness(gamma(self.content.as_slice().iter().cloned()), self.brightness);
         self.writer.write(iter)
     }
+
+    /// Like [`Self::flush_with_gamma`], but skips the write when neither the content nor the brightness changed
+    /// since the last one. Returns whether the LEDs were written.
+    pub fn flush_with_gamma_if_changed(&mut self) -> Result<bool, T::Error> {
+        if self.flushed.as_ref().is_some_and(|(content, brightness)| {
+            *brightness == self.brightness && content == &self.content
+        }) {
+            return Ok(false);
+        }
+        self.flush_with_gamma().map(|_| true)
+    }
 }
 
 impl<T: SmartLedsWrite, L: Layout, const N: usize> DrawTarget for SmartLedMatrix<T, L, N>
@@ -99,7 +122,7 @@ impl<T, L: Layout, const N: usize> OriginDimensions for SmartLedMatrix<T, L, N>
 
 #[cfg(test)]
 mod tests {
-    use embedded_graphics_core::{geometry::Point, prelude::Dimensions, primitives::PointsIter};
+    use embedded_graphics_core::{prelude::Dimensions, primitives::PointsIter};
 
     use super::*;
     use crate::layout::Rectangular;
@@ -157,6 +180,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_flush_if_changed() {
+        let content = &mut [RGB8::new(0, 0, 0); 64];
+        let writer = MockWriter { content };
+        let mut matrix = SmartLedMatrix::<_, _, { 8 * 8 }>::new(writer, Rectangular::new(8, 8));
+
+        assert_eq!(matrix.flush_with_gamma_if_changed(), Ok(true));
+        assert_eq!(matrix.flush_with_gamma_if_changed(), Ok(false));
+
+        matrix.draw_iter([Pixel(Point::new(1, 1), Rgb888::WHITE)]).unwrap();
+        assert_eq!(matrix.flush_with_gamma_if_changed(), Ok(true));
+        matrix.draw_iter([Pixel(Point::new(1, 1), Rgb888::WHITE)]).unwrap();
+        assert_eq!(matrix.flush_with_gamma_if_changed(), Ok(false));
+
+        matrix.set_brightness(10);
+        assert_eq!(matrix.flush_with_gamma_if_changed(), Ok(true));
+    }
+
     #[test]
     fn test_identity() {
         let content = &mut [RGB8::new(0, 0, 0); 64];
@@ -197,6 +238,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_pixel_readback() {
+        let content = &mut [RGB8::new(0, 0, 0); 64];
+        let writer = MockWriter { content };
+        let mut matrix = SmartLedMatrix::<_, _, { 8 * 8 }>::new(writer, Rectangular::new_invert_y(8, 8));
+
+        matrix.set_brightness(10);
+        matrix.draw_iter([Pixel(Point::new(2, 1), Rgb888::RED)]).unwrap();
+
+        assert_eq!(matrix.pixel(Point::new(2, 1)), Some(Rgb888::RED), r#"expected the drawn color at full brightness"#);
+        assert_eq!(matrix.pixel(Point::new(1, 2)), Some(Rgb888::BLACK), r#"expected black pixel"#);
+        assert_eq!(matrix.pixel(Point::new(8, 0)), None, r#"expected no pixel outside of the matrix"#);
+    }
+
     #[test]
     fn custom_layout() {
         struct CustomLayout;
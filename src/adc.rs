@@ -1,13 +1,28 @@
-use core::sync::atomic::{AtomicU16, Ordering::Relaxed};
+use core::{
+    cell::Cell,
+    sync::atomic::{AtomicU16, Ordering::Relaxed},
+};
 
-use embassy_time::{Duration, Timer};
+use embassy_sync::blocking_mutex::{raw::CriticalSectionRawMutex, Mutex};
+use embassy_time::{Duration, Instant, Timer};
 use num_traits::Pow as _;
+use rwtrix_core::battery::BatteryEstimator;
+pub use rwtrix_core::battery::{write_runtime, ChargeState};
 
 static BATTERY_LEVEL_PERCENTAGE: AtomicU16 = AtomicU16::new(0);
+static BATTERY_MILLIVOLTS: AtomicU16 = AtomicU16::new(0);
+static BATTERY_TREND: Mutex<CriticalSectionRawMutex, Cell<(ChargeState, Option<u32>)>> =
+    Mutex::new(Cell::new((ChargeState::Unknown, None)));
 static BRIGHTNESS_PERCENT: AtomicU16 = AtomicU16::new(0);
 
+/// Raw readings of an empty and a full cell, and the voltages they stand for.
 const MIN_BATTERY_RAW: u16 = 565;
 const MAX_BATTERY_RAW: u16 = 656;
+const MIN_BATTERY_MILLIVOLTS: u16 = 3300;
+const MAX_BATTERY_MILLIVOLTS: u16 = 4200;
+/// The estimator gets a sample every 10 seconds and looks at the last 15 minutes.
+const ESTIMATOR_INTERVAL: Duration = Duration::from_secs(10);
+const ESTIMATOR_WINDOW_SECS: u64 = 15 * 60;
 const MIN_BRIGHTNESS: u16 = 2;
 const MAX_BRIGHTNESS: u16 = 100;
 const LDR_GAMMA: f32 = 3.0;
@@ -34,6 +49,8 @@ where
 
 #[embassy_executor::task]
 pub async fn adc_task(mut adc: super::Adc, mut battery: super::BatteryPin, mut light_sensor: super::LightSensorPin) {
+    let mut estimator = BatteryEstimator::new(ESTIMATOR_WINDOW_SECS);
+    let mut last_estimate: Option<Instant> = None;
     loop {
         let mut battery_total: u32 = 0;
         let mut light_sensor_total: u32 = 0;
@@ -47,8 +64,14 @@ pub async fn adc_task(mut adc: super::Adc, mut battery: super::BatteryPin, mut l
         let battery_avg = (battery_total / 10) as u16;
         let light_sensor_avg = (light_sensor_total / 10) as u16;
 
-        let battery_percentage = battery_adc_to_percentage(battery_avg);
-        BATTERY_LEVEL_PERCENTAGE.store((battery_percentage * 10.0) as u16, Relaxed);
+        let battery_millivolts = battery_adc_to_millivolts(battery_avg);
+        BATTERY_MILLIVOLTS.store(battery_millivolts, Relaxed);
+        if last_estimate.is_none_or(|last| last.elapsed() >= ESTIMATOR_INTERVAL) {
+            last_estimate = Some(Instant::now());
+            estimator.push(Instant::now().as_secs(), battery_millivolts);
+            BATTERY_LEVEL_PERCENTAGE.store((estimator.percentage() * 10.0) as u16, Relaxed);
+            BATTERY_TREND.lock(|trend| trend.set((estimator.state(), estimator.minutes_remaining())));
+        }
 
         let brightness_percent = map_range(
             ((light_sensor_avg as f32 * LDR_FACTOR) / 1023.0 * 100.0).pow(LDR_GAMMA) / 100.0f32.pow(LDR_GAMMA - 1.0),
@@ -64,15 +87,15 @@ pub async fn adc_task(mut adc: super::Adc, mut battery: super::BatteryPin, mut l
     }
 }
 
-fn battery_adc_to_percentage(adc_value: u16) -> f32 {
-    let clamped_value = if adc_value < MIN_BATTERY_RAW {
-        MIN_BATTERY_RAW
-    } else if adc_value > MAX_BATTERY_RAW {
-        MAX_BATTERY_RAW
-    } else {
-        adc_value
-    };
-    map_range(clamped_value as f32, MIN_BATTERY_RAW as f32, MAX_BATTERY_RAW as f32, 0.0, 100.0)
+/// The voltage is taken as linear in the raw reading, the charge level then follows from the discharge curve.
+fn battery_adc_to_millivolts(adc_value: u16) -> u16 {
+    map_range(
+        adc_value as f32,
+        MIN_BATTERY_RAW as f32,
+        MAX_BATTERY_RAW as f32,
+        MIN_BATTERY_MILLIVOLTS as f32,
+        MAX_BATTERY_MILLIVOLTS as f32,
+    ) as u16
 }
 
 fn scale_12_to_10_exact(x: u16) -> u16 {
@@ -101,6 +124,15 @@ pub fn get_battery_level_percentage() -> f32 {
     BATTERY_LEVEL_PERCENTAGE.load(Relaxed) as f32 / 10.0
 }
 
+pub fn get_battery_millivolts() -> u16 {
+    BATTERY_MILLIVOLTS.load(Relaxed)
+}
+
+/// Whether the battery charges or discharges, with the minutes until it is full or empty once that can be told.
+pub fn get_battery_trend() -> (ChargeState, Option<u32>) {
+    BATTERY_TREND.lock(|trend| trend.get())
+}
+
 pub fn get_brightness_percent() -> f32 {
     BRIGHTNESS_PERCENT.load(Relaxed) as f32 / 10.0
 }
@@ -0,0 +1,172 @@
+use alloc::vec::Vec;
+use core::{
+    cell::RefCell,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use chrono::{Datelike as _, Timelike as _};
+use embassy_futures::select::{select, Either};
+use embassy_sync::{
+    blocking_mutex::{raw::CriticalSectionRawMutex, Mutex},
+    signal::Signal,
+};
+use embassy_time::{Duration, Instant, Timer};
+pub use rwtrix_core::alarm::Alarm;
+use rwtrix_core::alarm::{AlarmClock, AlarmError, LocalTime, DEFAULT_SNOOZE_MINUTES};
+use serde::{Deserialize, Serialize};
+
+use crate::buzzer;
+
+/// Number of alarm slots, each one is exposed to Home Assistant.
+pub const ALARM_COUNT: usize = 3;
+pub const MIN_SNOOZE_MINUTES: u8 = 1;
+pub const MAX_SNOOZE_MINUTES: u8 = 30;
+const CHECK_INTERVAL: Duration = Duration::from_millis(500);
+/// Silence between two repetitions of the melody of a ringing alarm.
+const MELODY_PAUSE: Duration = Duration::from_millis(500);
+
+static SETTINGS: Mutex<CriticalSectionRawMutex, RefCell<AlarmSettings>> =
+    Mutex::new(RefCell::new(AlarmSettings { alarms: Vec::new(), snooze_minutes: DEFAULT_SNOOZE_MINUTES }));
+static CLOCK: Mutex<CriticalSectionRawMutex, RefCell<AlarmClock>> = Mutex::new(RefCell::new(AlarmClock::new()));
+static ALARMS_VERSION: AtomicU32 = AtomicU32::new(0);
+static ALARMS_CHANGED: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+static ALARMS_EXTERNAL_CHANGED: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct AlarmSettings {
+    alarms: Vec<Alarm>,
+    snooze_minutes: u8,
+}
+
+impl Default for AlarmSettings {
+    fn default() -> Self {
+        AlarmSettings { alarms: Vec::new(), snooze_minutes: DEFAULT_SNOOZE_MINUTES }
+    }
+}
+
+fn modify_alarms<R>(f: impl FnOnce(&mut AlarmSettings) -> R) -> R {
+    let result = SETTINGS.lock(|settings| f(&mut settings.borrow_mut()));
+    ALARMS_VERSION.fetch_add(1, Ordering::Relaxed);
+    ALARMS_CHANGED.signal(());
+    ALARMS_EXTERNAL_CHANGED.signal(());
+    result
+}
+
+/// Always [`ALARM_COUNT`] alarms, unused slots are disabled.
+pub fn get_alarms() -> Vec<Alarm> {
+    SETTINGS.lock(|settings| settings.borrow().alarms.clone())
+}
+
+pub fn get_alarm(index: usize) -> Alarm {
+    SETTINGS.lock(|settings| settings.borrow().alarms.get(index).cloned().unwrap_or_default())
+}
+
+pub fn set_alarm_enabled(index: usize, enabled: bool) {
+    if get_alarm(index).enabled != enabled {
+        modify_alarms(|settings| settings.alarms.get_mut(index).map(|alarm| alarm.enabled = enabled));
+    }
+}
+
+/// Accepts `{"time": "07:30", "days": "MTWTF..", "label": "..", "melody": "beep|chime|siren", "enabled": bool}`,
+/// every field is optional.
+pub fn handle_command(index: usize, payload: &str) -> Result<(), AlarmError> {
+    let mut alarm = get_alarm(index);
+    alarm.apply_command(payload)?;
+    modify_alarms(|settings| settings.alarms.get_mut(index).map(|slot| *slot = alarm));
+    Ok(())
+}
+
+pub fn get_snooze_minutes() -> u8 {
+    SETTINGS.lock(|settings| settings.borrow().snooze_minutes)
+}
+
+pub fn set_snooze_minutes(minutes: u8) {
+    let minutes = minutes.clamp(MIN_SNOOZE_MINUTES, MAX_SNOOZE_MINUTES);
+    if get_snooze_minutes() != minutes {
+        modify_alarms(|settings| settings.snooze_minutes = minutes);
+    }
+}
+
+/// Changes every time an alarm is modified, used by the alarm page to refresh itself.
+pub fn get_alarms_version() -> u32 {
+    ALARMS_VERSION.load(Ordering::Relaxed)
+}
+
+/// Resolves after an alarm was modified, used to keep Home Assistant in sync with edits made on the device.
+pub async fn wait_for_change() {
+    ALARMS_EXTERNAL_CHANGED.wait().await
+}
+
+/// The alarm currently ringing, shown full screen by the matrix task.
+pub fn get_ringing() -> Option<Alarm> {
+    CLOCK.lock(|clock| clock.borrow().ringing()).map(get_alarm)
+}
+
+pub fn snooze() {
+    let snooze_minutes = get_snooze_minutes();
+    CLOCK.lock(|clock| clock.borrow_mut().snooze(Instant::now().as_millis(), snooze_minutes));
+    buzzer::stop();
+    info!("Alarm snoozed for {} minutes", snooze_minutes);
+}
+
+pub fn dismiss() {
+    let mut alarms = get_alarms();
+    if CLOCK.lock(|clock| clock.borrow_mut().dismiss(&mut alarms)) {
+        modify_alarms(|settings| settings.alarms = alarms);
+    }
+    buzzer::stop();
+    info!("Alarm dismissed");
+}
+
+fn local_time(rtc: &esp_hal::rtc_cntl::Rtc<'static>) -> LocalTime {
+    let now = chrono::NaiveDateTime::from_timestamp_micros(rtc.current_time_us() as i64).unwrap_or_default();
+    LocalTime {
+        weekday: now.weekday().num_days_from_monday() as u8,
+        hour: now.hour() as u8,
+        minute: now.minute() as u8,
+    }
+}
+
+/// Matches the schedules against the RTC and keeps the buzzer going while an alarm rings.
+#[embassy_executor::task]
+pub async fn alarm_task(storage: crate::storage::Storage, rtc: &'static esp_hal::rtc_cntl::Rtc<'static>) {
+    let mut settings = storage.read::<AlarmSettings>(&crate::storage::Key::Alarms).await.unwrap_or_default();
+    settings.alarms.resize_with(ALARM_COUNT, Alarm::default);
+    info!("Loaded {} alarms", settings.alarms.iter().filter(|alarm| alarm.enabled).count());
+    SETTINGS.lock(|stored| *stored.borrow_mut() = settings);
+    ALARMS_VERSION.fetch_add(1, Ordering::Relaxed);
+    ALARMS_EXTERNAL_CHANGED.signal(());
+
+    let mut next_melody = Instant::now();
+    loop {
+        match select(ALARMS_CHANGED.wait(), Timer::after(CHECK_INTERVAL)).await {
+            Either::First(_) => {
+                let settings = SETTINGS.lock(|settings| settings.borrow().clone());
+                storage.save(&crate::storage::Key::Alarms, &settings).await.expect("failed saving alarms");
+                info!("Alarms saved");
+            }
+            Either::Second(_) => {
+                let mut alarms = get_alarms();
+                let now = local_time(rtc);
+                let (started, changed) =
+                    CLOCK.lock(|clock| clock.borrow_mut().check(&mut alarms, now, Instant::now().as_millis()));
+                if changed {
+                    modify_alarms(|settings| settings.alarms = alarms);
+                }
+                if let Some(index) = started {
+                    info!("Alarm {} ringing", index + 1);
+                    next_melody = Instant::now();
+                }
+
+                let Some(alarm) = get_ringing() else {
+                    continue;
+                };
+                if Instant::now() >= next_melody {
+                    let pattern = buzzer::melody(alarm.melody);
+                    buzzer::play(pattern);
+                    next_melody = Instant::now() + buzzer::pattern_duration(pattern) + MELODY_PAUSE;
+                }
+            }
+        }
+    }
+}
@@ -13,7 +13,8 @@ pub async fn button_task(mut left: Input<'static>, mut mid: Input<'static>, mut
     let mut buttons = Buttons::new(left, mid, right);
 
     loop {
-        buttons.wait_for_press().await;
+        let pressed = buttons.wait_for_press().await;
+        sender.send(MatrixEventDetails::press(pressed)).await;
         let matrix_event = buttons.wait_for_release().await;
         info!("Sending button event: {:?}", matrix_event);
         sender.send(matrix_event).await;
@@ -74,13 +75,14 @@ impl Buttons {
         }
     }
 
-    pub async fn wait_for_press(&mut self) {
+    pub async fn wait_for_press(&mut self) -> MatrixEvent {
         self.event.clear();
         let button = self.wait_for_any_press().await;
         info!("Button {:?} pressed", button);
         self.event.set_main(button);
         self.event.push_event(button);
         self.duration = Instant::now();
+        button
     }
 
     pub async fn wait_for_release(&mut self) -> MatrixEventDetails {
@@ -100,6 +102,7 @@ impl Buttons {
             if let Some(new_button) = Self::wait_for_release_or_more_press(main, (extra0, extra1)).await {
                 info!("Button {:?} pressed while waiting for release of {:?}", new_button, self.event.get_main());
                 self.event.push_event(new_button);
+                get_event_channel_sender().send(MatrixEventDetails::press(new_button)).await;
             } else {
                 break;
             }
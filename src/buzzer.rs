@@ -1,9 +1,72 @@
-use embassy_futures::{
-    join::join3,
-    select::{select, select3, Either, Either3},
-};
-use embassy_time::Instant;
-use esp_hal::gpio::{Input, Output};
+use embassy_futures::select::{select, Either};
+use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, channel::Channel, signal::Signal};
+use embassy_time::{Duration, Timer};
+use embedded_hal::pwm::SetDutyCycle;
+use rwtrix_core::alarm::Melody;
+
+pub type BuzzerChannel = esp_hal::ledc::channel::Channel<'static, esp_hal::ledc::HighSpeed>;
+
+/// A tone of `on_ms` followed by `off_ms` of silence.
+#[derive(Debug, Clone, Copy)]
+pub struct Beep {
+    pub on_ms: u16,
+    pub off_ms: u16,
+}
+
+pub const PHASE_END: &[Beep] = &[Beep { on_ms: 150, off_ms: 100 }; 3];
+pub const LONG_ALARM: &[Beep] = &[Beep { on_ms: 400, off_ms: 200 }; 5];
+
+const MELODY_BEEP: &[Beep] = &[Beep { on_ms: 100, off_ms: 100 }, Beep { on_ms: 100, off_ms: 700 }];
+const MELODY_CHIME: &[Beep] =
+    &[Beep { on_ms: 300, off_ms: 150 }, Beep { on_ms: 300, off_ms: 150 }, Beep { on_ms: 600, off_ms: 1000 }];
+const MELODY_SIREN: &[Beep] = &[Beep { on_ms: 50, off_ms: 50 }; 10];
+
+static PATTERNS: Channel<CriticalSectionRawMutex, &'static [Beep], 4> = Channel::new();
+static STOP: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+
+pub fn melody(melody: Melody) -> &'static [Beep] {
+    match melody {
+        Melody::Beep => MELODY_BEEP,
+        Melody::Chime => MELODY_CHIME,
+        Melody::Siren => MELODY_SIREN,
+    }
+}
+
+pub fn pattern_duration(pattern: &[Beep]) -> Duration {
+    Duration::from_millis(pattern.iter().map(|beep| beep.on_ms as u64 + beep.off_ms as u64).sum())
+}
+
+/// Queues a beep pattern, patterns play one after another.
+pub fn play(pattern: &'static [Beep]) {
+    if PATTERNS.try_send(pattern).is_err() {
+        warn!("Buzzer queue full, dropping pattern");
+    }
+}
+
+/// Silences the buzzer right away and drops the queued patterns.
+pub fn stop() {
+    PATTERNS.clear();
+    STOP.signal(());
+}
+
+/// Returns `true` when [`stop`] was called before `ms` elapsed.
+async fn wait_or_stop(ms: u16) -> bool {
+    matches!(select(STOP.wait(), Timer::after_millis(ms as u64)).await, Either::First(_))
+}
 
 #[embassy_executor::task]
-pub async fn buzzer_task(mut output: Output<'static>) {}
+pub async fn buzzer_task(mut channel: BuzzerChannel) {
+    loop {
+        let pattern = PATTERNS.receive().await;
+        // a stop requested before this pattern was queued does not apply to it
+        STOP.reset();
+        for beep in pattern {
+            channel.set_duty_cycle_percent(50).ok();
+            let stopped = wait_or_stop(beep.on_ms).await;
+            channel.set_duty_cycle_percent(0).ok();
+            if stopped || wait_or_stop(beep.off_ms).await {
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use alloc::{string::String, vec::Vec};
+use core::{
+    cell::RefCell,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use embassy_sync::blocking_mutex::{raw::CriticalSectionRawMutex, Mutex};
+use embassy_time::{Duration, Timer};
+pub use rwtrix_core::chart::Series;
+use rwtrix_core::chart::{self, ChartError, ChartStyle};
+
+use crate::adc;
+
+/// With one sample a minute the charts cover the last half hour.
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(60);
+const BATTERY: &str = "battery";
+const LIGHT: &str = "light";
+const HEAP: &str = "heap";
+
+/// The built-in series first, then the ones pushed over MQTT. History is only kept in memory.
+static SERIES: Mutex<CriticalSectionRawMutex, RefCell<Vec<Series>>> = Mutex::new(RefCell::new(Vec::new()));
+static SERIES_VERSION: AtomicU32 = AtomicU32::new(0);
+
+/// Accepts `{"name": "..", "value": 1.5, "style": "bars"}`, the series is created on its first sample.
+pub fn handle_sample(payload: &str) -> Result<(), ChartError> {
+    SERIES.lock(|series| chart::apply_sample(&mut series.borrow_mut(), payload))?;
+    SERIES_VERSION.fetch_add(1, Ordering::Relaxed);
+    Ok(())
+}
+
+pub fn get_series() -> Vec<Series> {
+    SERIES.lock(|series| series.borrow().clone())
+}
+
+/// Names of all series, shown as the state of the Home Assistant text.
+pub fn get_series_names() -> String {
+    SERIES.lock(|series| {
+        let mut names = String::new();
+        for (i, series) in series.borrow().iter().enumerate() {
+            if i > 0 {
+                names.push_str(", ");
+            }
+            names.push_str(&series.name);
+        }
+        names
+    })
+}
+
+/// Changes with every sample, used by the chart page to refresh itself.
+pub fn get_series_version() -> u32 {
+    SERIES_VERSION.load(Ordering::Relaxed)
+}
+
+#[embassy_executor::task]
+pub async fn charts_task() {
+    SERIES.lock(|series| {
+        let builtin = [
+            Series::new(BATTERY, ChartStyle::Line, [255, 0, 0], [0, 255, 0]),
+            Series::new(LIGHT, ChartStyle::Bars, [32, 32, 128], [255, 220, 0]),
+            Series::new(HEAP, ChartStyle::Bars, [0, 255, 0], [255, 0, 0]),
+        ];
+        series.borrow_mut().splice(0..0, builtin);
+    });
+
+    loop {
+        Timer::after(SAMPLE_INTERVAL).await;
+        let samples = [
+            (BATTERY, adc::get_battery_level_percentage()),
+            (LIGHT, adc::get_brightness_percent()),
+            // the same reading `ha::heap_class` publishes
+            (HEAP, esp_alloc::HEAP.stats().current_usage as f32),
+        ];
+        SERIES.lock(|series| {
+            for (name, value) in samples {
+                if let Some(series) = series.borrow_mut().iter_mut().find(|series| series.name == name) {
+                    series.history.push(value);
+                }
+            }
+        });
+        SERIES_VERSION.fetch_add(1, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,84 @@
+use core::{
+    cell::Cell,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use embassy_sync::{
+    blocking_mutex::{raw::CriticalSectionRawMutex, Mutex},
+    signal::Signal,
+};
+pub use rwtrix_core::clock::{write_color, BlinkStyle, ClockFace, ClockSettings, HourFormat, WeekStart};
+use serde::Deserialize;
+
+static CLOCK_SETTINGS: Mutex<CriticalSectionRawMutex, Cell<Option<ClockSettings>>> = Mutex::new(Cell::new(None));
+static CLOCK_SETTINGS_VERSION: AtomicU32 = AtomicU32::new(0);
+static CLOCK_SETTINGS_CHANGED: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+
+pub fn get_settings() -> ClockSettings {
+    CLOCK_SETTINGS.lock(|settings| settings.get()).unwrap_or_default()
+}
+
+pub fn modify_settings(f: impl FnOnce(&mut ClockSettings)) {
+    let mut settings = get_settings();
+    f(&mut settings);
+    if settings != get_settings() {
+        CLOCK_SETTINGS.lock(|stored| stored.set(Some(settings)));
+        CLOCK_SETTINGS_VERSION.fetch_add(1, Ordering::Relaxed);
+        CLOCK_SETTINGS_CHANGED.signal(());
+    }
+}
+
+/// Changes every time the settings are modified, used by the time page to apply them live.
+pub fn get_settings_version() -> u32 {
+    CLOCK_SETTINGS_VERSION.load(Ordering::Relaxed)
+}
+
+/// Settings as stored before the clock faces, those keep the classic face.
+#[derive(Debug, Deserialize)]
+struct LegacyClockSettings {
+    hour_format: HourFormat,
+    show_seconds: bool,
+    week_start: WeekStart,
+    digit_color: [u8; 3],
+    calendar_color: [u8; 3],
+    weekday_color: [u8; 3],
+    blink: BlinkStyle,
+}
+
+fn decode_settings(raw: &[u8]) -> Option<ClockSettings> {
+    if let Ok(settings) = postcard::from_bytes::<ClockSettings>(raw) {
+        return Some(settings);
+    }
+    let legacy = postcard::from_bytes::<LegacyClockSettings>(raw).ok()?;
+    info!("Migrating clock settings from the format without faces: {:?}", legacy);
+    Some(ClockSettings {
+        hour_format: legacy.hour_format,
+        show_seconds: legacy.show_seconds,
+        week_start: legacy.week_start,
+        digit_color: legacy.digit_color,
+        calendar_color: legacy.calendar_color,
+        weekday_color: legacy.weekday_color,
+        blink: legacy.blink,
+        face: ClockFace::Classic,
+    })
+}
+
+#[embassy_executor::task]
+pub async fn clock_task(storage: crate::storage::Storage) {
+    let settings = storage
+        .read_raw(&crate::storage::Key::ClockSettings)
+        .await
+        .ok()
+        .and_then(|raw| decode_settings(&raw))
+        .unwrap_or_default();
+    info!("Loaded clock settings: {:?}", settings);
+    CLOCK_SETTINGS.lock(|stored| stored.set(Some(settings)));
+    CLOCK_SETTINGS_VERSION.fetch_add(1, Ordering::Relaxed);
+
+    loop {
+        CLOCK_SETTINGS_CHANGED.wait().await;
+        let settings = get_settings();
+        storage.save(&crate::storage::Key::ClockSettings, &settings).await.expect("failed saving clock settings");
+        info!("Clock settings saved: {:?}", settings);
+    }
+}
@@ -0,0 +1,81 @@
+use alloc::{string::String, vec::Vec};
+use core::{
+    cell::RefCell,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use embassy_sync::{
+    blocking_mutex::{raw::CriticalSectionRawMutex, Mutex},
+    signal::Signal,
+};
+pub use rwtrix_core::countdown::Countdown;
+use rwtrix_core::countdown::{self, CountdownError};
+
+static COUNTDOWNS: Mutex<CriticalSectionRawMutex, RefCell<Vec<Countdown>>> = Mutex::new(RefCell::new(Vec::new()));
+static COUNTDOWNS_VERSION: AtomicU32 = AtomicU32::new(0);
+static COUNTDOWNS_CHANGED: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+static COUNTDOWNS_EXTERNAL_CHANGED: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+
+/// Accepts `{"name": "..", "date": "YYYY-MM-DD[ HH:MM]", "mode": "until|since"}`, a missing or empty `date` deletes
+/// the countdown.
+pub fn handle_command(payload: &str) -> Result<(), CountdownError> {
+    let mut countdowns = get_countdowns();
+    countdown::apply_command(&mut countdowns, payload)?;
+    COUNTDOWNS.lock(|stored| *stored.borrow_mut() = countdowns);
+    notify_changed();
+    Ok(())
+}
+
+fn notify_changed() {
+    COUNTDOWNS_VERSION.fetch_add(1, Ordering::Relaxed);
+    COUNTDOWNS_CHANGED.signal(());
+    COUNTDOWNS_EXTERNAL_CHANGED.signal(());
+}
+
+pub fn get_countdowns() -> Vec<Countdown> {
+    COUNTDOWNS.lock(|countdowns| countdowns.borrow().clone())
+}
+
+/// One line per countdown like `Release 2026-12-24 18:00 until`, shown as the state of the Home Assistant text.
+pub fn description() -> String {
+    COUNTDOWNS.lock(|countdowns| {
+        let mut description = String::new();
+        for (i, entry) in countdowns.borrow().iter().enumerate() {
+            if i > 0 {
+                description.push_str(", ");
+            }
+            description.push_str(&entry.name);
+            description.push(' ');
+            countdown::write_datetime(&mut description, entry.target);
+            description.push(' ');
+            description.push_str(entry.mode.name());
+        }
+        description
+    })
+}
+
+/// Changes every time a countdown is modified, used by the countdown page to refresh itself.
+pub fn get_countdowns_version() -> u32 {
+    COUNTDOWNS_VERSION.load(Ordering::Relaxed)
+}
+
+/// Resolves after the countdowns were modified, used to keep Home Assistant in sync with edits made over HTTP.
+pub async fn wait_for_change() {
+    COUNTDOWNS_EXTERNAL_CHANGED.wait().await
+}
+
+#[embassy_executor::task]
+pub async fn countdowns_task(storage: crate::storage::Storage) {
+    let countdowns = storage.read::<Vec<Countdown>>(&crate::storage::Key::Countdowns).await.unwrap_or_default();
+    info!("Loaded {} countdowns", countdowns.len());
+    COUNTDOWNS.lock(|stored| *stored.borrow_mut() = countdowns);
+    COUNTDOWNS_VERSION.fetch_add(1, Ordering::Relaxed);
+    COUNTDOWNS_EXTERNAL_CHANGED.signal(());
+
+    loop {
+        COUNTDOWNS_CHANGED.wait().await;
+        let countdowns = get_countdowns();
+        storage.save(&crate::storage::Key::Countdowns, &countdowns).await.expect("failed saving countdowns");
+        info!("Countdowns saved: {}", countdowns.len());
+    }
+}
@@ -0,0 +1,150 @@
+use alloc::{string::String, vec::Vec};
+use core::{
+    cell::RefCell,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use embassy_sync::{
+    blocking_mutex::{raw::CriticalSectionRawMutex, Mutex},
+    signal::Signal,
+};
+use serde::{Deserialize, Serialize};
+
+pub const MAX_CUSTOM_APPS: usize = 8;
+pub const DEFAULT_CUSTOM_APP_DURATION_SECS: u16 = 10;
+
+static CUSTOM_APPS: Mutex<CriticalSectionRawMutex, RefCell<Vec<CustomApp>>> = Mutex::new(RefCell::new(Vec::new()));
+static CUSTOM_APPS_VERSION: AtomicU32 = AtomicU32::new(0);
+static CUSTOM_APPS_CHANGED: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomApp {
+    pub name: String,
+    pub text: String,
+    pub icon: Option<String>,
+    pub color: [u8; 3],
+    pub duration: u16,
+}
+
+#[derive(Debug)]
+pub enum CustomAppError {
+    InvalidJson(serde_json_core::de::Error),
+    EmptyName,
+    /// The name belongs to a built-in page.
+    BuiltinName,
+    TooManyApps,
+}
+
+/// Custom app command as pushed over MQTT, a missing or empty `text` deletes the app.
+#[derive(Deserialize)]
+struct CustomAppCommand<'a> {
+    name: &'a str,
+    text: Option<&'a str>,
+    icon: Option<&'a str>,
+    color: Option<[u8; 3]>,
+    duration: Option<u16>,
+}
+
+pub fn handle_command(payload: &str) -> Result<(), CustomAppError> {
+    let (command, _) = serde_json_core::from_str::<CustomAppCommand>(payload).map_err(CustomAppError::InvalidJson)?;
+    if command.name.is_empty() {
+        return Err(CustomAppError::EmptyName);
+    }
+
+    match command.text {
+        Some(text) if !text.is_empty() => upsert(CustomApp {
+            name: command.name.into(),
+            text: text.into(),
+            icon: command.icon.map(Into::into),
+            color: command.color.unwrap_or([255, 255, 255]),
+            duration: command.duration.unwrap_or(DEFAULT_CUSTOM_APP_DURATION_SECS),
+        }),
+        _ => {
+            remove(command.name);
+            Ok(())
+        }
+    }
+}
+
+pub fn upsert(app: CustomApp) -> Result<(), CustomAppError> {
+    if crate::page_registry::is_builtin(&app.name) {
+        return Err(CustomAppError::BuiltinName);
+    }
+    CUSTOM_APPS.lock(|apps| {
+        let mut apps = apps.borrow_mut();
+        if let Some(existing) = apps.iter_mut().find(|existing| existing.name == app.name) {
+            *existing = app;
+        } else if apps.len() < MAX_CUSTOM_APPS {
+            apps.push(app);
+        } else {
+            return Err(CustomAppError::TooManyApps);
+        }
+        Ok(())
+    })?;
+    notify_changed();
+    Ok(())
+}
+
+pub fn remove(name: &str) {
+    let removed = CUSTOM_APPS.lock(|apps| {
+        let mut apps = apps.borrow_mut();
+        let len = apps.len();
+        apps.retain(|app| app.name != name);
+        apps.len() != len
+    });
+    if removed {
+        notify_changed();
+    }
+}
+
+fn notify_changed() {
+    CUSTOM_APPS_VERSION.fetch_add(1, Ordering::Relaxed);
+    CUSTOM_APPS_CHANGED.signal(());
+}
+
+pub fn get_custom_apps() -> Vec<CustomApp> {
+    CUSTOM_APPS
+        .lock(|apps| apps.borrow().iter().filter(|app| !crate::page_registry::is_builtin(&app.name)).cloned().collect())
+}
+
+pub fn get_custom_app_names() -> String {
+    CUSTOM_APPS.lock(|apps| {
+        let apps = apps.borrow();
+        let mut names = String::new();
+        for (i, app) in apps.iter().filter(|app| !crate::page_registry::is_builtin(&app.name)).enumerate() {
+            if i > 0 {
+                names.push(',');
+            }
+            names.push_str(&app.name);
+        }
+        names
+    })
+}
+
+/// Changes every time the set of custom apps is modified, used by the matrix task to rebuild its pages.
+pub fn get_custom_apps_version() -> u32 {
+    CUSTOM_APPS_VERSION.load(Ordering::Relaxed)
+}
+
+#[embassy_executor::task]
+pub async fn custom_apps_task(storage: crate::storage::Storage) {
+    let mut apps = storage.read::<Vec<CustomApp>>(&crate::storage::Key::CustomApps).await.unwrap_or_default();
+    // apps stored before a built-in page took their name would never be shown
+    apps.retain(|app| {
+        let builtin = crate::page_registry::is_builtin(&app.name);
+        if builtin {
+            warn!("Dropping custom app {:?}, a built-in page has the same name", app.name.as_str());
+        }
+        !builtin
+    });
+    info!("Loaded {} custom apps", apps.len());
+    CUSTOM_APPS.lock(|custom_apps| *custom_apps.borrow_mut() = apps);
+    CUSTOM_APPS_VERSION.fetch_add(1, Ordering::Relaxed);
+
+    loop {
+        CUSTOM_APPS_CHANGED.wait().await;
+        let apps = get_custom_apps();
+        storage.save(&crate::storage::Key::CustomApps, &apps).await.expect("failed saving custom apps");
+        info!("Custom apps saved: {}", apps.len());
+    }
+}
@@ -0,0 +1,48 @@
+use core::{
+    cell::Cell,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use embassy_sync::{
+    blocking_mutex::{raw::CriticalSectionRawMutex, Mutex},
+    signal::Signal,
+};
+pub use rwtrix_core::date::{DateOrder, DateSettings, Language, Separator};
+
+static DATE_SETTINGS: Mutex<CriticalSectionRawMutex, Cell<Option<DateSettings>>> = Mutex::new(Cell::new(None));
+static DATE_SETTINGS_VERSION: AtomicU32 = AtomicU32::new(0);
+static DATE_SETTINGS_CHANGED: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+
+pub fn get_settings() -> DateSettings {
+    DATE_SETTINGS.lock(|settings| settings.get()).unwrap_or_default()
+}
+
+pub fn modify_settings(f: impl FnOnce(&mut DateSettings)) {
+    let mut settings = get_settings();
+    f(&mut settings);
+    if settings != get_settings() {
+        DATE_SETTINGS.lock(|stored| stored.set(Some(settings)));
+        DATE_SETTINGS_VERSION.fetch_add(1, Ordering::Relaxed);
+        DATE_SETTINGS_CHANGED.signal(());
+    }
+}
+
+/// Changes every time the settings are modified, used by the date page to apply them live.
+pub fn get_settings_version() -> u32 {
+    DATE_SETTINGS_VERSION.load(Ordering::Relaxed)
+}
+
+#[embassy_executor::task]
+pub async fn date_task(storage: crate::storage::Storage) {
+    let settings = storage.read::<DateSettings>(&crate::storage::Key::DateSettings).await.unwrap_or_default();
+    info!("Loaded date settings: {:?}", settings);
+    DATE_SETTINGS.lock(|stored| stored.set(Some(settings)));
+    DATE_SETTINGS_VERSION.fetch_add(1, Ordering::Relaxed);
+
+    loop {
+        DATE_SETTINGS_CHANGED.wait().await;
+        let settings = get_settings();
+        storage.save(&crate::storage::Key::DateSettings, &settings).await.expect("failed saving date settings");
+        info!("Date settings saved: {:?}", settings);
+    }
+}
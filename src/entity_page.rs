@@ -0,0 +1,103 @@
+use alloc::{string::String, vec::Vec};
+use core::{
+    cell::RefCell,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use embassy_sync::{
+    blocking_mutex::{raw::CriticalSectionRawMutex, Mutex},
+    signal::Signal,
+};
+pub use rwtrix_core::entity_page::EntityWidget;
+use rwtrix_core::entity_page::{self, EntityWidgetError};
+
+static WIDGETS: Mutex<CriticalSectionRawMutex, RefCell<Vec<EntityWidget>>> = Mutex::new(RefCell::new(Vec::new()));
+static WIDGETS_VERSION: AtomicU32 = AtomicU32::new(0);
+static WIDGETS_CHANGED: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+static WIDGETS_LOADED: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+/// Latest payload per topic, kept apart from the stored widgets since it changes all the time.
+static VALUES: Mutex<CriticalSectionRawMutex, RefCell<Vec<(String, String)>>> = Mutex::new(RefCell::new(Vec::new()));
+static VALUES_VERSION: AtomicU32 = AtomicU32::new(0);
+
+/// Accepts `{"name": "..", "topic": "..", "icon": "..", "unit": "..", "color": [r, g, b], "rules": [{"min": 25,
+/// "color": [r, g, b]}, {"state": "on", "color": [r, g, b]}]}`, a missing or empty `topic` deletes the widget.
+pub fn handle_command(payload: &str) -> Result<(), EntityWidgetError> {
+    let mut widgets = get_widgets();
+    entity_page::apply_command(&mut widgets, payload)?;
+    WIDGETS.lock(|stored| *stored.borrow_mut() = widgets);
+    WIDGETS_VERSION.fetch_add(1, Ordering::Relaxed);
+    WIDGETS_CHANGED.signal(());
+    Ok(())
+}
+
+pub fn get_widgets() -> Vec<EntityWidget> {
+    WIDGETS.lock(|widgets| widgets.borrow().clone())
+}
+
+/// One `name topic` pair per widget, shown as the state of the Home Assistant text.
+pub fn description() -> String {
+    WIDGETS.lock(|widgets| {
+        let mut description = String::new();
+        for (i, widget) in widgets.borrow().iter().enumerate() {
+            if i > 0 {
+                description.push_str(", ");
+            }
+            description.push_str(&widget.name);
+            description.push(' ');
+            description.push_str(&widget.topic);
+        }
+        description
+    })
+}
+
+/// Changes every time a widget is modified, used by the entity page to refresh itself.
+pub fn get_widgets_version() -> u32 {
+    WIDGETS_VERSION.load(Ordering::Relaxed)
+}
+
+/// Resolves once the stored widgets were loaded, their topics are subscribed to when connecting to Home Assistant.
+pub async fn wait_for_load() {
+    WIDGETS_LOADED.wait().await
+}
+
+pub fn set_value(topic: &str, payload: &str) {
+    VALUES.lock(|values| {
+        let mut values = values.borrow_mut();
+        match values.iter_mut().find(|(value_topic, _)| value_topic == topic) {
+            Some((_, value)) => {
+                value.clear();
+                value.push_str(payload);
+            }
+            None => values.push((topic.into(), payload.into())),
+        }
+    });
+    VALUES_VERSION.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Latest payload received on `topic`.
+pub fn get_value(topic: &str) -> Option<String> {
+    VALUES.lock(|values| {
+        values.borrow().iter().find(|(value_topic, _)| value_topic == topic).map(|(_, value)| value.clone())
+    })
+}
+
+/// Changes every time a value arrives.
+pub fn get_values_version() -> u32 {
+    VALUES_VERSION.load(Ordering::Relaxed)
+}
+
+#[embassy_executor::task]
+pub async fn entity_page_task(storage: crate::storage::Storage) {
+    let widgets = storage.read::<Vec<EntityWidget>>(&crate::storage::Key::EntityWidgets).await.unwrap_or_default();
+    info!("Loaded {} entity widgets", widgets.len());
+    WIDGETS.lock(|stored| *stored.borrow_mut() = widgets);
+    WIDGETS_VERSION.fetch_add(1, Ordering::Relaxed);
+    WIDGETS_LOADED.signal(());
+
+    loop {
+        WIDGETS_CHANGED.wait().await;
+        let widgets = get_widgets();
+        storage.save(&crate::storage::Key::EntityWidgets, &widgets).await.expect("failed saving entity widgets");
+        info!("Entity widgets saved: {}", widgets.len());
+    }
+}
@@ -0,0 +1,51 @@
+use core::cell::Cell;
+
+use embassy_sync::{
+    blocking_mutex::{raw::CriticalSectionRawMutex, Mutex},
+    signal::Signal,
+};
+use rwtrix_core::games::GameKind;
+pub use rwtrix_core::games::HighScores;
+
+static HIGH_SCORES: Mutex<CriticalSectionRawMutex, Cell<Option<HighScores>>> = Mutex::new(Cell::new(None));
+static HIGH_SCORES_CHANGED: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+static HIGH_SCORES_EXTERNAL_CHANGED: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+
+pub fn get_high_scores() -> HighScores {
+    HIGH_SCORES.lock(|high_scores| high_scores.get()).unwrap_or_default()
+}
+
+/// Called by the games page when a round ends, returns whether the score is a new high score.
+pub fn report_score(kind: GameKind, score: u32) -> bool {
+    let new_high_score = HIGH_SCORES.lock(|high_scores| {
+        let mut updated = high_scores.get().unwrap_or_default();
+        let new_high_score = updated.record(kind, score);
+        high_scores.set(Some(updated));
+        new_high_score
+    });
+    if new_high_score {
+        HIGH_SCORES_CHANGED.signal(());
+        HIGH_SCORES_EXTERNAL_CHANGED.signal(());
+    }
+    new_high_score
+}
+
+/// Resolves after a high score was beaten, used to publish it to Home Assistant.
+pub async fn wait_for_change() {
+    HIGH_SCORES_EXTERNAL_CHANGED.wait().await
+}
+
+#[embassy_executor::task]
+pub async fn games_task(storage: crate::storage::Storage) {
+    let high_scores = storage.read::<HighScores>(&crate::storage::Key::GameHighScores).await.unwrap_or_default();
+    info!("Loaded high scores: {:?}", high_scores);
+    HIGH_SCORES.lock(|stored| stored.set(Some(high_scores)));
+    HIGH_SCORES_EXTERNAL_CHANGED.signal(());
+
+    loop {
+        HIGH_SCORES_CHANGED.wait().await;
+        let high_scores = get_high_scores();
+        storage.save(&crate::storage::Key::GameHighScores, &high_scores).await.expect("failed saving high scores");
+        info!("High scores saved: {:?}", high_scores);
+    }
+}
@@ -1,14 +1,27 @@
-use alloc::{boxed::Box, string::String};
+use alloc::{boxed::Box, string::String, vec::Vec};
 use core::{fmt::Write as _, sync::atomic::Ordering};
 
 use embassy_executor::Spawner;
-use embassy_futures::select::{select, Either};
+use embassy_futures::select::{select, select3, select4, select_array, Either, Either3, Either4};
 use embassy_ha::{BinaryState, MqttState};
 use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, channel::Channel};
 use embassy_time::Timer;
-use static_cell::StaticCell;
+use rwtrix_core::{awtrix, games::GameKind, snapshot};
+use static_cell::ConstStaticCell;
 
-use crate::state;
+use crate::{
+    alarm::{self, ALARM_COUNT},
+    charts,
+    clock::{self, BlinkStyle, ClockFace, HourFormat, WeekStart},
+    countdowns, custom_apps,
+    date::{self, DateOrder, Language, Separator},
+    entity_page, games,
+    matrix::{screen, transition::TransitionEffect},
+    night::{self, NightAction, NightWindow},
+    notifications, page_registry,
+    pomodoro::{self, Phase},
+    state, stopwatch,
+};
 
 #[atomic_enum::atomic_enum]
 pub enum HaState {
@@ -24,9 +37,94 @@ const MQTT_BROKER_PORT: &str = dotenvy_macro::dotenv!("MQTT_BROKER_PORT");
 const MQTT_USER: &str = dotenvy_macro::dotenv!("MQTT_USER");
 const MQTT_PASSWORD: &str = dotenvy_macro::dotenv!("MQTT_PASSWORD");
 
-static RESOURCES: StaticCell<embassy_ha::DeviceResources> = StaticCell::new();
+/// Built in place, the resources are far too large for the stack.
+static RESOURCES: ConstStaticCell<embassy_ha::DeviceResources> =
+    ConstStaticCell::new(embassy_ha::DeviceResources::new());
 static MQTT_STATE_CHANNEL: Channel<CriticalSectionRawMutex, MqttState, 1> = Channel::new();
 static HA_STATE: AtomicHaState = AtomicHaState::new(HaState::Disconnected);
+static SCREEN_BUFFER: ConstStaticCell<heapless::Vec<u8, SCREEN_BUFFER_SIZE>> =
+    ConstStaticCell::new(heapless::Vec::new());
+static TEXT_BUFFERS: ConstStaticCell<TextBuffers> = ConstStaticCell::new(TextBuffers::new());
+static STOPWATCH_LAPS_BUFFER: ConstStaticCell<heapless::String<STOPWATCH_LAPS_LEN>> =
+    ConstStaticCell::new(heapless::String::new());
+
+/// Every panel pixel becomes a square of this many pixels in the screen image.
+const SCREEN_SCALE: usize = 4;
+/// Fits the PNG of any frame at [`SCREEN_SCALE`], even one without a single repeated pixel.
+const SCREEN_BUFFER_SIZE: usize = 2048;
+const SCREEN_INTERVAL: embassy_time::Duration = embassy_time::Duration::from_secs(30);
+/// Room for the lap list published as attributes of the last lap sensor.
+const STOPWATCH_LAPS_LEN: usize = 256;
+
+/// Lists and JSON commands can take the full text length Home Assistant allows.
+type LongTextBuffer = embassy_ha::TextBuffer<{ embassy_ha::TEXT_MAX_LENGTH }>;
+/// Fits `#RRGGBB`.
+type ColorTextBuffer = embassy_ha::TextBuffer<7>;
+
+/// Values of every text entity, each sized for what the entity holds.
+struct TextBuffers {
+    page_transition: LongTextBuffer,
+    pages: LongTextBuffer,
+    custom_app: LongTextBuffer,
+    notify: LongTextBuffer,
+    alarm_settings: [LongTextBuffer; ALARM_COUNT],
+    countdowns: LongTextBuffer,
+    clock_colors: [ColorTextBuffer; 3],
+    night_texts: [LongTextBuffer; 3],
+    entity_widgets: LongTextBuffer,
+    chart_sample: LongTextBuffer,
+}
+
+impl TextBuffers {
+    const fn new() -> Self {
+        TextBuffers {
+            page_transition: LongTextBuffer::new(),
+            pages: LongTextBuffer::new(),
+            custom_app: LongTextBuffer::new(),
+            notify: LongTextBuffer::new(),
+            alarm_settings: [const { LongTextBuffer::new() }; ALARM_COUNT],
+            countdowns: LongTextBuffer::new(),
+            clock_colors: [const { ColorTextBuffer::new() }; 3],
+            night_texts: [const { LongTextBuffer::new() }; 3],
+            entity_widgets: LongTextBuffer::new(),
+            chart_sample: LongTextBuffer::new(),
+        }
+    }
+}
+
+/// Ids and names of the clock color texts: digits, calendar header and current weekday.
+const CLOCK_COLOR_ENTITIES: [(&str, &str); 3] = [
+    ("clock_digit_color", "Clock Digit Color"),
+    ("clock_calendar_color", "Clock Calendar Color"),
+    ("clock_weekday_color", "Clock Weekday Color"),
+];
+
+/// Ids, names and icons of the night mode texts: schedule, location and pages.
+const NIGHT_TEXT_ENTITIES: [(&str, &str, &str); 3] = [
+    ("night_schedule", "Night Schedule", "mdi:clock-time-ten-outline"),
+    ("night_location", "Night Location", "mdi:map-marker"),
+    ("night_pages", "Night Pages", "mdi:view-carousel-outline"),
+];
+
+const DISPLAY_EFFECTS: [&str; 2] = ["auto", "manual"];
+const DISPLAY_EFFECT_AUTO: usize = 0;
+const DISPLAY_EFFECT_MANUAL: usize = 1;
+
+const POMODORO_PHASES: [&str; 4] = ["stopped", "work", "short_break", "long_break"];
+const POMODORO_PHASE_STOPPED: usize = 0;
+
+/// Switch and settings text ids and names, one pair per alarm slot.
+const ALARM_ENTITIES: [(&str, &str, &str, &str); ALARM_COUNT] = [
+    ("alarm1", "Alarm 1", "alarm1_settings", "Alarm 1 Settings"),
+    ("alarm2", "Alarm 2", "alarm2_settings", "Alarm 2 Settings"),
+    ("alarm3", "Alarm 3", "alarm3_settings", "Alarm 3 Settings"),
+];
+
+const GAME_ENTITIES: [(GameKind, &str, &str); 3] = [
+    (GameKind::Snake, "snake_high_score", "Snake High Score"),
+    (GameKind::Breakout, "breakout_high_score", "Breakout High Score"),
+    (GameKind::Runner, "runner_high_score", "Runner High Score"),
+];
 
 #[embassy_executor::task]
 pub async fn ha_task(spawner: Spawner, stack: embassy_net::Stack<'static>, mac_address: [u8; 6]) {
@@ -43,48 +141,68 @@ pub async fn ha_task(spawner: Spawner, stack: embassy_net::Stack<'static>, mac_a
     let device_id = Box::leak(device_id.into_boxed_str());
 
     let device = embassy_ha::new(
-        RESOURCES.init(Default::default()),
+        RESOURCES.take(),
         embassy_ha::DeviceConfig { device_id, device_name: device_id, manufacturer: "Dragonn", model: "RWTRIX 3" },
     );
+    info!("HA resources take {} bytes", core::mem::size_of::<embassy_ha::DeviceResources>());
+    let text_buffers = TEXT_BUFFERS.take();
 
-    let switch_indicator1 = embassy_ha::create_switch(
+    let light_indicator1 = embassy_ha::create_light(
         &device,
         "ind1",
-        embassy_ha::SwitchConfig {
+        embassy_ha::LightConfig {
             common: embassy_ha::EntityCommonConfig {
                 name: Some("Indicator 1"),
                 icon: Some("mdi:arrow-top-right-thick"),
                 ..Default::default()
             },
-            class: embassy_ha::SwitchClass::Generic,
+            color_mode: embassy_ha::LightColorMode::Rgb,
+            effects: &state::IndicatorMode::NAMES,
             command_policy: embassy_ha::CommandPolicy::PublishState,
         },
     );
 
-    let switch_indicator2 = embassy_ha::create_switch(
+    let light_indicator2 = embassy_ha::create_light(
         &device,
         "ind2",
-        embassy_ha::SwitchConfig {
+        embassy_ha::LightConfig {
             common: embassy_ha::EntityCommonConfig {
                 name: Some("Indicator 2"),
                 icon: Some("mdi:arrow-right-thick"),
                 ..Default::default()
             },
-            class: embassy_ha::SwitchClass::Generic,
+            color_mode: embassy_ha::LightColorMode::Rgb,
+            effects: &state::IndicatorMode::NAMES,
             command_policy: embassy_ha::CommandPolicy::PublishState,
         },
     );
 
-    let switch_indicator3 = embassy_ha::create_switch(
+    let light_indicator3 = embassy_ha::create_light(
         &device,
         "ind3",
-        embassy_ha::SwitchConfig {
+        embassy_ha::LightConfig {
             common: embassy_ha::EntityCommonConfig {
                 name: Some("Indicator 3"),
                 icon: Some("mdi:arrow-bottom-right-thick"),
                 ..Default::default()
             },
-            class: embassy_ha::SwitchClass::Generic,
+            color_mode: embassy_ha::LightColorMode::Rgb,
+            effects: &state::IndicatorMode::NAMES,
+            command_policy: embassy_ha::CommandPolicy::PublishState,
+        },
+    );
+
+    let display = embassy_ha::create_light(
+        &device,
+        "display",
+        embassy_ha::LightConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Display"),
+                icon: Some("mdi:led-strip"),
+                ..Default::default()
+            },
+            color_mode: embassy_ha::LightColorMode::Brightness,
+            effects: &DISPLAY_EFFECTS,
             command_policy: embassy_ha::CommandPolicy::PublishState,
         },
     );
@@ -103,6 +221,86 @@ pub async fn ha_task(spawner: Spawner, stack: embassy_net::Stack<'static>, mac_a
         },
     );
 
+    // the options are fixed once discovered, pages added later show up after a restart
+    let page_names: Vec<&'static str> =
+        page_registry::get_entries().into_iter().map(|entry| &*Box::leak(entry.name.into_boxed_str())).collect();
+    let page = embassy_ha::create_select(
+        &device,
+        "page",
+        embassy_ha::SelectConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Page"),
+                icon: Some("mdi:book-open-page-variant"),
+                ..Default::default()
+            },
+            options: Box::leak(page_names.into_boxed_slice()),
+            command_policy: embassy_ha::CommandPolicy::PublishState,
+        },
+    );
+
+    let transition_effect = embassy_ha::create_select(
+        &device,
+        "transition_effect",
+        embassy_ha::SelectConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Transition Effect"),
+                icon: Some("mdi:transition"),
+                ..Default::default()
+            },
+            options: &TransitionEffect::NAMES,
+            command_policy: embassy_ha::CommandPolicy::PublishState,
+        },
+    );
+
+    let transition_duration = embassy_ha::create_number(
+        &device,
+        "transition_duration",
+        embassy_ha::NumberConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Transition Duration"),
+                icon: Some("mdi:timer-sand"),
+                ..Default::default()
+            },
+            unit: Some(embassy_ha::NumberUnit::Other(embassy_ha::constants::HA_UNIT_TIME_MILLISECONDS)),
+            min: Some(crate::matrix::transition::MIN_TRANSITION_DURATION_MS as f32),
+            max: Some(crate::matrix::transition::MAX_TRANSITION_DURATION_MS as f32),
+            step: Some(50.0),
+            mode: embassy_ha::NumberMode::Box,
+            class: embassy_ha::NumberClass::Duration,
+            command_policy: embassy_ha::CommandPolicy::PublishState,
+        },
+    );
+
+    let page_transition = embassy_ha::create_text(
+        &device,
+        "page_transition",
+        embassy_ha::TextConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Page Transition"),
+                icon: Some("mdi:page-next"),
+                ..Default::default()
+            },
+            command_policy: embassy_ha::CommandPolicy::Manual,
+            ..Default::default()
+        },
+        &mut text_buffers.page_transition,
+    );
+
+    let pages = embassy_ha::create_text(
+        &device,
+        "pages",
+        embassy_ha::TextConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Pages"),
+                icon: Some("mdi:view-carousel"),
+                ..Default::default()
+            },
+            command_policy: embassy_ha::CommandPolicy::Manual,
+            ..Default::default()
+        },
+        &mut text_buffers.pages,
+    );
+
     let heap_usage = embassy_ha::create_sensor(
         &device,
         "heap_usage",
@@ -127,13 +325,616 @@ pub async fn ha_task(spawner: Spawner, stack: embassy_net::Stack<'static>, mac_a
         },
     );
 
+    let custom_app = embassy_ha::create_text(
+        &device,
+        "custom_app",
+        embassy_ha::TextConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Custom App"),
+                icon: Some("mdi:application-edit"),
+                ..Default::default()
+            },
+            command_policy: embassy_ha::CommandPolicy::Manual,
+            ..Default::default()
+        },
+        &mut text_buffers.custom_app,
+    );
+
+    let notify = embassy_ha::create_text(
+        &device,
+        "notify",
+        embassy_ha::TextConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Notify"),
+                icon: Some("mdi:message-alert"),
+                ..Default::default()
+            },
+            command_policy: embassy_ha::CommandPolicy::Manual,
+            ..Default::default()
+        },
+        &mut text_buffers.notify,
+    );
+
+    let dismiss_notification = embassy_ha::create_button(
+        &device,
+        "dismiss_notification",
+        embassy_ha::ButtonConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Dismiss Notification"),
+                icon: Some("mdi:message-off"),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let pomodoro_phase = embassy_ha::create_select(
+        &device,
+        "pomodoro_phase",
+        embassy_ha::SelectConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Pomodoro Phase"),
+                icon: Some("mdi:timer-outline"),
+                ..Default::default()
+            },
+            options: &POMODORO_PHASES,
+            command_policy: embassy_ha::CommandPolicy::Manual,
+        },
+    );
+
+    let pomodoro_remaining = embassy_ha::create_sensor(
+        &device,
+        "pomodoro_remaining",
+        embassy_ha::SensorConfig {
+            common: embassy_ha::EntityCommonConfig { name: Some("Pomodoro Remaining"), ..Default::default() },
+            state_class: embassy_ha::StateClass::Measurement,
+            class: embassy_ha::SensorClass::Duration,
+            unit: Some(embassy_ha::constants::HA_UNIT_TIME_SECONDS),
+            suggested_display_precision: Some(0),
+        },
+    );
+
+    let pomodoro_sessions = embassy_ha::create_sensor(
+        &device,
+        "pomodoro_sessions",
+        embassy_ha::SensorConfig {
+            common: embassy_ha::EntityCommonConfig { name: Some("Pomodoro Sessions"), ..Default::default() },
+            state_class: embassy_ha::StateClass::Measurement,
+            class: embassy_ha::SensorClass::Generic,
+            unit: None,
+            suggested_display_precision: Some(0),
+        },
+    );
+
+    let pomodoro_start = embassy_ha::create_button(
+        &device,
+        "pomodoro_start",
+        embassy_ha::ButtonConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Pomodoro Start/Pause"),
+                icon: Some("mdi:play-pause"),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let pomodoro_stop = embassy_ha::create_button(
+        &device,
+        "pomodoro_stop",
+        embassy_ha::ButtonConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Pomodoro Stop"),
+                icon: Some("mdi:stop"),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let pomodoro_skip = embassy_ha::create_button(
+        &device,
+        "pomodoro_skip",
+        embassy_ha::ButtonConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Pomodoro Skip"),
+                icon: Some("mdi:skip-next"),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let pomodoro_work = create_pomodoro_minutes(&device, "pomodoro_work", "Pomodoro Work");
+    let pomodoro_short_break = create_pomodoro_minutes(&device, "pomodoro_short_break", "Pomodoro Short Break");
+    let pomodoro_long_break = create_pomodoro_minutes(&device, "pomodoro_long_break", "Pomodoro Long Break");
+
+    let alarm_switches = ALARM_ENTITIES.map(|(id, name, _, _)| {
+        embassy_ha::create_switch(
+            &device,
+            id,
+            embassy_ha::SwitchConfig {
+                common: embassy_ha::EntityCommonConfig {
+                    name: Some(name),
+                    icon: Some("mdi:alarm"),
+                    ..Default::default()
+                },
+                class: embassy_ha::SwitchClass::Generic,
+                command_policy: embassy_ha::CommandPolicy::Manual,
+            },
+        )
+    });
+
+    let mut alarm_buffers = text_buffers.alarm_settings.iter_mut();
+    let alarm_settings = ALARM_ENTITIES.map(|(_, _, id, name)| {
+        embassy_ha::create_text(
+            &device,
+            id,
+            embassy_ha::TextConfig {
+                common: embassy_ha::EntityCommonConfig {
+                    name: Some(name),
+                    icon: Some("mdi:alarm-edit"),
+                    ..Default::default()
+                },
+                command_policy: embassy_ha::CommandPolicy::Manual,
+                ..Default::default()
+            },
+            alarm_buffers.next().unwrap(),
+        )
+    });
+
+    let alarm_snooze = embassy_ha::create_number(
+        &device,
+        "alarm_snooze",
+        embassy_ha::NumberConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Alarm Snooze"),
+                icon: Some("mdi:alarm-snooze"),
+                ..Default::default()
+            },
+            unit: Some(embassy_ha::NumberUnit::Other(embassy_ha::constants::HA_UNIT_TIME_MINUTES)),
+            min: Some(alarm::MIN_SNOOZE_MINUTES as f32),
+            max: Some(alarm::MAX_SNOOZE_MINUTES as f32),
+            step: Some(1.0),
+            mode: embassy_ha::NumberMode::Box,
+            class: embassy_ha::NumberClass::Duration,
+            command_policy: embassy_ha::CommandPolicy::PublishState,
+        },
+    );
+
+    let stopwatch_last_lap = embassy_ha::create_sensor_with_attributes(
+        &device,
+        "stopwatch_last_lap",
+        embassy_ha::SensorConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Stopwatch Last Lap"),
+                icon: Some("mdi:timer-check-outline"),
+                ..Default::default()
+            },
+            state_class: embassy_ha::StateClass::Measurement,
+            class: embassy_ha::SensorClass::Duration,
+            unit: Some(embassy_ha::constants::HA_UNIT_TIME_SECONDS),
+            suggested_display_precision: Some(2),
+        },
+        STOPWATCH_LAPS_BUFFER.take().as_mut_view(),
+    );
+
+    let countdowns = embassy_ha::create_text(
+        &device,
+        "countdowns",
+        embassy_ha::TextConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Countdowns"),
+                icon: Some("mdi:calendar-clock"),
+                ..Default::default()
+            },
+            command_policy: embassy_ha::CommandPolicy::Manual,
+            ..Default::default()
+        },
+        &mut text_buffers.countdowns,
+    );
+
+    let clock_face = embassy_ha::create_select(
+        &device,
+        "clock_face",
+        embassy_ha::SelectConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Clock Face"),
+                icon: Some("mdi:clock-outline"),
+                ..Default::default()
+            },
+            options: &ClockFace::NAMES,
+            command_policy: embassy_ha::CommandPolicy::Manual,
+        },
+    );
+
+    let clock_hour_format = embassy_ha::create_select(
+        &device,
+        "clock_hour_format",
+        embassy_ha::SelectConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Clock Hour Format"),
+                icon: Some("mdi:clock-digital"),
+                ..Default::default()
+            },
+            options: &HourFormat::NAMES,
+            command_policy: embassy_ha::CommandPolicy::Manual,
+        },
+    );
+
+    let clock_week_start = embassy_ha::create_select(
+        &device,
+        "clock_week_start",
+        embassy_ha::SelectConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Clock Week Start"),
+                icon: Some("mdi:calendar-week-begin"),
+                ..Default::default()
+            },
+            options: &WeekStart::NAMES,
+            command_policy: embassy_ha::CommandPolicy::Manual,
+        },
+    );
+
+    let clock_blink = embassy_ha::create_select(
+        &device,
+        "clock_blink",
+        embassy_ha::SelectConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Clock Blink"),
+                icon: Some("mdi:timer-outline"),
+                ..Default::default()
+            },
+            options: &BlinkStyle::NAMES,
+            command_policy: embassy_ha::CommandPolicy::Manual,
+        },
+    );
+
+    let clock_seconds = embassy_ha::create_switch(
+        &device,
+        "clock_seconds",
+        embassy_ha::SwitchConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Clock Seconds"),
+                icon: Some("mdi:clock-time-four-outline"),
+                ..Default::default()
+            },
+            class: embassy_ha::SwitchClass::Generic,
+            command_policy: embassy_ha::CommandPolicy::Manual,
+        },
+    );
+
+    let mut color_buffers = text_buffers.clock_colors.iter_mut();
+    let clock_colors = CLOCK_COLOR_ENTITIES.map(|(id, name)| {
+        embassy_ha::create_text(
+            &device,
+            id,
+            embassy_ha::TextConfig {
+                common: embassy_ha::EntityCommonConfig {
+                    name: Some(name),
+                    icon: Some("mdi:palette"),
+                    ..Default::default()
+                },
+                pattern: Some("^#?[0-9A-Fa-f]{6}$"),
+                command_policy: embassy_ha::CommandPolicy::Manual,
+                ..Default::default()
+            },
+            color_buffers.next().unwrap(),
+        )
+    });
+
+    let date_language = embassy_ha::create_select(
+        &device,
+        "date_language",
+        embassy_ha::SelectConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Date Language"),
+                icon: Some("mdi:translate"),
+                ..Default::default()
+            },
+            options: &Language::NAMES,
+            command_policy: embassy_ha::CommandPolicy::Manual,
+        },
+    );
+
+    let date_order = embassy_ha::create_select(
+        &device,
+        "date_order",
+        embassy_ha::SelectConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Date Order"),
+                icon: Some("mdi:calendar-text"),
+                ..Default::default()
+            },
+            options: &DateOrder::NAMES,
+            command_policy: embassy_ha::CommandPolicy::Manual,
+        },
+    );
+
+    let date_separator = embassy_ha::create_select(
+        &device,
+        "date_separator",
+        embassy_ha::SelectConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Date Separator"),
+                icon: Some("mdi:format-text"),
+                ..Default::default()
+            },
+            options: &Separator::NAMES,
+            command_policy: embassy_ha::CommandPolicy::Manual,
+        },
+    );
+
+    let date_month_names = embassy_ha::create_switch(
+        &device,
+        "date_month_names",
+        embassy_ha::SwitchConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Date Month Names"),
+                icon: Some("mdi:calendar-month"),
+                ..Default::default()
+            },
+            class: embassy_ha::SwitchClass::Generic,
+            command_policy: embassy_ha::CommandPolicy::Manual,
+        },
+    );
+
+    let date_weekday = embassy_ha::create_switch(
+        &device,
+        "date_weekday",
+        embassy_ha::SwitchConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Date Weekday"),
+                icon: Some("mdi:calendar-today"),
+                ..Default::default()
+            },
+            class: embassy_ha::SwitchClass::Generic,
+            command_policy: embassy_ha::CommandPolicy::Manual,
+        },
+    );
+
+    let night_mode = embassy_ha::create_switch(
+        &device,
+        "night_mode",
+        embassy_ha::SwitchConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Night Mode"),
+                icon: Some("mdi:weather-night"),
+                ..Default::default()
+            },
+            class: embassy_ha::SwitchClass::Generic,
+            command_policy: embassy_ha::CommandPolicy::Manual,
+        },
+    );
+
+    let night_window = embassy_ha::create_select(
+        &device,
+        "night_window",
+        embassy_ha::SelectConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Night Window"),
+                icon: Some("mdi:theme-light-dark"),
+                ..Default::default()
+            },
+            options: &NightWindow::NAMES,
+            command_policy: embassy_ha::CommandPolicy::Manual,
+        },
+    );
+
+    let night_action = embassy_ha::create_select(
+        &device,
+        "night_action",
+        embassy_ha::SelectConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Night Action"),
+                icon: Some("mdi:sleep"),
+                ..Default::default()
+            },
+            options: &NightAction::NAMES,
+            command_policy: embassy_ha::CommandPolicy::Manual,
+        },
+    );
+
+    let mut night_buffers = text_buffers.night_texts.iter_mut();
+    let night_texts = NIGHT_TEXT_ENTITIES.map(|(id, name, icon)| {
+        embassy_ha::create_text(
+            &device,
+            id,
+            embassy_ha::TextConfig {
+                common: embassy_ha::EntityCommonConfig { name: Some(name), icon: Some(icon), ..Default::default() },
+                command_policy: embassy_ha::CommandPolicy::Manual,
+                ..Default::default()
+            },
+            night_buffers.next().unwrap(),
+        )
+    });
+
+    let night_brightness = embassy_ha::create_number(
+        &device,
+        "night_brightness",
+        embassy_ha::NumberConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Night Brightness"),
+                icon: Some("mdi:brightness-4"),
+                ..Default::default()
+            },
+            min: Some(1.0),
+            max: Some(255.0),
+            step: Some(1.0),
+            mode: embassy_ha::NumberMode::Slider,
+            command_policy: embassy_ha::CommandPolicy::Manual,
+            ..Default::default()
+        },
+    );
+
+    let night_light_threshold = embassy_ha::create_number(
+        &device,
+        "night_light_threshold",
+        embassy_ha::NumberConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Night Light Threshold"),
+                icon: Some("mdi:brightness-auto"),
+                ..Default::default()
+            },
+            unit: Some(embassy_ha::NumberUnit::Other(embassy_ha::constants::HA_UNIT_PERCENTAGE)),
+            min: Some(0.0),
+            max: Some(100.0),
+            step: Some(1.0),
+            mode: embassy_ha::NumberMode::Slider,
+            command_policy: embassy_ha::CommandPolicy::Manual,
+            ..Default::default()
+        },
+    );
+
+    let night_wake = embassy_ha::create_number(
+        &device,
+        "night_wake",
+        embassy_ha::NumberConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Night Wake Time"),
+                icon: Some("mdi:gesture-tap-button"),
+                ..Default::default()
+            },
+            unit: Some(embassy_ha::NumberUnit::Other(embassy_ha::constants::HA_UNIT_TIME_SECONDS)),
+            min: Some(0.0),
+            max: Some(night::MAX_WAKE_SECS as f32),
+            step: Some(5.0),
+            mode: embassy_ha::NumberMode::Box,
+            class: embassy_ha::NumberClass::Duration,
+            command_policy: embassy_ha::CommandPolicy::Manual,
+        },
+    );
+
+    let entity_widgets = embassy_ha::create_text(
+        &device,
+        "entity_widgets",
+        embassy_ha::TextConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Entity Widgets"),
+                icon: Some("mdi:widgets"),
+                ..Default::default()
+            },
+            command_policy: embassy_ha::CommandPolicy::Manual,
+            ..Default::default()
+        },
+        &mut text_buffers.entity_widgets,
+    );
+
+    // like the page options, topics of widgets added later are only subscribed to after a restart
+    entity_page::wait_for_load().await;
+    let mut topics: Vec<String> = entity_page::get_widgets().into_iter().map(|widget| widget.topic).collect();
+    topics.sort_unstable();
+    topics.dedup();
+    let entity_values = topics
+        .into_iter()
+        .map(|topic| embassy_ha::create_subscription(&device, Box::leak(topic.into_boxed_str())))
+        .collect::<Vec<_>>();
+
+    let chart_sample = embassy_ha::create_text(
+        &device,
+        "chart_sample",
+        embassy_ha::TextConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Chart Sample"),
+                icon: Some("mdi:chart-bar"),
+                ..Default::default()
+            },
+            command_policy: embassy_ha::CommandPolicy::Manual,
+            ..Default::default()
+        },
+        &mut text_buffers.chart_sample,
+    );
+
+    let high_scores = GAME_ENTITIES.map(|(_, id, name)| {
+        embassy_ha::create_sensor(
+            &device,
+            id,
+            embassy_ha::SensorConfig {
+                common: embassy_ha::EntityCommonConfig {
+                    name: Some(name),
+                    icon: Some("mdi:trophy-outline"),
+                    ..Default::default()
+                },
+                state_class: embassy_ha::StateClass::Measurement,
+                class: embassy_ha::SensorClass::Generic,
+                unit: None,
+                suggested_display_precision: Some(0),
+            },
+        )
+    });
+
+    let screen_image = embassy_ha::create_image(
+        &device,
+        "screen",
+        embassy_ha::ImageConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Screen"),
+                icon: Some("mdi:monitor-screenshot"),
+                ..Default::default()
+            },
+            content_type: Some(embassy_ha::constants::HA_IMAGE_CONTENT_TYPE_PNG),
+        },
+        SCREEN_BUFFER.take(),
+    );
+
+    let screen_refresh = embassy_ha::create_button(
+        &device,
+        "screen_refresh",
+        embassy_ha::ButtonConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some("Screen Refresh"),
+                icon: Some("mdi:camera"),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
     spawner.must_spawn(heap_class(heap_usage, heap_max_usage));
-    spawner.must_spawn(switch_class(switch_indicator1, 0));
-    spawner.must_spawn(switch_class(switch_indicator2, 1));
-    spawner.must_spawn(switch_class(switch_indicator3, 2));
+    spawner.must_spawn(indicator_class(light_indicator1, 0));
+    spawner.must_spawn(indicator_class(light_indicator2, 1));
+    spawner.must_spawn(indicator_class(light_indicator3, 2));
 
+    spawner.must_spawn(display_class(display));
     spawner.must_spawn(transition_class(switch_transition));
+    spawner.must_spawn(transition_effect_class(transition_effect));
+    spawner.must_spawn(transition_duration_class(transition_duration));
+    spawner.must_spawn(page_transition_class(page_transition));
+    spawner.must_spawn(pages_class(pages));
+    spawner.must_spawn(page_class(page));
+    spawner.must_spawn(custom_app_class(custom_app));
+    spawner.must_spawn(notify_class(notify));
+    spawner.must_spawn(dismiss_notification_class(dismiss_notification));
+    spawner.must_spawn(pomodoro_class(pomodoro_phase, pomodoro_remaining, pomodoro_sessions));
+    spawner.must_spawn(pomodoro_button_class(pomodoro_start, pomodoro::toggle));
+    spawner.must_spawn(pomodoro_button_class(pomodoro_stop, pomodoro::stop));
+    spawner.must_spawn(pomodoro_button_class(pomodoro_skip, pomodoro::skip));
+    spawner.must_spawn(pomodoro_minutes_class(pomodoro_work, Phase::Work));
+    spawner.must_spawn(pomodoro_minutes_class(pomodoro_short_break, Phase::ShortBreak));
+    spawner.must_spawn(pomodoro_minutes_class(pomodoro_long_break, Phase::LongBreak));
+    spawner.must_spawn(alarms_class(alarm_switches, alarm_settings, alarm_snooze));
+    spawner.must_spawn(stopwatch_class(stopwatch_last_lap));
+    spawner.must_spawn(countdowns_class(countdowns));
+    spawner.must_spawn(clock_class(
+        [clock_face, clock_hour_format, clock_week_start, clock_blink],
+        clock_seconds,
+        clock_colors,
+    ));
+    spawner.must_spawn(date_class([date_language, date_order, date_separator], [date_month_names, date_weekday]));
+    spawner.must_spawn(night_class(
+        night_mode,
+        [night_window, night_action],
+        night_texts,
+        [night_brightness, night_light_threshold, night_wake],
+    ));
 
+    spawner.must_spawn(entity_widgets_class(entity_widgets));
+    for subscription in entity_values {
+        spawner.must_spawn(entity_value_class(subscription));
+    }
+    spawner.must_spawn(chart_sample_class(chart_sample));
+    spawner.must_spawn(high_scores_class(high_scores));
+    spawner.must_spawn(screen_class(screen_image, screen_refresh));
     spawner.must_spawn(state());
 
     let mqtt_params =
@@ -145,9 +946,76 @@ pub async fn ha_task(spawner: Spawner, stack: embassy_net::Stack<'static>, mac_a
 }
 
 #[embassy_executor::task(pool_size = 3)]
-async fn switch_class(mut switch: embassy_ha::Switch<'static>, index: usize) {
+async fn indicator_class(mut light: embassy_ha::Light<'static>, index: usize) {
+    let indicator = state::get_indicator(index);
+    light.set(embassy_ha::LightValue {
+        state: if indicator.on { BinaryState::On } else { BinaryState::Off },
+        brightness: indicator.brightness,
+        color: indicator.color,
+        effect: Some(indicator.mode.index()),
+    });
+
+    loop {
+        let value = light.wait().await;
+        let current = state::get_indicator(index);
+        let mode = value.effect.and_then(state::IndicatorMode::from_index).unwrap_or(current.mode);
+        state::set_indicator(
+            index,
+            state::Indicator {
+                on: value.state == BinaryState::On,
+                color: value.color,
+                brightness: value.brightness,
+                mode,
+                period_ms: if mode == current.mode { current.period_ms } else { state::DEFAULT_INDICATOR_PERIOD_MS },
+            },
+        );
+    }
+}
+
+fn display_light_value(display: state::DisplayState) -> embassy_ha::LightValue {
+    embassy_ha::LightValue {
+        state: if display.on { BinaryState::On } else { BinaryState::Off },
+        brightness: display.brightness.unwrap_or_else(crate::matrix::auto_brightness),
+        color: [255, 255, 255],
+        effect: Some(if display.brightness.is_some() { DISPLAY_EFFECT_MANUAL } else { DISPLAY_EFFECT_AUTO }),
+    }
+}
+
+/// Moving the brightness slider switches to manual mode, picking the `auto` effect goes back to the light sensor.
+#[embassy_executor::task]
+async fn display_class(mut light: embassy_ha::Light<'static>) {
+    loop {
+        let current = state::get_display_state();
+        let published = display_light_value(current);
+        light.set(published);
+
+        // in auto mode the brightness is republished as the ambient light changes
+        let value = match select(light.wait(), Timer::after(embassy_time::Duration::from_secs(10))).await {
+            Either::First(value) => value,
+            Either::Second(_) => continue,
+        };
+        let brightness = if value.effect == Some(DISPLAY_EFFECT_AUTO) && published.effect != value.effect {
+            None
+        } else if value.brightness != published.brightness || value.effect == Some(DISPLAY_EFFECT_MANUAL) {
+            Some(value.brightness)
+        } else {
+            current.brightness
+        };
+        state::set_display_state(state::DisplayState { on: value.state == BinaryState::On, brightness });
+    }
+}
+
+#[embassy_executor::task]
+async fn page_class(mut page_select: embassy_ha::Select<'static>) {
+    let mut current = page_registry::get_current_page();
     loop {
-        state::set_indicator_state(index, switch.wait().await.into());
+        if let Some(index) = page_select.options().iter().position(|option| *option == current) {
+            page_select.set(index);
+        }
+        match select(page_select.wait(), page_registry::wait_for_current_page_change()).await {
+            Either::First(index) => page_registry::request_page(page_select.options()[index]),
+            Either::Second(page) => current = page,
+        }
     }
 }
 
@@ -165,6 +1033,384 @@ async fn transition_class(mut switch: embassy_ha::Switch<'static>) {
     }
 }
 
+#[embassy_executor::task]
+async fn transition_effect_class(mut select: embassy_ha::Select<'static>) {
+    select.set(state::get_transition_effect().index());
+    loop {
+        if let Some(effect) = TransitionEffect::from_index(select.wait().await) {
+            state::set_transition_effect(effect);
+        }
+    }
+}
+
+#[embassy_executor::task]
+async fn transition_duration_class(mut number: embassy_ha::Number<'static>) {
+    number.publish(state::get_transition_duration().as_millis() as f32);
+    loop {
+        state::set_transition_duration(number.wait().await as u32);
+    }
+}
+
+/// Accepts `page=effect` to override the transition of a single page, `page=` goes back to the global effect. The
+/// state is published back as the list of overrides.
+#[embassy_executor::task]
+async fn page_transition_class(mut text: embassy_ha::Text<'static>) {
+    text.set(&state::get_page_transitions_description());
+    loop {
+        let command = text.wait().await;
+        let (page, effect) = command.split_once('=').unwrap_or((command.as_str(), ""));
+        let page = page.trim();
+        let effect = effect.trim();
+        if page.is_empty() {
+            warn!("Invalid page transition {:?}: missing page name", command.as_str());
+        } else if effect.is_empty() {
+            state::set_page_transition_effect(page, None);
+        } else if let Some(effect) = TransitionEffect::from_name(effect) {
+            state::set_page_transition_effect(page, Some(effect));
+        } else {
+            warn!("Invalid page transition {:?}: unknown effect {:?}", command.as_str(), effect);
+        }
+        text.set(&state::get_page_transitions_description());
+    }
+}
+
+/// Accepts `{"name": "..", "enabled": bool, "duration": secs, "position": n}`, the state is published back as the
+/// page order with disabled pages prefixed by `!`.
+#[embassy_executor::task]
+async fn pages_class(mut text: embassy_ha::Text<'static>) {
+    loop {
+        text.set(&page_registry::get_entries_description());
+        match select(text.wait(), page_registry::wait_for_change()).await {
+            Either::First(command) => {
+                if let Err(e) = page_registry::handle_command(&command) {
+                    warn!("Invalid page command {:?}: {:?}", command.as_str(), e);
+                }
+            }
+            Either::Second(_) => {}
+        }
+    }
+}
+
+/// Accepts `{"name": "..", "text": "..", "icon": "..", "color": [r, g, b], "duration": secs}`, the state is
+/// published back as the list of installed custom app names. Names of built-in pages are refused.
+#[embassy_executor::task]
+async fn custom_app_class(mut text: embassy_ha::Text<'static>) {
+    text.set(&custom_apps::get_custom_app_names());
+    loop {
+        let command = text.wait().await;
+        if let Err(e) = custom_apps::handle_command(&command) {
+            warn!("Invalid custom app command {:?}: {:?}", command.as_str(), e);
+        }
+        text.set(&custom_apps::get_custom_app_names());
+    }
+}
+
+/// Accepts `{"text": "..", "icon": "..", "color": [r, g, b], "duration": secs, "hold": bool, "priority": n}`.
+#[embassy_executor::task]
+async fn notify_class(mut text: embassy_ha::Text<'static>) {
+    loop {
+        let command = text.wait().await;
+        if let Err(e) = notifications::handle_command(&command) {
+            warn!("Invalid notification {:?}: {:?}", command.as_str(), e);
+        }
+    }
+}
+
+#[embassy_executor::task]
+async fn dismiss_notification_class(mut button: embassy_ha::Button<'static>) {
+    loop {
+        button.pressed().await;
+        notifications::request_dismiss();
+    }
+}
+
+/// Settings texts accept `{"time": "07:30", "days": "MTWTF..", "label": "..", "melody": "beep|chime|siren"}` with
+/// every field optional, the state is published back as `07:30 MTWTF.. beep label`. A single task keeps every alarm
+/// entity in sync since the alarms can also be toggled from the alarm page.
+#[embassy_executor::task]
+async fn alarms_class(
+    mut switches: [embassy_ha::Switch<'static>; ALARM_COUNT],
+    mut settings: [embassy_ha::Text<'static>; ALARM_COUNT],
+    mut snooze: embassy_ha::Number<'static>,
+) {
+    loop {
+        for (index, alarm) in alarm::get_alarms().iter().enumerate().take(ALARM_COUNT) {
+            switches[index].set(if alarm.enabled { BinaryState::On } else { BinaryState::Off });
+            settings[index].set(&alarm.description());
+        }
+        snooze.publish(alarm::get_snooze_minutes() as f32);
+
+        match select4(
+            select_array(switches.each_mut().map(|switch| switch.wait())),
+            select_array(settings.each_mut().map(|text| text.wait())),
+            snooze.wait(),
+            alarm::wait_for_change(),
+        )
+        .await
+        {
+            Either4::First((state, index)) => alarm::set_alarm_enabled(index, state == BinaryState::On),
+            Either4::Second((command, index)) => {
+                if let Err(e) = alarm::handle_command(index, &command) {
+                    warn!("Invalid alarm {} command {:?}: {:?}", index + 1, command.as_str(), e);
+                }
+            }
+            Either4::Third(minutes) => alarm::set_snooze_minutes(minutes as u8),
+            Either4::Fourth(_) => {}
+        }
+    }
+}
+
+/// The lap list is published as the `laps` attribute of the last lap sensor.
+#[embassy_executor::task]
+async fn stopwatch_class(mut last_lap: embassy_ha::Sensor<'static>) {
+    let mut laps = stopwatch::Laps::of(&Default::default());
+    loop {
+        last_lap.publish(laps.last_lap_ms.unwrap_or_default() as f32 / 1000.0);
+        last_lap.publish_attributes(&laps.json);
+        laps = stopwatch::wait_for_laps().await;
+    }
+}
+
+/// Accepts `{"name": "..", "date": "YYYY-MM-DD HH:MM", "mode": "until|since"}`, the state is published back as the
+/// list of countdowns. Countdowns can be edited over HTTP too, so changes from there are published as well.
+#[embassy_executor::task]
+async fn countdowns_class(mut text: embassy_ha::Text<'static>) {
+    loop {
+        text.set(&countdowns::description());
+        if let Either::First(command) = select(text.wait(), countdowns::wait_for_change()).await {
+            if let Err(e) = countdowns::handle_command(&command) {
+                warn!("Invalid countdown command {:?}: {:?}", command.as_str(), e);
+            }
+        }
+    }
+}
+
+/// The selects are the face, the hour format, the week start and the blink style, in that order. Colors are set as `#RRGGBB`.
+#[embassy_executor::task]
+async fn clock_class(
+    mut selects: [embassy_ha::Select<'static>; 4],
+    mut seconds: embassy_ha::Switch<'static>,
+    mut colors: [embassy_ha::Text<'static>; 3],
+) {
+    loop {
+        let settings = clock::get_settings();
+        selects[0].set(settings.face.index());
+        selects[1].set(settings.hour_format.index());
+        selects[2].set(settings.week_start.index());
+        selects[3].set(settings.blink.index());
+        seconds.set(if settings.show_seconds { BinaryState::On } else { BinaryState::Off });
+        for (text, color) in
+            colors.iter_mut().zip([settings.digit_color, settings.calendar_color, settings.weekday_color])
+        {
+            let mut value = String::with_capacity(7);
+            clock::write_color(&mut value, color);
+            text.set(&value);
+        }
+
+        match select3(
+            select_array(selects.each_mut().map(|select| select.wait())),
+            seconds.wait(),
+            select_array(colors.each_mut().map(|text| text.wait())),
+        )
+        .await
+        {
+            Either3::First((option, index)) => clock::modify_settings(|settings| match index {
+                0 => settings.face = ClockFace::from_index(option).unwrap_or_default(),
+                1 => settings.hour_format = HourFormat::from_index(option).unwrap_or_default(),
+                2 => settings.week_start = WeekStart::from_index(option).unwrap_or_default(),
+                _ => settings.blink = BlinkStyle::from_index(option).unwrap_or_default(),
+            }),
+            Either3::Second(state) => {
+                clock::modify_settings(|settings| settings.show_seconds = state == BinaryState::On)
+            }
+            Either3::Third((value, index)) => {
+                let Some(awtrix::Color(color)) = awtrix::Color::parse_hex(&value) else {
+                    warn!("Invalid clock color {:?}", value.as_str());
+                    continue;
+                };
+                clock::modify_settings(|settings| match index {
+                    0 => settings.digit_color = color,
+                    1 => settings.calendar_color = color,
+                    _ => settings.weekday_color = color,
+                });
+            }
+        }
+    }
+}
+
+/// The selects are the language, the order and the separator, the switches the month names and the weekday.
+#[embassy_executor::task]
+async fn date_class(mut selects: [embassy_ha::Select<'static>; 3], mut switches: [embassy_ha::Switch<'static>; 2]) {
+    loop {
+        let settings = date::get_settings();
+        selects[0].set(settings.language.index());
+        selects[1].set(settings.order.index());
+        selects[2].set(settings.separator.index());
+        for (switch, on) in switches.iter_mut().zip([settings.month_names, settings.weekday]) {
+            switch.set(if on { BinaryState::On } else { BinaryState::Off });
+        }
+
+        match select(
+            select_array(selects.each_mut().map(|select| select.wait())),
+            select_array(switches.each_mut().map(|switch| switch.wait())),
+        )
+        .await
+        {
+            Either::First((option, index)) => date::modify_settings(|settings| match index {
+                0 => settings.language = Language::from_index(option).unwrap_or_default(),
+                1 => settings.order = DateOrder::from_index(option).unwrap_or_default(),
+                _ => settings.separator = Separator::from_index(option).unwrap_or_default(),
+            }),
+            Either::Second((state, index)) => date::modify_settings(|settings| match index {
+                0 => settings.month_names = state == BinaryState::On,
+                _ => settings.weekday = state == BinaryState::On,
+            }),
+        }
+    }
+}
+
+/// The switch forces the night, the selects are the window and the action, the texts the schedule as `22:00-07:00`,
+/// the location as `lat,lon` and the pages kept at night, the numbers the brightness, the light threshold and the
+/// wake-up time.
+#[embassy_executor::task]
+async fn night_class(
+    mut forced: embassy_ha::Switch<'static>,
+    mut selects: [embassy_ha::Select<'static>; 2],
+    mut texts: [embassy_ha::Text<'static>; 3],
+    mut numbers: [embassy_ha::Number<'static>; 3],
+) {
+    loop {
+        let settings = night::get_settings();
+        forced.set(if settings.forced { BinaryState::On } else { BinaryState::Off });
+        selects[0].set(settings.window.index());
+        selects[1].set(settings.action.index());
+        for (index, text) in texts.iter_mut().enumerate() {
+            let mut value = String::new();
+            match index {
+                0 => settings.write_schedule(&mut value),
+                1 => settings.write_location(&mut value),
+                _ => settings.write_pages(&mut value),
+            }
+            text.set(&value);
+        }
+        for (number, value) in numbers.iter_mut().zip([
+            settings.brightness as f32,
+            settings.light_threshold as f32,
+            settings.wake_secs as f32,
+        ]) {
+            number.publish(value);
+        }
+
+        match select4(
+            forced.wait(),
+            select_array(selects.each_mut().map(|select| select.wait())),
+            select_array(texts.each_mut().map(|text| text.wait())),
+            select_array(numbers.each_mut().map(|number| number.wait())),
+        )
+        .await
+        {
+            Either4::First(state) => night::modify_settings(|settings| settings.forced = state == BinaryState::On),
+            Either4::Second((option, index)) => night::modify_settings(|settings| match index {
+                0 => settings.window = NightWindow::from_index(option).unwrap_or_default(),
+                _ => settings.action = NightAction::from_index(option).unwrap_or_default(),
+            }),
+            Either4::Third((value, 0)) => match night::parse_schedule(&value) {
+                Some((start, end)) => night::modify_settings(|settings| {
+                    (settings.start_minute, settings.end_minute) = (start, end);
+                }),
+                None => warn!("Invalid night schedule {:?}", value.as_str()),
+            },
+            Either4::Third((value, 1)) => match night::parse_location(&value) {
+                Some((latitude, longitude)) => night::modify_settings(|settings| {
+                    (settings.latitude, settings.longitude) = (latitude, longitude);
+                }),
+                None => warn!("Invalid night location {:?}", value.as_str()),
+            },
+            Either4::Third((value, _)) => {
+                night::modify_settings(|settings| settings.pages = night::parse_pages(&value))
+            }
+            Either4::Fourth((value, index)) => night::modify_settings(|settings| match index {
+                0 => settings.brightness = value.clamp(1.0, 255.0) as u8,
+                1 => settings.light_threshold = value.clamp(0.0, 100.0) as u8,
+                _ => settings.wake_secs = (value.max(0.0) as u16).min(night::MAX_WAKE_SECS),
+            }),
+        }
+    }
+}
+
+fn create_pomodoro_minutes(
+    device: &embassy_ha::Device<'static>,
+    id: &'static str,
+    name: &'static str,
+) -> embassy_ha::Number<'static> {
+    embassy_ha::create_number(
+        device,
+        id,
+        embassy_ha::NumberConfig {
+            common: embassy_ha::EntityCommonConfig {
+                name: Some(name),
+                icon: Some("mdi:timer-cog-outline"),
+                ..Default::default()
+            },
+            unit: Some(embassy_ha::NumberUnit::Other(embassy_ha::constants::HA_UNIT_TIME_MINUTES)),
+            min: Some(pomodoro::MIN_PHASE_MINUTES as f32),
+            max: Some(pomodoro::MAX_PHASE_MINUTES as f32),
+            step: Some(1.0),
+            mode: embassy_ha::NumberMode::Box,
+            class: embassy_ha::NumberClass::Duration,
+            command_policy: embassy_ha::CommandPolicy::PublishState,
+        },
+    )
+}
+
+/// Publishes the pomodoro progress every second, selecting a phase starts it and `stopped` stops the timer.
+#[embassy_executor::task]
+async fn pomodoro_class(
+    mut phase: embassy_ha::Select<'static>,
+    mut remaining: embassy_ha::Sensor<'static>,
+    mut sessions: embassy_ha::Sensor<'static>,
+) {
+    loop {
+        let status = pomodoro::get_status();
+        phase.set(if status.stopped {
+            POMODORO_PHASE_STOPPED
+        } else {
+            POMODORO_PHASES.iter().position(|name| *name == status.phase.name()).unwrap_or(POMODORO_PHASE_STOPPED)
+        });
+        remaining.publish(status.remaining_secs as f32);
+        sessions.publish(status.completed_sessions as f32);
+
+        if let Either::First(index) = select(phase.wait(), Timer::after(embassy_time::Duration::from_secs(1))).await {
+            match POMODORO_PHASES[index] {
+                "work" => pomodoro::start_phase(Phase::Work),
+                "short_break" => pomodoro::start_phase(Phase::ShortBreak),
+                "long_break" => pomodoro::start_phase(Phase::LongBreak),
+                _ => pomodoro::stop(),
+            }
+        }
+    }
+}
+
+#[embassy_executor::task(pool_size = 3)]
+async fn pomodoro_button_class(mut button: embassy_ha::Button<'static>, action: fn()) {
+    loop {
+        button.pressed().await;
+        action();
+    }
+}
+
+/// Phase lengths can also be changed from the timer page, so the value is refreshed periodically.
+#[embassy_executor::task(pool_size = 3)]
+async fn pomodoro_minutes_class(mut number: embassy_ha::Number<'static>, phase: Phase) {
+    loop {
+        number.publish((pomodoro::get_settings().duration_secs(phase) / 60) as f32);
+        if let Either::First(minutes) = select(number.wait(), Timer::after(embassy_time::Duration::from_secs(5))).await
+        {
+            pomodoro::set_phase_minutes(phase, minutes as u32);
+        }
+    }
+}
+
 #[embassy_executor::task]
 async fn state() {
     let receiver = MQTT_STATE_CHANNEL.receiver();
@@ -200,6 +1446,63 @@ async fn heap_class(mut heap_usage: embassy_ha::Sensor<'static>, mut heap_max_us
     }
 }
 
+/// Widgets are edited with the same `{"name": "..", "topic": "..", ..}` commands, a missing topic deletes one.
+#[embassy_executor::task]
+async fn entity_widgets_class(mut text: embassy_ha::Text<'static>) {
+    loop {
+        text.set(&entity_page::description());
+        let command = text.wait().await;
+        if let Err(e) = entity_page::handle_command(&command) {
+            warn!("Invalid entity widget command {:?}: {:?}", command.as_str(), e);
+        }
+    }
+}
+
+/// One per subscribed topic, at most one per widget.
+#[embassy_executor::task(pool_size = 8)]
+async fn entity_value_class(mut subscription: embassy_ha::Subscription<'static>) {
+    loop {
+        let payload = subscription.wait().await;
+        entity_page::set_value(subscription.topic(), &payload);
+    }
+}
+
+/// Samples are pushed as `{"name": "power", "value": 1250.5}`, e.g. from an automation triggered by a state change.
+#[embassy_executor::task]
+async fn chart_sample_class(mut text: embassy_ha::Text<'static>) {
+    loop {
+        text.set(&charts::get_series_names());
+        let command = text.wait().await;
+        if let Err(e) = charts::handle_sample(&command) {
+            warn!("Invalid chart sample {:?}: {:?}", command.as_str(), e);
+        }
+    }
+}
+
+/// Published once the stored scores are loaded and whenever a round beats one.
+#[embassy_executor::task]
+async fn high_scores_class(mut sensors: [embassy_ha::Sensor<'static>; 3]) {
+    loop {
+        let high_scores = games::get_high_scores();
+        for (sensor, (kind, _, _)) in sensors.iter_mut().zip(GAME_ENTITIES) {
+            sensor.publish(high_scores.get(kind) as f32);
+        }
+        games::wait_for_change().await;
+    }
+}
+
+/// Publishes a PNG of what the panel shows every [`SCREEN_INTERVAL`], the refresh button publishes one right away.
+#[embassy_executor::task]
+async fn screen_class(mut image: embassy_ha::Image<'static>, mut refresh: embassy_ha::Button<'static>) {
+    loop {
+        match screen::capture().await {
+            Some(pixels) => image.publish(&snapshot::encode_png(&pixels, crate::matrix::WIDTH as usize, SCREEN_SCALE)),
+            None => warn!("Screen capture for Home Assistant timed out"),
+        }
+        select(refresh.pressed(), Timer::after(SCREEN_INTERVAL)).await;
+    }
+}
+
 pub fn get_ha_state() -> HaState {
     HA_STATE.load(Ordering::Relaxed)
 }
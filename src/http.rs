@@ -0,0 +1,346 @@
+use alloc::{string::String, vec, vec::Vec};
+
+use embassy_net::tcp::TcpSocket;
+use embassy_time::Duration;
+use embedded_graphics::pixelcolor::RgbColor;
+use rwtrix_core::{
+    awtrix::{self, Route},
+    countdown::{self, CountdownError},
+    http::{Method, ParseError, Request, Response, StatusCode, MAX_REQUEST_SIZE},
+};
+
+use crate::{
+    countdowns,
+    custom_apps::{self, CustomApp, CustomAppError},
+    matrix::{screen, transition::TransitionEffect},
+    notifications::{self, Notification},
+    page_registry,
+    state::{self, Indicator, IndicatorMode},
+};
+
+const HTTP_PORT: u16 = 80;
+const SOCKET_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Result of handling a request, POST endpoints answer with a plain `OK` like AWTRIX does.
+enum Reply {
+    Ok,
+    Json(Vec<u8>),
+    Error(StatusCode, &'static str),
+}
+
+/// Serves a compatible subset of the AWTRIX 3 HTTP API, one connection at a time.
+#[embassy_executor::task]
+pub async fn http_task(stack: embassy_net::Stack<'static>) {
+    crate::wifi::wait_for_connection(&stack).await;
+
+    let mut rx_buffer = vec![0u8; 1024];
+    let mut tx_buffer = vec![0u8; 1024];
+    let mut request_buffer = vec![0u8; MAX_REQUEST_SIZE];
+    let mut response_buffer = Vec::new();
+
+    info!("HTTP server listening on port {}", HTTP_PORT);
+    loop {
+        let mut socket = TcpSocket::new(stack, &mut rx_buffer, &mut tx_buffer);
+        socket.set_timeout(Some(SOCKET_TIMEOUT));
+        if let Err(e) = socket.accept(HTTP_PORT).await {
+            warn!("HTTP accept failed: {:?}", e);
+            continue;
+        }
+
+        let reply = match read_request(&mut socket, &mut request_buffer).await {
+            Ok(len) => match rwtrix_core::http::parse_request(&request_buffer[..len]) {
+                Ok(request) => {
+                    info!("HTTP {:?} {}", request.method, request.path);
+                    handle_request(&request).await
+                }
+                Err(_) => Reply::Error(StatusCode::BadRequest, "Malformed request"),
+            },
+            Err(reply) => reply,
+        };
+
+        response_buffer.clear();
+        match &reply {
+            Reply::Ok => Response::text(StatusCode::Ok, "OK"),
+            Reply::Json(body) => Response::json(body),
+            Reply::Error(status, message) => Response::text(*status, message),
+        }
+        .write_to(&mut response_buffer);
+
+        if let Err(e) = write_all(&mut socket, &response_buffer).await {
+            warn!("HTTP write failed: {:?}", e);
+        }
+        socket.flush().await.ok();
+        socket.close();
+    }
+}
+
+/// Reads until a complete request is buffered, returning its length.
+async fn read_request(socket: &mut TcpSocket<'_>, buffer: &mut [u8]) -> Result<usize, Reply> {
+    let mut len = 0;
+    loop {
+        match rwtrix_core::http::parse_request(&buffer[..len]) {
+            Ok(_) => return Ok(len),
+            Err(ParseError::Invalid) => return Err(Reply::Error(StatusCode::BadRequest, "Malformed request")),
+            Err(ParseError::TooLarge) => return Err(Reply::Error(StatusCode::PayloadTooLarge, "Request too large")),
+            Err(ParseError::Incomplete) if len == buffer.len() => {
+                return Err(Reply::Error(StatusCode::PayloadTooLarge, "Request too large"));
+            }
+            Err(ParseError::Incomplete) => {}
+        }
+
+        match socket.read(&mut buffer[len..]).await {
+            Ok(0) | Err(_) => return Err(Reply::Error(StatusCode::BadRequest, "Connection closed")),
+            Ok(n) => len += n,
+        }
+    }
+}
+
+async fn write_all(socket: &mut TcpSocket<'_>, mut data: &[u8]) -> Result<(), embassy_net::tcp::Error> {
+    while !data.is_empty() {
+        let n = socket.write(data).await?;
+        data = &data[n..];
+    }
+    Ok(())
+}
+
+async fn handle_request(request: &Request<'_>) -> Reply {
+    let Some(route) = awtrix::route(request.path) else {
+        return Reply::Error(StatusCode::NotFound, "Not found");
+    };
+
+    match (route, request.method) {
+        (Route::Notify, Method::Post) => notify(request),
+        (Route::DismissNotification, Method::Post) => {
+            notifications::request_dismiss();
+            Reply::Ok
+        }
+        (Route::Custom, Method::Post) => custom(request),
+        (Route::Settings, Method::Get) => to_json(&settings()),
+        (Route::Settings, Method::Post) => update_settings(request),
+        (Route::Stats, Method::Get) => stats(),
+        (Route::Indicator(index), Method::Post) => indicator(request, index),
+        (Route::Screen, Method::Get) => screen().await,
+        (Route::Countdown, Method::Get) => to_json(&list_countdowns()),
+        (Route::Countdown, Method::Post) => update_countdown(request),
+        _ => Reply::Error(StatusCode::MethodNotAllowed, "Method not allowed"),
+    }
+}
+
+fn notify(request: &Request<'_>) -> Reply {
+    let command = match awtrix::from_json::<awtrix::NotifyRequest>(request.body) {
+        Ok(command) => command,
+        Err(_) => return Reply::Error(StatusCode::BadRequest, "Invalid JSON"),
+    };
+    let Some(text) = command.text.filter(|text| !text.is_empty()) else {
+        return Reply::Error(StatusCode::BadRequest, "Missing text");
+    };
+
+    let notification = Notification {
+        text,
+        icon: command.icon,
+        color: command.color.unwrap_or(awtrix::Color([255, 255, 255])).0,
+        duration: command.duration.unwrap_or(notifications::DEFAULT_NOTIFICATION_DURATION_SECS),
+        hold: command.hold.unwrap_or(false),
+        priority: 0,
+    };
+    match notifications::push(notification) {
+        Ok(()) => Reply::Ok,
+        Err(_) => Reply::Error(StatusCode::ServiceUnavailable, "Notification queue full"),
+    }
+}
+
+fn custom(request: &Request<'_>) -> Reply {
+    let Some(name) = request.query_param("name").filter(|name| !name.is_empty()) else {
+        return Reply::Error(StatusCode::BadRequest, "Missing name");
+    };
+    let command = if request.body.iter().all(u8::is_ascii_whitespace) {
+        awtrix::CustomAppRequest::default()
+    } else {
+        match awtrix::from_json::<awtrix::CustomAppRequest>(request.body) {
+            Ok(command) => command,
+            Err(_) => return Reply::Error(StatusCode::BadRequest, "Invalid JSON"),
+        }
+    };
+
+    match command.text.filter(|text| !text.is_empty()) {
+        Some(text) => {
+            let app = CustomApp {
+                name: name.into(),
+                text,
+                icon: command.icon,
+                color: command.color.unwrap_or(awtrix::Color([255, 255, 255])).0,
+                duration: command.duration.unwrap_or(custom_apps::DEFAULT_CUSTOM_APP_DURATION_SECS),
+            };
+            match custom_apps::upsert(app) {
+                Ok(()) => Reply::Ok,
+                Err(CustomAppError::BuiltinName) => Reply::Error(StatusCode::BadRequest, "Name of a built-in page"),
+                Err(_) => Reply::Error(StatusCode::ServiceUnavailable, "Too many custom apps"),
+            }
+        }
+        None => {
+            custom_apps::remove(name);
+            Reply::Ok
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+struct CountdownEntry {
+    name: String,
+    date: String,
+    mode: &'static str,
+}
+
+fn list_countdowns() -> Vec<CountdownEntry> {
+    countdowns::get_countdowns()
+        .into_iter()
+        .map(|entry| {
+            let mut date = String::new();
+            countdown::write_datetime(&mut date, entry.target);
+            CountdownEntry { name: entry.name, date, mode: entry.mode.name() }
+        })
+        .collect()
+}
+
+/// Takes the same `{"name": "..", "date": "..", "mode": ".."}` command as the Home Assistant text.
+fn update_countdown(request: &Request<'_>) -> Reply {
+    let Ok(payload) = core::str::from_utf8(request.body) else {
+        return Reply::Error(StatusCode::BadRequest, "Invalid JSON");
+    };
+    match countdowns::handle_command(payload) {
+        Ok(()) => Reply::Ok,
+        Err(CountdownError::InvalidJson(_)) => Reply::Error(StatusCode::BadRequest, "Invalid JSON"),
+        Err(CountdownError::EmptyName) => Reply::Error(StatusCode::BadRequest, "Missing name"),
+        Err(CountdownError::InvalidDate) => Reply::Error(StatusCode::BadRequest, "Invalid date"),
+        Err(CountdownError::UnknownMode) => Reply::Error(StatusCode::BadRequest, "Unknown mode"),
+        Err(CountdownError::TooManyCountdowns) => Reply::Error(StatusCode::ServiceUnavailable, "Too many countdowns"),
+    }
+}
+
+/// AWTRIX effect numbers: 0 random, 1 slide, 2 dim, 5 pixelate, 6 curtain and 10 fade, the ones without a
+/// counterpart fall back to sliding.
+fn effect_from_awtrix(index: u8) -> TransitionEffect {
+    match index {
+        0 => TransitionEffect::Random,
+        2 | 10 => TransitionEffect::Fade,
+        5 => TransitionEffect::Dissolve,
+        6 => TransitionEffect::Wipe,
+        _ => TransitionEffect::SlideLeft,
+    }
+}
+
+fn effect_to_awtrix(effect: TransitionEffect) -> u8 {
+    match effect {
+        TransitionEffect::Random => 0,
+        TransitionEffect::SlideLeft
+        | TransitionEffect::SlideRight
+        | TransitionEffect::SlideUp
+        | TransitionEffect::SlideDown => 1,
+        TransitionEffect::Dissolve => 5,
+        TransitionEffect::Wipe => 6,
+        TransitionEffect::Fade => 10,
+    }
+}
+
+fn settings() -> awtrix::Settings {
+    awtrix::Settings {
+        auto_transition: state::get_transition_state(),
+        transition_speed: state::get_transition_duration().as_millis() as u32,
+        transition_effect: effect_to_awtrix(state::get_transition_effect()),
+        time: page_registry::is_page_enabled("time"),
+        date: page_registry::is_page_enabled("date"),
+        battery: page_registry::is_page_enabled("battery"),
+    }
+}
+
+fn update_settings(request: &Request<'_>) -> Reply {
+    let command = match awtrix::from_json::<awtrix::SettingsRequest>(request.body) {
+        Ok(command) => command,
+        Err(_) => return Reply::Error(StatusCode::BadRequest, "Invalid JSON"),
+    };
+
+    if let Some(auto_transition) = command.auto_transition {
+        state::internal_set_transition_state(auto_transition);
+    }
+    if let Some(speed) = command.transition_speed {
+        state::set_transition_duration(speed);
+    }
+    if let Some(effect) = command.transition_effect {
+        state::set_transition_effect(effect_from_awtrix(effect));
+    }
+    for (page, enabled) in [("time", command.time), ("date", command.date), ("battery", command.battery)] {
+        if let Some(enabled) = enabled {
+            page_registry::set_page_enabled(page, enabled).ok();
+        }
+    }
+    Reply::Ok
+}
+
+fn stats() -> Reply {
+    let indicators = state::get_indicators_state();
+    let app = page_registry::get_current_page();
+    let heap_stats = esp_alloc::HEAP.stats();
+    to_json(&awtrix::Stats {
+        bat: crate::adc::get_battery_level_percentage() as u8,
+        ram: (heap_stats.size - heap_stats.current_usage) as u32,
+        uptime: embassy_time::Instant::now().as_secs(),
+        version: env!("CARGO_PKG_VERSION"),
+        indicator1: indicators[0].on,
+        indicator2: indicators[1].on,
+        indicator3: indicators[2].on,
+        app: &app,
+    })
+}
+
+/// AWTRIX `blink` is the on (and off) time and `fade` the whole cycle, both in milliseconds.
+fn indicator(request: &Request<'_>, index: usize) -> Reply {
+    let command = if request.body.iter().all(u8::is_ascii_whitespace) {
+        awtrix::IndicatorRequest::default()
+    } else {
+        match awtrix::from_json::<awtrix::IndicatorRequest>(request.body) {
+            Ok(command) => command,
+            Err(_) => return Reply::Error(StatusCode::BadRequest, "Invalid JSON"),
+        }
+    };
+
+    let current = state::get_indicator(index - 1);
+    let indicator = match command.color.filter(|color| !color.is_black()) {
+        Some(color) => {
+            let (mode, period_ms) = match (command.blink, command.fade) {
+                (Some(blink), _) if blink > 0 => (IndicatorMode::Blink, blink.saturating_mul(2)),
+                (_, Some(fade)) if fade > 0 => (IndicatorMode::Fade, fade),
+                _ => (IndicatorMode::Solid, state::DEFAULT_INDICATOR_PERIOD_MS as u32),
+            };
+            Indicator {
+                on: true,
+                color: color.0,
+                brightness: 255,
+                mode,
+                period_ms: period_ms.min(u16::MAX as u32) as u16,
+            }
+        }
+        None => Indicator { on: false, ..current },
+    };
+    state::set_indicator(index - 1, indicator);
+    Reply::Ok
+}
+
+async fn screen() -> Reply {
+    let Some(pixels) = screen::capture().await else {
+        return Reply::Error(StatusCode::ServiceUnavailable, "Screen capture timed out");
+    };
+    let mut body = String::new();
+    awtrix::write_screen_json(pixels.iter().map(|pixel| awtrix::Color([pixel.r(), pixel.g(), pixel.b()])), &mut body);
+    Reply::Json(body.into_bytes())
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Reply {
+    let mut buffer = vec![0u8; 512];
+    match serde_json_core::to_slice(value, &mut buffer) {
+        Ok(len) => {
+            buffer.truncate(len);
+            Reply::Json(buffer)
+        }
+        Err(_) => Reply::Error(StatusCode::InternalServerError, "Response too large"),
+    }
+}
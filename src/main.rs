@@ -39,14 +39,29 @@ use static_cell::StaticCell;
 extern crate alloc;
 
 mod adc;
+mod alarm;
 mod buttons;
 mod buzzer;
+mod charts;
+mod clock;
+mod countdowns;
+mod custom_apps;
+mod date;
 mod ds1307;
+mod entity_page;
+mod games;
 mod ha;
+mod http;
 mod matrix;
 mod mk_static;
+mod night;
+mod notifications;
 mod ntp;
+mod page_registry;
+mod pomodoro;
+mod realtime;
 mod state;
+mod stopwatch;
 mod storage;
 mod udp;
 mod wifi;
@@ -82,7 +97,11 @@ async fn main(spawner: Spawner) {
     let mut ledc = esp_hal::ledc::Ledc::new(peripherals.LEDC);
     ledc.set_global_slow_clock(esp_hal::ledc::LSGlobalClkSource::APBClk);
 
-    let mut hs_timer = ledc.timer::<esp_hal::ledc::HighSpeed>(esp_hal::ledc::timer::Number::Timer1);
+    // the buzzer channel keeps a reference to its timer for as long as it lives
+    let hs_timer = mk_static::mk_static!(
+        esp_hal::ledc::timer::Timer<'static, esp_hal::ledc::HighSpeed>,
+        ledc.timer::<esp_hal::ledc::HighSpeed>(esp_hal::ledc::timer::Number::Timer1)
+    );
     hs_timer
         .configure(esp_hal::ledc::timer::config::Config {
             duty: esp_hal::ledc::timer::config::Duty::Duty8Bit,
@@ -95,7 +114,7 @@ async fn main(spawner: Spawner) {
     esp_hal::ledc::channel::ChannelIFace::configure(
         &mut channel,
         esp_hal::ledc::channel::config::Config {
-            timer: &hs_timer,
+            timer: hs_timer,
             duty_pct: 0,
             drive_mode: esp_hal::gpio::DriveMode::PushPull,
         },
@@ -172,13 +191,23 @@ async fn main(spawner: Spawner) {
     let (stack, runner) = embassy_net::new(
         wifi_interface,
         config,
-        mk_static::mk_static!(StackResources<4>, StackResources::<4>::new()),
+        mk_static::mk_static!(StackResources<8>, StackResources::<8>::new()),
         seed,
     );
 
     let storage = storage::init(peripherals.FLASH).await;
 
     spawner.must_spawn(state::state_task(storage.clone()));
+    spawner.must_spawn(page_registry::page_registry_task(storage.clone()));
+    spawner.must_spawn(pomodoro::pomodoro_task(storage.clone()));
+    spawner.must_spawn(alarm::alarm_task(storage.clone(), rtc2));
+    spawner.must_spawn(countdowns::countdowns_task(storage.clone()));
+    spawner.must_spawn(clock::clock_task(storage.clone()));
+    spawner.must_spawn(date::date_task(storage.clone()));
+    spawner.must_spawn(night::night_task(storage.clone()));
+    spawner.must_spawn(entity_page::entity_page_task(storage.clone()));
+    spawner.must_spawn(games::games_task(storage.clone()));
+    spawner.must_spawn(custom_apps::custom_apps_task(storage));
     spawner.must_spawn(wifi::wifi_task(wifi_controller, *&storage));
     spawner.must_spawn(wifi::net_task(runner));
 
@@ -188,6 +217,8 @@ async fn main(spawner: Spawner) {
 
     spawner.must_spawn(ntp::ntp_task(stack));
     spawner.must_spawn(ha::ha_task(spawner, stack, mac_address));
+    spawner.must_spawn(http::http_task(stack));
+    spawner.must_spawn(realtime::realtime_task(stack));
     spawner.must_spawn(buttons::button_task(left, right, middle));
 
     let mut adc_config = esp_hal::analog::adc::AdcConfig::default();
@@ -197,7 +228,9 @@ async fn main(spawner: Spawner) {
 
     let adc = esp_hal::analog::adc::Adc::new(peripherals.ADC1, adc_config);
 
+    spawner.must_spawn(buzzer::buzzer_task(channel));
     spawner.must_spawn(adc::adc_task(adc, battery_pin, light_sensor_pin));
+    spawner.must_spawn(charts::charts_task());
 
     loop {
         Timer::after(Duration::from_millis(1000)).await;
@@ -0,0 +1,90 @@
+use alloc::string::String;
+use core::fmt::Write as _;
+
+use embassy_time::Instant;
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+use rwtrix_core::frame::Redraw;
+
+use crate::{
+    alarm::{self, Alarm},
+    matrix::{
+        event::MatrixEventDetails,
+        fonts::AwtrixFont,
+        icons,
+        pages::PageTarget,
+        scroller::{ScrollMode, Scroller},
+    },
+};
+
+const FLASH_PERIOD_MS: u64 = 500;
+
+/// Full screen alert shown while an alarm rings, on top of everything else.
+pub struct AlarmOverlay {
+    current: Option<Alarm>,
+    scroller: Scroller,
+}
+
+impl AlarmOverlay {
+    pub fn new() -> Self {
+        AlarmOverlay {
+            current: None,
+            scroller: Scroller::new(crate::matrix::WIDTH - icons::ICON_SIZE - 1, ScrollMode::Loop),
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.current.is_some()
+    }
+
+    /// Flashes and scrolls while ringing.
+    pub fn redraw(&self) -> Redraw {
+        if self.is_active() {
+            Redraw::ANIMATION
+        } else {
+            Redraw::IDLE
+        }
+    }
+
+    pub fn update(&mut self) {
+        let ringing = alarm::get_ringing();
+        if ringing != self.current {
+            if let Some(alarm) = &ringing {
+                let mut text = String::new();
+                write!(text, "{:02}:{:02}", alarm.hour, alarm.minute).ok();
+                if !alarm.label.is_empty() {
+                    write!(text, " {}", alarm.label).ok();
+                }
+                self.scroller.set_text(&text);
+                self.scroller.restart();
+            }
+            self.current = ringing;
+        }
+        self.scroller.update();
+    }
+
+    /// Select dismisses the ringing alarm and the other buttons snooze it, every event is consumed while it rings.
+    pub fn handle_event(&mut self, event: &MatrixEventDetails) -> bool {
+        if self.current.is_none() {
+            return false;
+        }
+        if event.has_select() {
+            alarm::dismiss();
+        } else {
+            alarm::snooze();
+        }
+        self.current = None;
+        true
+    }
+
+    pub fn render<T: PageTarget>(&self, target: &mut T) {
+        if self.current.is_none() {
+            return;
+        }
+
+        let flash = (Instant::now().as_millis() / FLASH_PERIOD_MS) % 2 == 0;
+        let (background, foreground) = if flash { (Rgb888::RED, Rgb888::WHITE) } else { (Rgb888::BLACK, Rgb888::RED) };
+        target.clear(background).ok();
+        icons::draw_icon(target, "bell", Point::zero(), foreground);
+        self.scroller.render(target, Point::new(icons::ICON_SIZE as i32 + 1, 1), AwtrixFont::new(foreground));
+    }
+}
@@ -0,0 +1,46 @@
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*, primitives::Rectangle};
+use rwtrix_core::chart::{ChartStyle, Scale, Series};
+
+use crate::matrix::pages::PageTarget;
+
+/// Draws the samples of `series` into `area`, one column each with the newest on the right, scaled to the range of
+/// the samples. Every pixel takes the color of its height, blending from the low color at the bottom to the high
+/// color at the top.
+pub fn draw_chart<T: PageTarget>(target: &mut T, series: &Series, area: Rectangle) {
+    let Some(scale) = Scale::fit(&series.history) else {
+        return;
+    };
+    let rows = area.size.height;
+    let columns = series.history.len().min(area.size.width as usize);
+    let skip = series.history.len() - columns;
+    let left = area.top_left.x + (area.size.width as usize - columns) as i32;
+    let color_at = |row: u32| {
+        let [r, g, b] = series.color(row as f32 / rows.saturating_sub(1).max(1) as f32);
+        Rgb888::new(r, g, b)
+    };
+
+    let mut previous_row = None;
+    for (column, value) in series.history.iter().skip(skip).enumerate() {
+        let x = left + column as i32;
+        // rows counted from the bottom of the area
+        let (from, to) = match series.style {
+            ChartStyle::Bars => (0, scale.bar_height(value, rows) - 1),
+            ChartStyle::Line => {
+                let row = scale.bar_height(value, rows) - 1;
+                // steep changes are joined by a vertical stroke so the line stays connected
+                let from = previous_row.map_or(row, |previous: u32| {
+                    if previous < row {
+                        previous + 1
+                    } else {
+                        previous.saturating_sub(1).max(row)
+                    }
+                });
+                previous_row = Some(row);
+                (from.min(row), from.max(row))
+            }
+        };
+        let pixels =
+            (from..=to).map(|row| Pixel(Point::new(x, area.top_left.y + (rows - 1 - row) as i32), color_at(row)));
+        target.draw_iter(pixels).ok();
+    }
+}
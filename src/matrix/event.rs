@@ -13,11 +13,20 @@ pub enum MatrixEvent {
     Select,
 }
 
+/// Every button sends a press when it goes down and one release for all buttons held together. Only pages that
+/// capture the buttons get presses, everything else acts on the release.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatrixEventKind {
+    Press,
+    Release,
+}
+
 #[derive(Debug, Clone)]
 pub struct MatrixEventDetails {
     pub duration: Duration,
     pub main: MatrixEvent,
     pub events: heapless::index_set::FnvIndexSet<MatrixEvent, 4>,
+    pub kind: MatrixEventKind,
 }
 
 impl MatrixEventDetails {
@@ -26,9 +35,23 @@ impl MatrixEventDetails {
             duration: Duration::from_millis(0),
             events: heapless::index_set::FnvIndexSet::new(),
             main: MatrixEvent::Left,
+            kind: MatrixEventKind::Release,
         }
     }
 
+    /// `button` just went down.
+    pub fn press(button: MatrixEvent) -> Self {
+        let mut event = Self::new();
+        event.set_main(button);
+        event.push_event(button);
+        event.kind = MatrixEventKind::Press;
+        event
+    }
+
+    pub fn is_press(&self) -> bool {
+        self.kind == MatrixEventKind::Press
+    }
+
     pub fn push_event(&mut self, event: MatrixEvent) {
         self.events.insert(event).ok();
     }
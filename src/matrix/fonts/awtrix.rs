@@ -1861,6 +1861,280 @@ static AWTRIX_GLYPHS: phf::Map<char, AwtrixGlyph> = phf_map! {
         y_offset: -5,
         bitmap: &[0xA0, 0x00, 0xA0, 0x60, 0x20, 0x40],
     },
+    // Latin Extended-A letters of Polish and Czech, accents squeezed in like the Latin-1 ones above. The capital S and Z
+    // only get one row of accent, like Ä, to keep four rows for the letter
+    '\u{0104}' => AwtrixGlyph {
+        width: 8,
+        height: 6,
+        advance: 4,
+        x_offset: 0,
+        y_offset: -5,
+        bitmap: &[0xC0, 0xA0, 0xE0, 0xA0, 0xA0, 0x20],
+    },
+    '\u{0105}' => AwtrixGlyph {
+        width: 8,
+        height: 4,
+        advance: 4,
+        x_offset: 0,
+        y_offset: -3,
+        bitmap: &[0x60, 0xA0, 0xE0, 0x20],
+    },
+    '\u{0106}' => AwtrixGlyph {
+        width: 8,
+        height: 5,
+        advance: 4,
+        x_offset: 0,
+        y_offset: -5,
+        bitmap: &[0x20, 0x40, 0xE0, 0x80, 0xE0],
+    },
+    '\u{0107}' => AwtrixGlyph {
+        width: 8,
+        height: 5,
+        advance: 4,
+        x_offset: 0,
+        y_offset: -5,
+        bitmap: &[0x20, 0x40, 0x60, 0x80, 0x60],
+    },
+    '\u{010C}' => AwtrixGlyph {
+        width: 8,
+        height: 5,
+        advance: 4,
+        x_offset: 0,
+        y_offset: -5,
+        bitmap: &[0xA0, 0x40, 0xE0, 0x80, 0xE0],
+    },
+    '\u{010D}' => AwtrixGlyph {
+        width: 8,
+        height: 5,
+        advance: 4,
+        x_offset: 0,
+        y_offset: -5,
+        bitmap: &[0xA0, 0x40, 0x60, 0x80, 0x60],
+    },
+    '\u{010E}' => AwtrixGlyph {
+        width: 8,
+        height: 5,
+        advance: 4,
+        x_offset: 0,
+        y_offset: -5,
+        bitmap: &[0xA0, 0x40, 0xC0, 0xA0, 0xC0],
+    },
+    '\u{010F}' => AwtrixGlyph {
+        width: 8,
+        height: 5,
+        advance: 5,
+        x_offset: 0,
+        y_offset: -5,
+        bitmap: &[0x28, 0x60, 0xA0, 0xA0, 0x60],
+    },
+    '\u{0118}' => AwtrixGlyph {
+        width: 8,
+        height: 6,
+        advance: 4,
+        x_offset: 0,
+        y_offset: -5,
+        bitmap: &[0xE0, 0x80, 0xE0, 0x80, 0xE0, 0x20],
+    },
+    '\u{0119}' => AwtrixGlyph {
+        width: 8,
+        height: 4,
+        advance: 4,
+        x_offset: 0,
+        y_offset: -3,
+        bitmap: &[0x60, 0xE0, 0x60, 0x20],
+    },
+    '\u{011A}' => AwtrixGlyph {
+        width: 8,
+        height: 5,
+        advance: 4,
+        x_offset: 0,
+        y_offset: -5,
+        bitmap: &[0xA0, 0x40, 0xE0, 0xC0, 0xE0],
+    },
+    '\u{011B}' => AwtrixGlyph {
+        width: 8,
+        height: 5,
+        advance: 4,
+        x_offset: 0,
+        y_offset: -5,
+        bitmap: &[0xA0, 0x40, 0x60, 0xE0, 0x60],
+    },
+    '\u{0141}' => AwtrixGlyph {
+        width: 8,
+        height: 5,
+        advance: 4,
+        x_offset: 0,
+        y_offset: -5,
+        bitmap: &[0x80, 0x80, 0xC0, 0x80, 0xE0],
+    },
+    '\u{0142}' => AwtrixGlyph {
+        width: 8,
+        height: 5,
+        advance: 4,
+        x_offset: 0,
+        y_offset: -5,
+        bitmap: &[0xC0, 0x40, 0x60, 0xC0, 0xE0],
+    },
+    '\u{0143}' => AwtrixGlyph {
+        width: 8,
+        height: 5,
+        advance: 4,
+        x_offset: 0,
+        y_offset: -5,
+        bitmap: &[0x20, 0x40, 0xA0, 0xE0, 0xA0],
+    },
+    '\u{0144}' => AwtrixGlyph {
+        width: 8,
+        height: 5,
+        advance: 4,
+        x_offset: 0,
+        y_offset: -5,
+        bitmap: &[0x20, 0x40, 0xC0, 0xA0, 0xA0],
+    },
+    '\u{0147}' => AwtrixGlyph {
+        width: 8,
+        height: 5,
+        advance: 4,
+        x_offset: 0,
+        y_offset: -5,
+        bitmap: &[0xA0, 0x40, 0xA0, 0xE0, 0xA0],
+    },
+    '\u{0148}' => AwtrixGlyph {
+        width: 8,
+        height: 5,
+        advance: 4,
+        x_offset: 0,
+        y_offset: -5,
+        bitmap: &[0xA0, 0x40, 0xC0, 0xA0, 0xA0],
+    },
+    '\u{0158}' => AwtrixGlyph {
+        width: 8,
+        height: 5,
+        advance: 4,
+        x_offset: 0,
+        y_offset: -5,
+        bitmap: &[0xA0, 0x40, 0xC0, 0xC0, 0xA0],
+    },
+    '\u{0159}' => AwtrixGlyph {
+        width: 8,
+        height: 5,
+        advance: 4,
+        x_offset: 0,
+        y_offset: -5,
+        bitmap: &[0xA0, 0x40, 0x60, 0x80, 0x80],
+    },
+    '\u{015A}' => AwtrixGlyph {
+        width: 8,
+        height: 5,
+        advance: 4,
+        x_offset: 0,
+        y_offset: -5,
+        bitmap: &[0x60, 0xE0, 0x80, 0x20, 0xE0],
+    },
+    '\u{015B}' => AwtrixGlyph {
+        width: 8,
+        height: 5,
+        advance: 4,
+        x_offset: 0,
+        y_offset: -5,
+        bitmap: &[0x20, 0x40, 0x60, 0x40, 0xC0],
+    },
+    '\u{0160}' => AwtrixGlyph {
+        width: 8,
+        height: 5,
+        advance: 4,
+        x_offset: 0,
+        y_offset: -5,
+        bitmap: &[0xA0, 0xE0, 0x80, 0x20, 0xE0],
+    },
+    '\u{0161}' => AwtrixGlyph {
+        width: 8,
+        height: 5,
+        advance: 4,
+        x_offset: 0,
+        y_offset: -5,
+        bitmap: &[0xA0, 0x40, 0x60, 0x40, 0xC0],
+    },
+    '\u{0164}' => AwtrixGlyph {
+        width: 8,
+        height: 5,
+        advance: 4,
+        x_offset: 0,
+        y_offset: -5,
+        bitmap: &[0xA0, 0x40, 0xE0, 0x40, 0x40],
+    },
+    '\u{0165}' => AwtrixGlyph {
+        width: 8,
+        height: 5,
+        advance: 5,
+        x_offset: 0,
+        y_offset: -5,
+        bitmap: &[0x50, 0xE0, 0x40, 0x40, 0x60],
+    },
+    '\u{016E}' => AwtrixGlyph {
+        width: 8,
+        height: 5,
+        advance: 4,
+        x_offset: 0,
+        y_offset: -5,
+        bitmap: &[0xC0, 0xC0, 0xA0, 0xA0, 0xE0],
+    },
+    '\u{016F}' => AwtrixGlyph {
+        width: 8,
+        height: 5,
+        advance: 4,
+        x_offset: 0,
+        y_offset: -5,
+        bitmap: &[0x60, 0x60, 0xA0, 0xA0, 0x60],
+    },
+    '\u{0179}' => AwtrixGlyph {
+        width: 8,
+        height: 5,
+        advance: 4,
+        x_offset: 0,
+        y_offset: -5,
+        bitmap: &[0x60, 0xE0, 0x20, 0x80, 0xE0],
+    },
+    '\u{017A}' => AwtrixGlyph {
+        width: 8,
+        height: 5,
+        advance: 4,
+        x_offset: 0,
+        y_offset: -5,
+        bitmap: &[0x20, 0x40, 0xC0, 0x40, 0x60],
+    },
+    '\u{017B}' => AwtrixGlyph {
+        width: 8,
+        height: 5,
+        advance: 4,
+        x_offset: 0,
+        y_offset: -5,
+        bitmap: &[0x40, 0xE0, 0x20, 0x80, 0xE0],
+    },
+    '\u{017C}' => AwtrixGlyph {
+        width: 8,
+        height: 5,
+        advance: 4,
+        x_offset: 0,
+        y_offset: -5,
+        bitmap: &[0x40, 0x00, 0xC0, 0x40, 0x60],
+    },
+    '\u{017D}' => AwtrixGlyph {
+        width: 8,
+        height: 5,
+        advance: 4,
+        x_offset: 0,
+        y_offset: -5,
+        bitmap: &[0xA0, 0xE0, 0x20, 0x80, 0xE0],
+    },
+    '\u{017E}' => AwtrixGlyph {
+        width: 8,
+        height: 5,
+        advance: 4,
+        x_offset: 0,
+        y_offset: -5,
+        bitmap: &[0xA0, 0x40, 0xC0, 0x40, 0x60],
+    },
 };
 
 pub struct AwtrixFont {
@@ -1871,6 +2145,15 @@ impl AwtrixFont {
     pub fn new(text_color: Rgb888) -> Self {
         AwtrixFont { text_color }
     }
+
+    /// Horizontal advance of `text` in pixels.
+    pub fn measure_width(text: &str) -> u32 {
+        AwtrixFont::new(Rgb888::BLACK)
+            .measure_string(text, embedded_graphics::prelude::Point::zero(), embedded_graphics::text::Baseline::Top)
+            .bounding_box
+            .size
+            .width
+    }
 }
 
 impl TextRenderer for AwtrixFont {
@@ -1938,6 +2221,21 @@ impl TextRenderer for AwtrixFont {
     }
 }
 
+/// The Cyrillic glyphs sit at the code points AWTRIX uses after its own UTF-8 conversion, from `0x7F` for `А` on.
+/// Only upper case glyphs exist, lower case letters are drawn with them.
+fn map_cyrillic(c: char) -> char {
+    let slot = match c {
+        'А'..='Я' => c as u32 - 'А' as u32,
+        'а'..='я' => c as u32 - 'а' as u32,
+        'Ё' | 'ё' => 'Е' as u32 - 'А' as u32,
+        'Ґ' | 'ґ' => 0x20,
+        'Є' | 'є' => 0x21,
+        'І' | 'і' | 'Ї' | 'ї' => 0x22,
+        _ => return c,
+    };
+    char::from_u32(0x7F + slot).unwrap_or(c)
+}
+
 pub struct AwtrixFontInner {
     pub cursor_x: i32,
     pub cursor_y: i32,
@@ -1969,6 +2267,7 @@ impl AwtrixFontInner {
             _ => {}
         }
 
+        let c = map_cyrillic(c);
         let glyph = AWTRIX_GLYPHS.get(&c).or_else(|| AWTRIX_GLYPHS.get(&' ')).unwrap();
 
         let w = glyph.width as usize;
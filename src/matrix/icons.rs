@@ -0,0 +1,40 @@
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+use phf::phf_map;
+
+use crate::matrix::pages::PageTarget;
+
+pub const ICON_SIZE: u32 = 8;
+
+/// 8x8 monochrome icons, one byte per row with the most significant bit on the left.
+static ICONS: phf::Map<&'static str, [u8; 8]> = phf_map! {
+    "heart" => [0x66, 0xFF, 0xFF, 0xFF, 0x7E, 0x3C, 0x18, 0x00],
+    "bell" => [0x18, 0x3C, 0x7E, 0x7E, 0x7E, 0xFF, 0x00, 0x18],
+    "sun" => [0x99, 0x42, 0x3C, 0xBD, 0xBD, 0x3C, 0x42, 0x99],
+    "moon" => [0x3C, 0x70, 0xE0, 0xE0, 0xE0, 0xE0, 0x70, 0x3C],
+    "drop" => [0x18, 0x18, 0x3C, 0x7E, 0x7E, 0x7E, 0x3C, 0x00],
+    "temp" => [0x18, 0x24, 0x24, 0x24, 0x3C, 0x7E, 0x7E, 0x3C],
+    "mail" => [0x00, 0xFF, 0xC3, 0xA5, 0x99, 0x81, 0xFF, 0x00],
+    "home" => [0x18, 0x3C, 0x7E, 0xFF, 0x7E, 0x66, 0x66, 0x66],
+    "alert" => [0x18, 0x18, 0x18, 0x18, 0x18, 0x00, 0x18, 0x00],
+    "check" => [0x00, 0x01, 0x03, 0x86, 0xCC, 0x78, 0x30, 0x00],
+    "cross" => [0x81, 0x42, 0x24, 0x18, 0x18, 0x24, 0x42, 0x81],
+    "music" => [0x3F, 0x21, 0x21, 0x21, 0x63, 0xE7, 0xE7, 0x00],
+    "bolt" => [0x0C, 0x18, 0x30, 0x7E, 0x0C, 0x18, 0x30, 0x60],
+};
+
+pub fn has_icon(name: &str) -> bool {
+    ICONS.contains_key(name)
+}
+
+pub fn draw_icon<T: PageTarget>(target: &mut T, name: &str, top_left: Point, color: Rgb888) {
+    let Some(bitmap) = ICONS.get(name) else {
+        return;
+    };
+
+    let pixels = bitmap.iter().enumerate().flat_map(|(y, row)| {
+        (0..ICON_SIZE as i32)
+            .filter(move |x| row & (0x80 >> x) != 0)
+            .map(move |x| Pixel(top_left + Point::new(x, y as i32), color))
+    });
+    target.draw_iter(pixels).ok();
+}
@@ -1,22 +1,50 @@
-use alloc::{boxed::Box, vec::Vec};
+use alloc::{boxed::Box, string::String, vec::Vec};
 use core::fmt::Write as _;
 
-use embassy_time::Duration;
-use embedded_graphics::{prelude::*, primitives::Rectangle};
+use embassy_time::Instant;
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
 use esp_hal::{
-    delay::Delay,
     rmt::{PulseCode, Rmt},
     time::Rate,
 };
 use esp_hal_smartled::SmartLedsAdapter;
+use rwtrix_core::{
+    frame::{FrameScheduler, Redraw},
+    layers::{LayerId, LayerStack},
+};
 
-use crate::{adc::get_brightness_percent, state};
+use crate::{adc::get_brightness_percent, custom_apps, night::NightAction, page_registry, realtime, state};
 
+mod alarm;
+mod chart;
 mod color;
 pub mod event;
 mod fonts;
+mod icons;
+mod night;
+mod notification;
+mod page_menu;
 mod pages;
+pub mod screen;
+mod scroller;
 mod status;
+pub mod transition;
+
+pub const WIDTH: u32 = 32;
+pub const HEIGHT: u32 = 8;
+
+/// Longest sleep between two passes of the loop, bounds the latency of button presses and of feeding the watchdog.
+const POLL_INTERVAL_MS: u64 = 10;
+/// Frame time of the realtime stream, which has no redraw hints.
+const REALTIME_POLL_MS: u64 = 5;
+
+/// Gives core 1 to the idle task instead of spinning.
+fn sleep_ms(ms: u64) {
+    if ms == 0 {
+        return;
+    }
+    esp_rtos::CurrentThreadHandle::get().delay(esp_hal::time::Duration::from_millis(ms));
+}
 
 pub fn matrix_task(
     rmt: esp_hal::peripherals::RMT<'static>,
@@ -33,7 +61,7 @@ pub fn matrix_task(
     .expect("Failed to initialize RMT");
     info!("Rmt initialized.");
 
-    const NUM_LEDS: usize = 32 * 8;
+    const NUM_LEDS: usize = (WIDTH * HEIGHT) as usize;
     const BUFFER_SIZE: usize = esp_hal_smartled::buffer_size_async(NUM_LEDS);
     let rmt_channel = rmt.channel0;
     //let mut rmt_buffer = [0_u32; esp_hal_smartled::buffer_size_async(NUM_LEDS)];
@@ -48,7 +76,7 @@ pub fn matrix_task(
 
     let mut matrix = smart_leds_matrix::SmartLedMatrix::<_, _, { NUM_LEDS }>::new(
         led,
-        smart_leds_matrix::layout::Rectangular::new_tc001(32, 8),
+        smart_leds_matrix::layout::Rectangular::new_tc001(WIDTH, HEIGHT),
     );
 
     let handle = esp_rtos::CurrentThreadHandle::get();
@@ -61,53 +89,139 @@ pub fn matrix_task(
     //let mut current_page = pages::Time::new(rtc);
     let mut current_page_instant = embassy_time::Instant::now();
 
-    let mut pages = Vec::with_capacity(4);
-    pages.push(pages::Time::new(rtc));
-    pages.push(pages::Date::new(rtc));
-    pages.push(pages::Timer::new(rtc));
-    pages.push(pages::Battery::new());
-
-    let mut current_page_index = 0;
+    let mut pages = Vec::with_capacity(page_registry::BUILTIN_PAGES.len());
+    let mut current_page_index = sync_pages(&mut pages, 0, rtc);
+    let mut custom_apps_version = custom_apps::get_custom_apps_version();
+    let mut page_registry_version = page_registry::get_page_registry_version();
 
     let mut status = status::Status::new();
-    let delay_millis = 50;
+    let mut notification = notification::NotificationOverlay::new();
+    let mut alarm_overlay = alarm::AlarmOverlay::new();
+    let mut page_menu = page_menu::PageMenu::new();
+    let mut realtime_version = realtime::get_frame_version();
+    let mut scheduler = FrameScheduler::new();
+    let mut layers = Box::new(LayerStack::new());
+    let mut night = night::NightMode::new(rtc);
+    // the only page shown by the clock night action
+    let mut night_clock = pages::Time::new(rtc);
 
     let event_receiver = event::get_event_channel_receiver();
 
     loop {
+        // fed on every pass, however long frames are apart
+        wdt0.feed();
+
+        let versions = (custom_apps::get_custom_apps_version(), page_registry::get_page_registry_version());
+        if versions != (custom_apps_version, page_registry_version) {
+            (custom_apps_version, page_registry_version) = versions;
+            current_page_index = sync_pages(&mut pages, current_page_index, rtc);
+            page_registry::set_current_page(pages[current_page_index].name());
+            scheduler.request();
+        }
+
+        let night_changed = night.update();
+
+        // a ringing alarm takes the display back from a realtime sender
+        if realtime::is_active() && crate::alarm::get_ringing().is_none() {
+            let version = realtime::get_frame_version();
+            if version != realtime_version || night_changed {
+                realtime_version = version;
+                let frame = realtime::with_frame(|frame| *frame);
+                // streamed frames follow the night brightness too, the off action keeps the panel dark
+                matrix.set_brightness(night.brightness(brightness()));
+                matrix
+                    .draw_iter(frame.chunks_exact(3).enumerate().map(|(i, rgb)| {
+                        let point = Point::new((i as u32 % WIDTH) as i32, (i as u32 / WIDTH) as i32);
+                        Pixel(point, Rgb888::new(rgb[0], rgb[1], rgb[2]))
+                    }))
+                    .ok();
+                screen::capture_if_requested(|point| matrix.pixel(point));
+                matrix.flush_with_gamma().ok();
+            }
+            // the buttons have nothing to control while a sender owns the display
+            while event_receiver.try_receive().is_ok() {}
+            current_page_instant = embassy_time::Instant::now();
+            // the page is drawn again once the stream ends
+            scheduler.request();
+            sleep_ms(REALTIME_POLL_MS);
+            continue;
+        }
+
         let event = event_receiver.try_receive();
-        if event.is_err() {
+        if event.is_ok() || notification.is_pending() || screen::is_capture_requested() || night_changed {
+            scheduler.request();
+        }
+        if event.is_err() && scheduler.is_due(Instant::now().as_millis()) {
             let current_page = &mut pages[current_page_index];
-            let mut brightness = ((get_brightness_percent() / 100.0) * 255.0) as u8;
-            if brightness < 5 {
-                brightness = 5;
+            let night_action = night.action();
+            layers.reset();
+            // notifications wait for the morning unless the night action keeps the pages
+            let redraw = match night_action {
+                Some(NightAction::Off) => Redraw::IDLE,
+                Some(NightAction::Clock) => {
+                    night_clock.update();
+                    night_clock.render(layers.layer_mut(LayerId::Page));
+                    night_clock.redraw()
+                }
+                Some(NightAction::Dim) | None => {
+                    current_page.update();
+                    current_page.render(layers.layer_mut(LayerId::Page));
+                    status.update();
+                    status.render(layers.layer_mut(LayerId::Status));
+                    page_menu.update();
+                    page_menu.render(layers.layer_mut(LayerId::Overlay));
+                    notification.update();
+                    notification.render(layers.layer_mut(LayerId::Overlay));
+                    alarm_overlay.update();
+                    alarm_overlay.render(layers.layer_mut(LayerId::Overlay));
+                    current_page
+                        .redraw()
+                        .min(status.redraw())
+                        .min(page_menu.redraw())
+                        .min(notification.redraw())
+                        .min(alarm_overlay.redraw())
+                }
+            };
+            if night_action == Some(NightAction::Clock) {
+                matrix
+                    .draw_iter(layers.compose().map(|Pixel(point, color)| Pixel(point, crate::night::to_red(color))))
+                    .ok();
+            } else {
+                matrix.draw_iter(layers.compose()).ok();
             }
-            matrix.set_brightness(brightness);
-            wdt0.feed();
-            current_page.update();
-            current_page.render(&mut matrix);
-            status.update();
-            status.render(&mut matrix);
-            let now = embassy_time::Instant::now();
-            loop {
-                matrix.flush_with_gamma().ok();
-                wdt0.feed();
-                Delay::new().delay_millis(delay_millis);
-                let now2 = embassy_time::Instant::now();
-                if let Some(elapsed) = now2.checked_duration_since(now) {
-                    if elapsed >= Duration::from_millis(100) {
-                        break;
-                    }
-                } else {
-                    warn!("Time went backwards!");
-                    break;
+            screen::capture_if_requested(|point| matrix.pixel(point));
+            matrix.set_brightness(night.brightness(brightness()));
+            matrix.flush_with_gamma_if_changed().ok();
+            scheduler.rendered(Instant::now().as_millis(), redraw);
+
+            for (page_index, page) in &mut pages.iter_mut().enumerate() {
+                if page_index != current_page_index {
+                    page.idle_update();
                 }
             }
         }
         let mut page_left = false;
         let mut page_right = false;
         if let Ok(event) = event {
-            if event.is_single_press() && event.is_long_press() {
+            let captured = pages[current_page_index].captures_buttons();
+            if event.is_press() {
+                // only pages that took over the buttons care about presses, everything else acts on the release
+                if captured && !alarm_overlay.is_active() && !notification.is_active() {
+                    pages[current_page_index].handle_event(event);
+                }
+            } else if alarm_overlay.handle_event(&event) {
+                // consumed by the ringing alarm
+            } else if night.wake() {
+                // only woke the display up
+                scheduler.request();
+            } else if notification.handle_event(&event) {
+                // consumed by the notification overlay
+            } else if captured {
+                // long presses and the page menu combo are part of the game
+                pages[current_page_index].handle_event(event);
+            } else if page_menu.handle_event(&event) {
+                // consumed by the page menu
+            } else if event.is_single_press() && event.is_long_press() {
                 match event.get_main() {
                     event::MatrixEvent::Left => {
                         page_left = true;
@@ -115,6 +229,9 @@ pub fn matrix_task(
                     event::MatrixEvent::Right => {
                         page_right = true;
                     }
+                    event::MatrixEvent::Select if pages[current_page_index].handles_long_select() => {
+                        pages[current_page_index].handle_event(event);
+                    }
                     event::MatrixEvent::Select => {
                         state::internal_set_transition_state(!state::get_transition_state());
                     }
@@ -127,82 +244,70 @@ pub fn matrix_task(
         let transition_state = state::get_transition_state();
         let now = embassy_time::Instant::now();
         if let Some(elapsed) = now.checked_duration_since(current_page_instant) {
-            if (elapsed >= Duration::from_secs(10) && transition_state) || (page_left || page_right) {
-                let mut new_page_index = (current_page_index + 1) % pages.len();
-                if page_left {
-                    if current_page_index == 0 {
-                        new_page_index = pages.len() - 1;
-                    } else {
-                        new_page_index = current_page_index - 1;
-                    }
+            // the pages stay where they are while the night clock or the dark panel are shown
+            let frozen = matches!(night.action(), Some(NightAction::Clock | NightAction::Off));
+            let rotate = transition_state
+                && !notification.is_active()
+                && !page_menu.is_active()
+                && !alarm_overlay.is_active()
+                && !frozen;
+            let current_page = &pages[current_page_index];
+            let duration =
+                page_registry::get_page_duration(current_page.name()).unwrap_or_else(|| current_page.duration());
+            let page_done = elapsed >= duration && current_page.can_rotate();
+            let requested_page = if frozen {
+                None
+            } else {
+                page_registry::take_requested_page()
+                    .and_then(|name| pages.iter().position(|page| page.name() == name))
+                    // a page the night mode leaves out is switched away from right away
+                    .or_else(|| {
+                        (!night.shows_page(current_page.name()))
+                            .then(|| next_page_index(&pages, current_page_index, false, &night))
+                    })
+            };
+            let next_page = match requested_page {
+                Some(requested_page) => Some(requested_page),
+                None if (page_done && rotate) || page_left || page_right => {
+                    Some(next_page_index(&pages, current_page_index, page_left, &night))
                 }
-
+                None => None,
+            }
+            .filter(|index| *index != current_page_index);
+            if let Some(new_page_index) = next_page {
                 let [new_page, current_page] = pages.get_disjoint_mut([new_page_index, current_page_index]).unwrap();
 
                 //let new_page = &mut pages[new_page_index];
                 //let current_page = &mut pages[current_page_index];
 
+                new_page.enter();
                 new_page.update();
 
-                let size = matrix.size();
+                let mut effect = state::get_page_transition_effect(new_page.name());
                 if page_left {
-                    for i in 0..size.width {
-                        let new_page_offset = Point::new(i as i32 - matrix.size().width as i32, 0);
-                        let current_page_offset = Point::new(i as i32, 0);
-                        {
-                            let mut new_page_target = matrix.translated(new_page_offset);
-                            let mut new_page_target = new_page_target.clipped(&Rectangle {
-                                top_left: Point::zero(),
-                                size: Size { width: size.width - new_page_offset.x as u32, height: size.height },
-                            });
-                            new_page.render(&mut new_page_target);
-                        }
-                        {
-                            let mut current_page_target = matrix.translated(current_page_offset);
-                            let mut current_page_target = current_page_target.clipped(&Rectangle {
-                                top_left: Point::zero(),
-                                size: Size { width: size.width - current_page_offset.x as u32, height: size.height },
-                            });
-                            current_page.render(&mut current_page_target);
-                        }
-                        status.render(&mut matrix);
-
-                        matrix.flush_with_gamma().ok();
-                        wdt0.feed();
-                        Delay::new().delay_millis(25);
-                    }
-                } else {
-                    for i in (0..size.width).rev() {
-                        let current_page_offset = Point::new(i as i32 - matrix.size().width as i32, 0);
-                        let new_page_offset = Point::new(i as i32, 0);
-                        {
-                            let mut current_page_target = matrix.translated(current_page_offset);
-                            let mut current_page_target = current_page_target.clipped(&Rectangle {
-                                top_left: Point::zero(),
-                                size: Size { width: size.width - current_page_offset.x as u32, height: size.height },
-                            });
-                            current_page.render(&mut current_page_target);
-                        }
-                        {
-                            let mut new_page_target = matrix.translated(new_page_offset);
-                            let mut new_page_target = new_page_target.clipped(&Rectangle {
-                                top_left: Point::zero(),
-                                size: Size { width: size.width - new_page_offset.x as u32, height: size.height },
-                            });
-                            new_page.render(&mut new_page_target);
-                        }
-
-                        status.render(&mut matrix);
-
-                        matrix.flush_with_gamma().ok();
-                        wdt0.feed();
-                        Delay::new().delay_millis(25);
-                    }
+                    effect = effect.reversed();
                 }
+                matrix.set_brightness(night.brightness(brightness()));
+                transition::run(
+                    &mut layers,
+                    effect,
+                    state::get_transition_duration(),
+                    current_page,
+                    new_page,
+                    &status,
+                    |layers| {
+                        matrix.draw_iter(layers.compose()).ok();
+                        matrix.flush_with_gamma_if_changed().ok();
+                        wdt0.feed();
+                        sleep_ms(25);
+                    },
+                );
 
                 current_page_index = new_page_index;
+                page_registry::set_current_page(pages[current_page_index].name());
                 current_page_instant = embassy_time::Instant::now();
-            } else if !transition_state {
+                scheduler.request();
+            } else if !rotate {
                 current_page_instant = embassy_time::Instant::now();
             }
         } else {
@@ -210,10 +315,78 @@ pub fn matrix_task(
             current_page_instant = embassy_time::Instant::now();
         }
 
-        for (page_index, page) in &mut pages.iter_mut().enumerate() {
-            if page_index != current_page_index {
-                page.idle_update();
+        sleep_ms(scheduler.sleep_ms(Instant::now().as_millis(), POLL_INTERVAL_MS));
+    }
+}
+
+/// Panel brightness following the ambient light sensor unless overridden from HA, never fully dark while on.
+fn brightness() -> u8 {
+    match state::get_display_state() {
+        state::DisplayState { on: false, .. } => 0,
+        state::DisplayState { brightness: Some(brightness), .. } => brightness.max(1),
+        state::DisplayState { brightness: None, .. } => auto_brightness(),
+    }
+}
+
+pub fn auto_brightness() -> u8 {
+    let brightness = ((get_brightness_percent() / 100.0) * 255.0) as u8;
+    brightness.max(5)
+}
+
+/// Index of the page after (or before) the current one, skipping the pages the night mode leaves out. The current
+/// index when there is no other page to show.
+fn next_page_index(pages: &[pages::Pages], current: usize, backwards: bool, night: &night::NightMode) -> usize {
+    let len = pages.len();
+    (1..len)
+        .map(|offset| if backwards { (current + len - offset) % len } else { (current + offset) % len })
+        .find(|index| night.shows_page(pages[*index].name()))
+        .unwrap_or(current)
+}
+
+/// Brings the page list in line with the enabled pages of the registry, reusing the pages that are still enabled so
+/// they keep their state. Returns the new index of the current page.
+fn sync_pages(
+    pages: &mut Vec<pages::Pages>,
+    current_page_index: usize,
+    rtc: &'static esp_hal::rtc_cntl::Rtc<'static>,
+) -> usize {
+    let current_page_name = pages.get(current_page_index).map(|page| String::from(page.name()));
+    let mut apps = custom_apps::get_custom_apps();
+    let mut old_pages = core::mem::take(pages);
+
+    for name in page_registry::get_enabled_pages() {
+        let app = apps.iter().position(|app| app.name == name).map(|position| apps.remove(position));
+        let page = match old_pages.iter().position(|page| page.name() == name) {
+            Some(position) => {
+                let mut page = old_pages.remove(position);
+                if let (pages::Pages::Custom(page), Some(app)) = (&mut page, app) {
+                    page.set_app(app);
+                }
+                Some(page)
             }
+            None => match (name.as_str(), app) {
+                ("time", _) => Some(pages::Time::new(rtc)),
+                ("date", _) => Some(pages::Date::new(rtc)),
+                ("timer", _) => Some(pages::Timer::new()),
+                ("battery", _) => Some(pages::Battery::new()),
+                ("alarm", _) => Some(pages::Alarm::new()),
+                ("stopwatch", _) => Some(pages::Stopwatch::new()),
+                ("countdown", _) => Some(pages::Countdown::new(rtc)),
+                ("entities", _) => Some(pages::Entities::new()),
+                ("chart", _) => Some(pages::Chart::new()),
+                ("games", _) => Some(pages::Games::new()),
+                (_, Some(app)) => Some(pages::Custom::new(app)),
+                _ => None,
+            },
+        };
+        if let Some(page) = page {
+            pages.push(page);
         }
     }
+
+    if pages.is_empty() {
+        pages.push(pages::Time::new(rtc));
+    }
+
+    current_page_name.and_then(|name| pages.iter().position(|page| page.name() == name)).unwrap_or(0)
 }
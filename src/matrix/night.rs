@@ -0,0 +1,122 @@
+use chrono::{Datelike as _, Offset as _, TimeZone as _, Timelike as _};
+use embassy_time::{Duration, Instant};
+
+use crate::{
+    adc, alarm,
+    night::{self, NightAction, NightDetector, NightSettings, NightWindow},
+    ntp,
+};
+
+/// How often the window and the light sensor are checked.
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Decides whether the display is in night mode and keeps track of button wake-ups.
+pub struct NightMode {
+    rtc: &'static esp_hal::rtc_cntl::Rtc<'static>,
+    settings: NightSettings,
+    settings_version: u32,
+    detector: NightDetector,
+    /// Day of the year and the sunrise and sunset computed for it.
+    sun: Option<(u16, Option<(u16, u16)>)>,
+    night: bool,
+    /// An alarm rings, which always gets the normal display.
+    ringing: bool,
+    awake_until: Option<Instant>,
+    checked_at: Option<Instant>,
+}
+
+impl NightMode {
+    pub fn new(rtc: &'static esp_hal::rtc_cntl::Rtc<'static>) -> Self {
+        NightMode {
+            rtc,
+            settings: night::get_settings(),
+            settings_version: night::get_settings_version(),
+            detector: NightDetector::new(),
+            sun: None,
+            night: false,
+            ringing: false,
+            awake_until: None,
+            checked_at: None,
+        }
+    }
+
+    /// Checks the night again once per interval, returns `true` when the display has to change.
+    pub fn update(&mut self) -> bool {
+        let before = self.action();
+        if self.checked_at.is_none_or(|checked_at| checked_at.elapsed() >= CHECK_INTERVAL) {
+            self.checked_at = Some(Instant::now());
+            if night::get_settings_version() != self.settings_version {
+                self.settings_version = night::get_settings_version();
+                self.settings = night::get_settings();
+                self.sun = None;
+            }
+
+            let now = chrono::NaiveDateTime::from_timestamp_micros(self.rtc.current_time_us() as i64).unwrap();
+            let minute = (now.hour() * 60 + now.minute()) as u16;
+            let sun = self.sun_times(&now);
+            self.night = self.detector.update(&self.settings, minute, sun, adc::get_brightness_percent());
+            self.ringing = alarm::get_ringing().is_some();
+        }
+        if self.awake_until.is_some_and(|awake_until| Instant::now() >= awake_until) {
+            self.awake_until = None;
+        }
+        self.action() != before
+    }
+
+    /// Today's sunrise and sunset, only computed once a day.
+    fn sun_times(&mut self, now: &chrono::NaiveDateTime) -> Option<(u16, u16)> {
+        if self.settings.window != NightWindow::Sun {
+            return None;
+        }
+        let day = now.ordinal() as u16;
+        match self.sun {
+            Some((sun_day, sun)) if sun_day == day => sun,
+            _ => {
+                let utc_offset = ntp::TIMEZONE
+                    .offset_from_local_datetime(now)
+                    .earliest()
+                    .map_or(0, |offset| offset.fix().local_minus_utc() / 60);
+                let sun = night::sun_times(day, self.settings.latitude, self.settings.longitude, utc_offset);
+                info!("Sunrise and sunset today: {:?}", sun);
+                self.sun = Some((day, sun));
+                sun
+            }
+        }
+    }
+
+    /// What the display does instead of the normal pages, `None` by day, after a button press and while an alarm
+    /// rings.
+    pub fn action(&self) -> Option<NightAction> {
+        (self.night && self.awake_until.is_none() && !self.ringing).then_some(self.settings.action)
+    }
+
+    /// Night brightness, never brighter than the display would be by day.
+    pub fn brightness(&self, brightness: u8) -> u8 {
+        match self.action() {
+            None => brightness,
+            Some(NightAction::Off) => 0,
+            Some(_) => self.settings.brightness.min(brightness),
+        }
+    }
+
+    /// Whether the page is part of the rotation, the dim action can be limited to some pages.
+    pub fn shows_page(&self, name: &str) -> bool {
+        match self.action() {
+            Some(NightAction::Dim) => self.settings.shows_page(name),
+            _ => true,
+        }
+    }
+
+    /// A button press at night wakes the display up for a while, every press meanwhile extends it. Returns `true`
+    /// when the press only woke the display and must not reach the pages.
+    pub fn wake(&mut self) -> bool {
+        if !self.night || self.ringing {
+            return false;
+        }
+        let was_dark = self.awake_until.is_none();
+        if self.settings.wake_secs > 0 {
+            self.awake_until = Some(Instant::now() + Duration::from_secs(self.settings.wake_secs as u64));
+        }
+        was_dark
+    }
+}
@@ -0,0 +1,115 @@
+use embassy_time::{Duration, Instant};
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+use rwtrix_core::{frame::Redraw, layers::Layer};
+
+use crate::{
+    matrix::{
+        event::MatrixEventDetails,
+        fonts::AwtrixFont,
+        icons,
+        scroller::{ScrollMode, Scroller},
+    },
+    notifications::{self, Notification},
+};
+
+/// Alpha of the dark backdrop behind the notification text, the page stays faintly visible below it.
+const BACKDROP_ALPHA: u8 = 200;
+
+pub struct NotificationOverlay {
+    current: Option<Notification>,
+    shown_at: Instant,
+    scroller: Scroller,
+}
+
+impl NotificationOverlay {
+    pub fn new() -> Self {
+        NotificationOverlay {
+            current: None,
+            shown_at: Instant::now(),
+            scroller: Scroller::new(crate::matrix::WIDTH, ScrollMode::Loop),
+        }
+    }
+
+    fn text_x(notification: &Notification) -> i32 {
+        if notification.icon.is_some() {
+            icons::ICON_SIZE as i32 + 1
+        } else {
+            1
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.current.is_some()
+    }
+
+    /// A notification is waiting to be shown, cheap enough to check between frames.
+    pub fn is_pending(&self) -> bool {
+        self.current.is_none() && notifications::peek_next_priority().is_some()
+    }
+
+    pub fn redraw(&self) -> Redraw {
+        if self.is_active() {
+            self.scroller.redraw()
+        } else {
+            Redraw::IDLE
+        }
+    }
+
+    pub fn update(&mut self) {
+        let dismiss = notifications::take_dismiss_request();
+
+        if let Some(current) = &self.current {
+            let expired = !current.hold
+                && self.scroller.pass_completed()
+                && Instant::now().checked_duration_since(self.shown_at).unwrap_or_default()
+                    >= Duration::from_secs(current.duration as u64);
+            if dismiss || expired {
+                self.current = None;
+            } else if notifications::peek_next_priority().is_some_and(|priority| priority > current.priority) {
+                if let Some(preempted) = self.current.take() {
+                    notifications::requeue(preempted);
+                }
+            }
+        }
+
+        if self.current.is_none() {
+            if let Some(next) = notifications::pop_next() {
+                info!("Showing notification: {:?}", next.text);
+                self.scroller.set_width(crate::matrix::WIDTH - Self::text_x(&next) as u32);
+                self.scroller.set_text(&next.text);
+                self.scroller.restart();
+                self.current = Some(next);
+                self.shown_at = Instant::now();
+            }
+        }
+
+        self.scroller.update();
+    }
+
+    /// Select dismisses the visible notification, returns `true` when the event was consumed.
+    pub fn handle_event(&mut self, event: &MatrixEventDetails) -> bool {
+        if self.current.is_some() && event.is_single_press() && event.has_select() {
+            self.current = None;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn render(&self, target: &mut Layer) {
+        let Some(notification) = &self.current else {
+            return;
+        };
+
+        target.translucent(BACKDROP_ALPHA).clear(Rgb888::BLACK).ok();
+
+        let [r, g, b] = notification.color;
+        let color = Rgb888::new(r, g, b);
+
+        if let Some(icon) = &notification.icon {
+            icons::draw_icon(target, icon, Point::zero(), color);
+        }
+
+        self.scroller.render(target, Point::new(Self::text_x(notification), 1), AwtrixFont::new(color));
+    }
+}
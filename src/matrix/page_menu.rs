@@ -0,0 +1,190 @@
+use alloc::{format, vec::Vec};
+
+use embassy_time::{Duration, Instant};
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+use rwtrix_core::frame::Redraw;
+
+use crate::{
+    matrix::{
+        event::MatrixEventDetails,
+        fonts::AwtrixFont,
+        pages::PageTarget,
+        scroller::{ScrollMode, Scroller},
+    },
+    page_registry::{self, PageEntry},
+};
+
+const MENU_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MenuMode {
+    /// Left/Right select a page, Select toggles it, long Left/Right move it and long Select edits its duration.
+    List,
+    /// Left/Right change the duration of the selected page, Select goes back to the list.
+    Duration,
+}
+
+/// On-device editor for the page registry, opened and closed by pressing Left and Right together.
+pub struct PageMenu {
+    entries: Option<Vec<PageEntry>>,
+    cursor: usize,
+    mode: MenuMode,
+    last_input: Instant,
+    scroller: Scroller,
+}
+
+impl PageMenu {
+    pub fn new() -> Self {
+        PageMenu {
+            entries: None,
+            cursor: 0,
+            mode: MenuMode::List,
+            last_input: Instant::now(),
+            scroller: Scroller::new(crate::matrix::WIDTH - 2, ScrollMode::Loop),
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.entries.is_some()
+    }
+
+    fn open(&mut self) {
+        info!("Opening page menu");
+        self.entries = Some(page_registry::get_entries());
+        self.cursor = 0;
+        self.mode = MenuMode::List;
+    }
+
+    fn close(&mut self) {
+        info!("Closing page menu");
+        self.entries = None;
+    }
+
+    pub fn redraw(&self) -> Redraw {
+        if self.is_active() {
+            self.scroller.redraw()
+        } else {
+            Redraw::IDLE
+        }
+    }
+
+    pub fn update(&mut self) {
+        let idle = Instant::now().checked_duration_since(self.last_input).unwrap_or_default();
+        if self.is_active() && idle > MENU_TIMEOUT {
+            self.close();
+        }
+
+        let text = match (&self.entries, self.mode) {
+            (Some(entries), MenuMode::List) => entries[self.cursor].name.clone(),
+            (Some(entries), MenuMode::Duration) => match entries[self.cursor].duration {
+                Some(duration) => format!("{}s", duration),
+                None => "AUTO".into(),
+            },
+            (None, _) => return,
+        };
+        self.scroller.set_text(&text);
+        self.scroller.update();
+    }
+
+    /// Returns `true` when the event was consumed by the menu.
+    pub fn handle_event(&mut self, event: &MatrixEventDetails) -> bool {
+        let combo = event.has_left() && event.has_right();
+        let Some(entries) = &mut self.entries else {
+            if combo {
+                self.open();
+                self.last_input = Instant::now();
+                return true;
+            }
+            return false;
+        };
+        self.last_input = Instant::now();
+
+        if combo {
+            self.close();
+            return true;
+        }
+        if !event.is_single_press() {
+            return true;
+        }
+
+        let long = event.is_long_press();
+        let len = entries.len();
+        let mut changed = false;
+        match self.mode {
+            MenuMode::List => {
+                if event.has_select() {
+                    if long {
+                        self.mode = MenuMode::Duration;
+                    } else {
+                        entries[self.cursor].enabled = !entries[self.cursor].enabled;
+                        changed = true;
+                    }
+                } else if event.has_left() {
+                    let target = (self.cursor + len - 1) % len;
+                    if long {
+                        entries.swap(self.cursor, target);
+                        changed = true;
+                    }
+                    self.cursor = target;
+                } else if event.has_right() {
+                    let target = (self.cursor + 1) % len;
+                    if long {
+                        entries.swap(self.cursor, target);
+                        changed = true;
+                    }
+                    self.cursor = target;
+                }
+            }
+            MenuMode::Duration => {
+                let step = if long { 5 } else { 1 };
+                let entry = &mut entries[self.cursor];
+                if event.has_select() {
+                    self.mode = MenuMode::List;
+                } else if event.has_left() {
+                    entry.duration = match entry.duration {
+                        Some(duration) if duration > page_registry::MIN_PAGE_DURATION_SECS => {
+                            Some(duration.saturating_sub(step).max(page_registry::MIN_PAGE_DURATION_SECS))
+                        }
+                        _ => None,
+                    };
+                    changed = true;
+                } else if event.has_right() {
+                    entry.duration = Some(match entry.duration {
+                        Some(duration) => (duration + step).min(page_registry::MAX_PAGE_DURATION_SECS),
+                        None => page_registry::DEFAULT_PAGE_DURATION_SECS,
+                    });
+                    changed = true;
+                }
+            }
+        }
+
+        if changed {
+            page_registry::set_entries(entries.clone());
+        }
+        true
+    }
+
+    pub fn render<T: PageTarget>(&self, target: &mut T) {
+        let Some(entries) = &self.entries else {
+            return;
+        };
+        let entry = &entries[self.cursor];
+
+        target.clear(Rgb888::BLACK).ok();
+
+        let color = match (self.mode, entry.enabled) {
+            (MenuMode::Duration, _) => Rgb888::YELLOW,
+            (MenuMode::List, true) => Rgb888::GREEN,
+            (MenuMode::List, false) => Rgb888::RED,
+        };
+        self.scroller.render(target, Point::new(1, 1), AwtrixFont::new(color));
+
+        // position of the selected page along the bottom row
+        let width = crate::matrix::WIDTH as usize;
+        let start = self.cursor * width / entries.len();
+        let end = ((self.cursor + 1) * width / entries.len()).max(start + 1);
+        for x in start..end {
+            Pixel(Point::new(x as i32, crate::matrix::HEIGHT as i32 - 1), Rgb888::CSS_DARK_GRAY).draw(target).ok();
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use alloc::{boxed::Box, string::String};
+use core::fmt::Write as _;
+
+use embedded_graphics::{
+    pixelcolor::Rgb888,
+    prelude::*,
+    primitives::{PrimitiveStyle, Rectangle},
+    text::Text,
+};
+
+use crate::{
+    alarm::{self, ALARM_COUNT},
+    matrix::{
+        event::MatrixEventDetails,
+        fonts::AwtrixFont,
+        icons,
+        pages::{PageTarget, Pages},
+    },
+};
+
+/// Shows one alarm slot at a time, left and right go through the slots and select turns the shown alarm on or off.
+/// Everything else is edited from Home Assistant.
+pub struct Alarm {
+    selected: usize,
+    alarm: alarm::Alarm,
+    alarms_version: u32,
+    current_time: String,
+}
+
+impl Alarm {
+    pub fn new() -> Pages {
+        Pages::Alarm(Box::new(Alarm {
+            selected: 0,
+            alarm: alarm::Alarm::default(),
+            alarms_version: alarm::get_alarms_version().wrapping_sub(1),
+            current_time: String::with_capacity(5),
+        }))
+    }
+
+    fn refresh(&mut self) {
+        self.alarms_version = alarm::get_alarms_version();
+        self.alarm = alarm::get_alarm(self.selected);
+        self.current_time.clear();
+        write!(self.current_time, "{:02}:{:02}", self.alarm.hour, self.alarm.minute).ok();
+    }
+
+    pub fn update(&mut self) {
+        if alarm::get_alarms_version() != self.alarms_version {
+            self.refresh();
+        }
+    }
+
+    pub fn render<T: PageTarget>(&self, target: &mut T) {
+        target.clear(Rgb888::BLACK).ok();
+
+        let color = if self.alarm.enabled { Rgb888::GREEN } else { Rgb888::CSS_GRAY };
+        icons::draw_icon(target, "bell", Point::zero(), color);
+        Text::new(self.current_time.as_str(), Point::new(12, 1), AwtrixFont::new(color)).draw(target).ok();
+
+        for day in 0..7 {
+            let color = if self.alarm.rings_on(day) { color } else { Rgb888::new(40, 40, 40) };
+            let day = day as i32;
+            Rectangle::new(Point::new(10 + 3 * day, 7), Size::new(2, 1))
+                .into_styled(PrimitiveStyle::with_fill(color))
+                .draw(target)
+                .ok();
+        }
+
+        // slot number in the top right corner
+        for slot in 0..ALARM_COUNT {
+            let color = if slot == self.selected { Rgb888::WHITE } else { Rgb888::new(40, 40, 40) };
+            Pixel(Point::new(31, slot as i32), color).draw(target).ok();
+        }
+    }
+
+    pub fn handle_event(&mut self, event: MatrixEventDetails) {
+        if !event.is_single_press() {
+            return;
+        }
+        if event.has_left() {
+            self.selected = (self.selected + ALARM_COUNT - 1) % ALARM_COUNT;
+        } else if event.has_right() {
+            self.selected = (self.selected + 1) % ALARM_COUNT;
+        } else if event.has_select() {
+            alarm::set_alarm_enabled(self.selected, !self.alarm.enabled);
+        }
+        self.refresh();
+    }
+}
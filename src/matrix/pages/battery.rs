@@ -1,7 +1,7 @@
 use alloc::{boxed::Box, string::String};
 use core::fmt::Write as _;
 
-use chrono::{Datelike, Timelike as _};
+use embassy_time::Instant;
 use embedded_graphics::{
     pixelcolor::Rgb888,
     prelude::*,
@@ -9,26 +9,82 @@ use embedded_graphics::{
     text::Text,
 };
 use num_traits::float::Float;
+use rwtrix_core::frame::Redraw;
 
-use crate::matrix::{
-    fonts::AwtrixFont,
-    pages::{PageTarget, Pages},
+use crate::{
+    adc::{self, ChargeState},
+    matrix::{
+        fonts::AwtrixFont,
+        pages::{PageTarget, Pages},
+    },
 };
 
+/// Rows inside the battery icon.
+const ICON_LEVELS: i32 = 6;
+/// How long the level and the runtime estimate are shown each before switching.
+const TEXT_SWITCH_MS: u64 = 3000;
+/// Time between the steps of the charging animation.
+const CHARGING_STEP_MS: u64 = 250;
+
 pub struct Battery {
     battery_level_percentage: f32,
+    state: ChargeState,
+    minutes_remaining: Option<u32>,
     current_battery: String,
+    showing_runtime: bool,
+    since: Instant,
 }
 
 impl Battery {
     pub fn new() -> Pages {
-        Pages::Battery(Box::new(Battery { battery_level_percentage: 0.0, current_battery: String::new() }))
+        Pages::Battery(Box::new(Battery {
+            battery_level_percentage: 0.0,
+            state: ChargeState::Unknown,
+            minutes_remaining: None,
+            current_battery: String::new(),
+            showing_runtime: false,
+            since: Instant::now(),
+        }))
+    }
+
+    /// Starts with the level, the runtime estimate follows.
+    pub fn enter(&mut self) {
+        self.since = Instant::now();
+    }
+
+    pub fn redraw(&self) -> Redraw {
+        if self.state == ChargeState::Charging {
+            Redraw::AfterMs(CHARGING_STEP_MS)
+        } else {
+            Redraw::IDLE
+        }
     }
 
     pub fn update(&mut self) {
+        self.battery_level_percentage = adc::get_battery_level_percentage();
+        (self.state, self.minutes_remaining) = adc::get_battery_trend();
+        self.showing_runtime =
+            self.minutes_remaining.is_some() && self.since.elapsed().as_millis() / TEXT_SWITCH_MS % 2 == 1;
+
         self.current_battery.clear();
-        self.battery_level_percentage = crate::adc::get_battery_level_percentage();
-        write!(&mut self.current_battery, "{:.0}%", self.battery_level_percentage).ok();
+        match self.minutes_remaining {
+            Some(minutes) if self.showing_runtime => adc::write_runtime(&mut self.current_battery, minutes),
+            _ => {
+                write!(&mut self.current_battery, "{:.0}%", self.battery_level_percentage).ok();
+            }
+        }
+    }
+
+    fn level_color(&self) -> Rgb888 {
+        if matches!(self.state, ChargeState::Charging | ChargeState::Full) {
+            Rgb888::GREEN
+        } else if self.battery_level_percentage < 20.0 {
+            Rgb888::RED
+        } else if self.battery_level_percentage < 50.0 {
+            Rgb888::YELLOW
+        } else {
+            Rgb888::MAGENTA
+        }
     }
 
     pub fn render<T: PageTarget>(&self, target: &mut T) {
@@ -44,18 +100,31 @@ impl Battery {
         rectangle.translate(Point::new(0, 0)).draw(target).ok();
         rectangle.translate(Point::new(4, 0)).draw(target).ok();
 
-        let filled_height = (self.battery_level_percentage / 100.0 * 6.0).round() as i32;
+        let level_height = (self.battery_level_percentage / 100.0 * ICON_LEVELS as f32).round() as i32;
+        // while charging the fill keeps rising from the current level to the top
+        let filled_height = if self.state == ChargeState::Charging {
+            let steps = (ICON_LEVELS - level_height + 1) as u64;
+            level_height + (self.since.elapsed().as_millis() / CHARGING_STEP_MS % steps) as i32
+        } else {
+            level_height
+        };
 
         Rectangle::new(Point::new(1, 7 - filled_height), Size::new(3, filled_height as u32))
-            .into_styled(PrimitiveStyle::with_fill(Rgb888::MAGENTA))
+            .into_styled(PrimitiveStyle::with_fill(self.level_color()))
             .draw(target)
             .ok();
 
-        let time_style = AwtrixFont::new(Rgb888::YELLOW);
-        Text::new(self.current_battery.as_str(), Point::new(12, 1), time_style).draw(target).ok();
+        let text_color = match self.state {
+            _ if !self.showing_runtime => Rgb888::YELLOW,
+            ChargeState::Charging => Rgb888::GREEN,
+            _ => Rgb888::WHITE,
+        };
+        let text_width = AwtrixFont::measure_width(&self.current_battery) as i32;
+        let text_x = 6 + (crate::matrix::WIDTH as i32 - 6 - text_width).max(0) / 2;
+        Text::new(self.current_battery.as_str(), Point::new(text_x, 1), AwtrixFont::new(text_color)).draw(target).ok();
     }
 
     pub fn handle_event(&mut self, _event: crate::matrix::event::MatrixEventDetails) {
-        // No event handling needed for Time page
+        // No event handling needed for Battery page
     }
 }
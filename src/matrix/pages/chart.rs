@@ -0,0 +1,129 @@
+use alloc::{boxed::Box, vec::Vec};
+
+use embassy_time::{Duration, Instant};
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*, primitives::Rectangle};
+use rwtrix_core::frame::Redraw;
+
+use crate::{
+    charts::{self, Series},
+    matrix::{
+        chart,
+        event::MatrixEventDetails,
+        fonts::AwtrixFont,
+        pages::{PageTarget, Pages},
+        scroller::{ScrollMode, Scroller},
+    },
+};
+
+/// Short names still stay up this long before the chart is shown.
+const MIN_NAME_DURATION: Duration = Duration::from_secs(1);
+const CHART_DURATION: Duration = Duration::from_secs(5);
+
+/// Goes through the series that have samples one after the other, showing the name and then the chart. Left and
+/// right skip between the series.
+pub struct Chart {
+    series: Vec<Series>,
+    series_version: u32,
+    index: usize,
+    /// When the chart of the current series started showing, `None` while its name is shown.
+    chart_since: Option<Instant>,
+    name_since: Instant,
+    /// Every series was shown once since the page was entered.
+    cycle_completed: bool,
+    scroller: Scroller,
+}
+
+impl Chart {
+    pub fn new() -> Pages {
+        Pages::Chart(Box::new(Chart {
+            series: Vec::new(),
+            series_version: charts::get_series_version().wrapping_sub(1),
+            index: 0,
+            chart_since: None,
+            name_since: Instant::now(),
+            cycle_completed: false,
+            scroller: Scroller::new(crate::matrix::WIDTH, ScrollMode::Once),
+        }))
+    }
+
+    pub fn enter(&mut self) {
+        self.cycle_completed = false;
+        self.show(self.index);
+    }
+
+    pub fn can_rotate(&self) -> bool {
+        self.cycle_completed || self.series.is_empty()
+    }
+
+    fn show(&mut self, index: usize) {
+        self.index = if self.series.is_empty() { 0 } else { index % self.series.len() };
+        self.chart_since = None;
+        self.name_since = Instant::now();
+        let name = self.series.get(self.index).map_or("no data", |series| series.name.as_str());
+        self.scroller.set_text(name);
+        self.scroller.restart();
+    }
+
+    fn next(&mut self) {
+        if self.index + 1 >= self.series.len() {
+            self.cycle_completed = true;
+        }
+        self.show(self.index + 1);
+    }
+
+    /// New samples arrive at any time and are polled, only the name scrolls.
+    pub fn redraw(&self) -> Redraw {
+        match self.chart_since {
+            None => self.scroller.redraw(),
+            Some(_) => Redraw::IDLE,
+        }
+    }
+
+    pub fn update(&mut self) {
+        if charts::get_series_version() != self.series_version {
+            self.series_version = charts::get_series_version();
+            let name = self.series.get(self.index).map(|series| series.name.clone());
+            self.series = charts::get_series().into_iter().filter(|series| !series.history.is_empty()).collect();
+            // keep showing the same series, new samples only move its chart
+            match name.and_then(|name| self.series.iter().position(|series| series.name == name)) {
+                Some(index) => self.index = index,
+                None => self.show(self.index),
+            }
+        }
+        self.scroller.update();
+
+        if self.series.is_empty() {
+            return;
+        }
+        match self.chart_since {
+            None if self.scroller.pass_completed() && self.name_since.elapsed() >= MIN_NAME_DURATION => {
+                self.chart_since = Some(Instant::now());
+            }
+            Some(since) if since.elapsed() >= CHART_DURATION => self.next(),
+            _ => {}
+        }
+    }
+
+    pub fn render<T: PageTarget>(&self, target: &mut T) {
+        target.clear(Rgb888::BLACK).ok();
+        match (self.series.get(self.index), self.chart_since) {
+            (Some(series), Some(_)) => {
+                let area = Rectangle::new(Point::zero(), Size::new(crate::matrix::WIDTH, crate::matrix::HEIGHT));
+                chart::draw_chart(target, series, area);
+            }
+            (Some(_), None) => self.scroller.render(target, Point::new(0, 1), AwtrixFont::new(Rgb888::WHITE)),
+            (None, _) => self.scroller.render(target, Point::new(0, 1), AwtrixFont::new(Rgb888::CSS_GRAY)),
+        }
+    }
+
+    pub fn handle_event(&mut self, event: MatrixEventDetails) {
+        if !event.is_single_press() || self.series.is_empty() {
+            return;
+        }
+        if event.has_left() {
+            self.show(self.index + self.series.len() - 1);
+        } else if event.has_right() {
+            self.show(self.index + 1);
+        }
+    }
+}
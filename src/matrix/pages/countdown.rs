@@ -0,0 +1,151 @@
+use alloc::{boxed::Box, string::String, vec::Vec};
+
+use embassy_time::{Duration, Instant};
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*, text::Text};
+use rwtrix_core::{countdown::CountdownMode, frame::Redraw};
+
+use crate::{
+    countdowns,
+    matrix::{
+        event::MatrixEventDetails,
+        fonts::AwtrixFont,
+        pages::{PageTarget, Pages},
+        scroller::{ScrollMode, Scroller},
+    },
+};
+
+/// Short names still stay up this long before the value is shown.
+const MIN_NAME_DURATION: Duration = Duration::from_secs(1);
+const VALUE_DURATION: Duration = Duration::from_secs(3);
+
+/// Goes through the countdowns one after the other, showing the name and then the time left or elapsed. Left and
+/// right skip between the countdowns.
+pub struct Countdown {
+    rtc: &'static esp_hal::rtc_cntl::Rtc<'static>,
+    countdowns: Vec<countdowns::Countdown>,
+    countdowns_version: u32,
+    index: usize,
+    /// When the value of the current countdown started showing, `None` while its name is shown.
+    value_since: Option<Instant>,
+    name_since: Instant,
+    /// Every countdown was shown once since the page was entered.
+    cycle_completed: bool,
+    scroller: Scroller,
+    value: String,
+}
+
+impl Countdown {
+    pub fn new(rtc: &'static esp_hal::rtc_cntl::Rtc<'static>) -> Pages {
+        Pages::Countdown(Box::new(Countdown {
+            rtc,
+            countdowns: Vec::new(),
+            countdowns_version: countdowns::get_countdowns_version().wrapping_sub(1),
+            index: 0,
+            value_since: None,
+            name_since: Instant::now(),
+            cycle_completed: false,
+            scroller: Scroller::new(crate::matrix::WIDTH, ScrollMode::Once),
+            value: String::with_capacity(8),
+        }))
+    }
+
+    pub fn enter(&mut self) {
+        self.cycle_completed = false;
+        self.show(self.index);
+    }
+
+    pub fn can_rotate(&self) -> bool {
+        self.cycle_completed || self.countdowns.is_empty()
+    }
+
+    fn show(&mut self, index: usize) {
+        self.index = if self.countdowns.is_empty() { 0 } else { index % self.countdowns.len() };
+        self.value_since = None;
+        self.name_since = Instant::now();
+        let name = self.countdowns.get(self.index).map_or("no events", |countdown| countdown.name.as_str());
+        self.scroller.set_text(name);
+        self.scroller.restart();
+    }
+
+    fn next(&mut self) {
+        if self.index + 1 >= self.countdowns.len() {
+            self.cycle_completed = true;
+        }
+        self.show(self.index + 1);
+    }
+
+    /// The value ticks every second, only the name scrolls.
+    pub fn redraw(&self) -> Redraw {
+        match self.value_since {
+            None => self.scroller.redraw(),
+            Some(_) => Redraw::IDLE,
+        }
+    }
+
+    pub fn update(&mut self) {
+        if countdowns::get_countdowns_version() != self.countdowns_version {
+            self.countdowns_version = countdowns::get_countdowns_version();
+            self.countdowns = countdowns::get_countdowns();
+            self.show(self.index);
+        }
+        self.scroller.update();
+
+        let Some(countdown) = self.countdowns.get(self.index) else {
+            return;
+        };
+        match self.value_since {
+            None if self.scroller.pass_completed() && self.name_since.elapsed() >= MIN_NAME_DURATION => {
+                self.value_since = Some(Instant::now());
+            }
+            Some(since) if since.elapsed() >= VALUE_DURATION => {
+                self.next();
+                return;
+            }
+            _ => {}
+        }
+
+        // the RTC keeps local wall time, as do the stored targets
+        let now = (self.rtc.current_time_us() / 1_000_000) as i64;
+        self.value.clear();
+        countdown.write_value(&mut self.value, now);
+    }
+
+    pub fn render<T: PageTarget>(&self, target: &mut T) {
+        target.clear(Rgb888::BLACK).ok();
+        let Some(countdown) = self.countdowns.get(self.index) else {
+            self.scroller.render(target, Point::new(0, 1), AwtrixFont::new(Rgb888::CSS_GRAY));
+            return;
+        };
+        let color = match countdown.mode {
+            CountdownMode::Until => Rgb888::CSS_ORANGE,
+            CountdownMode::Since => Rgb888::CYAN,
+        };
+        if self.value_since.is_none() {
+            self.scroller.render(target, Point::new(0, 1), AwtrixFont::new(Rgb888::WHITE));
+        } else {
+            let x = (crate::matrix::WIDTH.saturating_sub(AwtrixFont::measure_width(&self.value)) / 2) as i32;
+            Text::new(self.value.as_str(), Point::new(x, 1), AwtrixFont::new(color)).draw(target).ok();
+        }
+
+        // position in the list along the bottom row
+        let count = self.countdowns.len() as i32;
+        if count > 1 {
+            let left = (crate::matrix::WIDTH as i32 - (2 * count - 1)) / 2;
+            for i in 0..count {
+                let color = if i as usize == self.index { color } else { Rgb888::new(40, 40, 40) };
+                Pixel(Point::new(left + 2 * i, 7), color).draw(target).ok();
+            }
+        }
+    }
+
+    pub fn handle_event(&mut self, event: MatrixEventDetails) {
+        if !event.is_single_press() || self.countdowns.is_empty() {
+            return;
+        }
+        if event.has_left() {
+            self.show(self.index + self.countdowns.len() - 1);
+        } else if event.has_right() {
+            self.show(self.index + 1);
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use alloc::boxed::Box;
+
+use embassy_time::Duration;
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+use rwtrix_core::frame::Redraw;
+
+use crate::{
+    custom_apps::CustomApp,
+    matrix::{
+        fonts::AwtrixFont,
+        icons,
+        pages::{PageTarget, Pages},
+        scroller::{ScrollMode, Scroller},
+    },
+};
+
+pub struct Custom {
+    app: CustomApp,
+    scroller: Scroller,
+}
+
+impl Custom {
+    pub fn new(app: CustomApp) -> Pages {
+        let mut page = Custom { app, scroller: Scroller::new(crate::matrix::WIDTH, ScrollMode::Loop) };
+        page.refresh_scroller();
+        Pages::Custom(Box::new(page))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.app.name
+    }
+
+    pub fn set_app(&mut self, app: CustomApp) {
+        self.app = app;
+        self.refresh_scroller();
+    }
+
+    fn text_x(&self) -> i32 {
+        if self.app.icon.is_some() {
+            icons::ICON_SIZE as i32 + 1
+        } else {
+            1
+        }
+    }
+
+    fn refresh_scroller(&mut self) {
+        self.scroller.set_width(crate::matrix::WIDTH - self.text_x() as u32);
+        self.scroller.set_text(&self.app.text);
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs(self.app.duration as u64)
+    }
+
+    pub fn enter(&mut self) {
+        self.scroller.restart();
+    }
+
+    pub fn can_rotate(&self) -> bool {
+        self.scroller.pass_completed()
+    }
+
+    pub fn redraw(&self) -> Redraw {
+        self.scroller.redraw()
+    }
+
+    pub fn update(&mut self) {
+        self.scroller.update();
+    }
+
+    pub fn render<T: PageTarget>(&self, target: &mut T) {
+        target.clear(Rgb888::BLACK).ok();
+
+        let [r, g, b] = self.app.color;
+        let color = Rgb888::new(r, g, b);
+
+        if let Some(icon) = &self.app.icon {
+            icons::draw_icon(target, icon, Point::zero(), color);
+        }
+
+        self.scroller.render(target, Point::new(self.text_x(), 1), AwtrixFont::new(color));
+    }
+
+    pub fn handle_event(&mut self, _event: crate::matrix::event::MatrixEventDetails) {
+        // No event handling needed for Custom page
+    }
+}
@@ -1,38 +1,77 @@
 use alloc::{boxed::Box, string::String};
-use core::fmt::Write as _;
 
+use chrono::Datelike as _;
 use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+use rwtrix_core::frame::Redraw;
 
-use crate::matrix::{fonts::AwtrixFont, pages::Pages};
+use crate::{
+    date::{self, DateSettings},
+    matrix::{
+        fonts::AwtrixFont,
+        pages::Pages,
+        scroller::{ScrollMode, Scroller},
+    },
+};
 
 pub struct Date {
     rtc: &'static esp_hal::rtc_cntl::Rtc<'static>,
-    current_time: String,
+    settings: DateSettings,
+    settings_version: u32,
+    current_date: String,
+    scroller: Scroller,
 }
 
 impl Date {
     pub fn new(rtc: &'static esp_hal::rtc_cntl::Rtc<'static>) -> Pages {
-        Pages::Date(Box::new(Date { rtc, current_time: String::from("00:00:00") }))
+        Pages::Date(Box::new(Date {
+            rtc,
+            settings: date::get_settings(),
+            settings_version: date::get_settings_version(),
+            current_date: String::from("00.00.00"),
+            scroller: Scroller::new(crate::matrix::WIDTH - 3, ScrollMode::Once),
+        }))
+    }
+
+    pub fn enter(&mut self) {
+        self.scroller.restart();
+    }
+
+    pub fn can_rotate(&self) -> bool {
+        self.scroller.pass_completed()
+    }
+
+    pub fn redraw(&self) -> Redraw {
+        self.scroller.redraw()
     }
 
     pub fn update(&mut self) {
-        self.current_time.clear();
+        if date::get_settings_version() != self.settings_version {
+            self.settings_version = date::get_settings_version();
+            self.settings = date::get_settings();
+        }
+
+        self.current_date.clear();
         let now = self.rtc.current_time_us();
         let now = chrono::NaiveDateTime::from_timestamp_micros(now as i64).unwrap();
         let date = now.date();
-        write!(&mut self.current_time, "{}", date.format("%d.%m.%y")).ok();
+        self.settings.write_date(
+            &mut self.current_date,
+            date.year(),
+            date.month() as u8,
+            date.day() as u8,
+            date.weekday().num_days_from_monday() as u8,
+        );
+        self.scroller.set_text(&self.current_date);
+        self.scroller.update();
     }
 
     pub fn render<T: super::PageTarget>(&self, target: &mut T) {
         target.clear(Rgb888::BLACK).ok();
-        //let font = embedded_graphics::mono_font::ascii::FONT_4X6;
-        //let style = embedded_graphics::mono_font::MonoTextStyle::new(&font, embedded_graphics::pixelcolor::Rgb888::RED);
-        //let style = super::font::mateine(embedded_graphics::pixelcolor::Rgb888::RED);
         let style = AwtrixFont::new(Rgb888::YELLOW);
-        embedded_graphics::text::Text::new(self.current_time.as_str(), Point::new(3, 1), style).draw(target).ok();
+        self.scroller.render(target, Point::new(3, 1), style);
     }
 
     pub fn handle_event(&mut self, _event: crate::matrix::event::MatrixEventDetails) {
-        // No event handling needed for Time page
+        // No event handling needed for Date page
     }
 }
@@ -0,0 +1,160 @@
+use alloc::{boxed::Box, string::String, vec::Vec};
+
+use embassy_time::{Duration, Instant};
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*, text::Text};
+use rwtrix_core::frame::Redraw;
+
+use crate::{
+    entity_page::{self, EntityWidget},
+    matrix::{
+        event::MatrixEventDetails,
+        fonts::AwtrixFont,
+        icons,
+        pages::{PageTarget, Pages},
+        scroller::{ScrollMode, Scroller},
+    },
+};
+
+/// Short names still stay up this long before the value is shown.
+const MIN_NAME_DURATION: Duration = Duration::from_secs(1);
+const VALUE_DURATION: Duration = Duration::from_secs(4);
+
+/// Goes through the entity widgets one after the other, showing the name and then the latest state. Left and right
+/// skip between the widgets.
+pub struct Entities {
+    widgets: Vec<EntityWidget>,
+    widgets_version: u32,
+    values_version: u32,
+    index: usize,
+    /// When the value of the current widget started showing, `None` while its name is shown.
+    value_since: Option<Instant>,
+    name_since: Instant,
+    /// Every widget was shown once since the page was entered.
+    cycle_completed: bool,
+    scroller: Scroller,
+    value: String,
+    color: Rgb888,
+}
+
+impl Entities {
+    pub fn new() -> Pages {
+        Pages::Entities(Box::new(Entities {
+            widgets: Vec::new(),
+            widgets_version: entity_page::get_widgets_version().wrapping_sub(1),
+            values_version: entity_page::get_values_version().wrapping_sub(1),
+            index: 0,
+            value_since: None,
+            name_since: Instant::now(),
+            cycle_completed: false,
+            scroller: Scroller::new(crate::matrix::WIDTH, ScrollMode::Once),
+            value: String::with_capacity(16),
+            color: Rgb888::WHITE,
+        }))
+    }
+
+    pub fn enter(&mut self) {
+        self.cycle_completed = false;
+        self.show(self.index);
+    }
+
+    pub fn can_rotate(&self) -> bool {
+        self.cycle_completed || self.widgets.is_empty()
+    }
+
+    fn text_x(&self) -> i32 {
+        match self.widgets.get(self.index) {
+            Some(EntityWidget { icon: Some(_), .. }) => icons::ICON_SIZE as i32 + 1,
+            _ => 0,
+        }
+    }
+
+    fn show(&mut self, index: usize) {
+        self.index = if self.widgets.is_empty() { 0 } else { index % self.widgets.len() };
+        self.value_since = None;
+        self.name_since = Instant::now();
+        let name = self.widgets.get(self.index).map_or("no entities", |widget| widget.name.as_str());
+        self.scroller.set_width(crate::matrix::WIDTH - self.text_x() as u32);
+        self.scroller.set_text(name);
+        self.scroller.restart();
+        self.refresh_value();
+    }
+
+    fn next(&mut self) {
+        if self.index + 1 >= self.widgets.len() {
+            self.cycle_completed = true;
+        }
+        self.show(self.index + 1);
+    }
+
+    fn refresh_value(&mut self) {
+        let Some(widget) = self.widgets.get(self.index) else {
+            return;
+        };
+        let payload = entity_page::get_value(&widget.topic);
+        self.value.clear();
+        widget.write_value(&mut self.value, payload.as_deref());
+        let [r, g, b] = widget.color(payload.as_deref());
+        self.color = Rgb888::new(r, g, b);
+    }
+
+    /// Values arrive from Home Assistant at any time and are polled, only the name scrolls.
+    pub fn redraw(&self) -> Redraw {
+        match self.value_since {
+            None => self.scroller.redraw(),
+            Some(_) => Redraw::IDLE,
+        }
+    }
+
+    pub fn update(&mut self) {
+        if entity_page::get_widgets_version() != self.widgets_version {
+            self.widgets_version = entity_page::get_widgets_version();
+            self.widgets = entity_page::get_widgets();
+            self.show(self.index);
+        }
+        if entity_page::get_values_version() != self.values_version {
+            self.values_version = entity_page::get_values_version();
+            self.refresh_value();
+        }
+        self.scroller.update();
+
+        if self.widgets.is_empty() {
+            return;
+        }
+        match self.value_since {
+            None if self.scroller.pass_completed() && self.name_since.elapsed() >= MIN_NAME_DURATION => {
+                self.value_since = Some(Instant::now());
+            }
+            Some(since) if since.elapsed() >= VALUE_DURATION => self.next(),
+            _ => {}
+        }
+    }
+
+    pub fn render<T: PageTarget>(&self, target: &mut T) {
+        target.clear(Rgb888::BLACK).ok();
+        let Some(widget) = self.widgets.get(self.index) else {
+            self.scroller.render(target, Point::new(0, 1), AwtrixFont::new(Rgb888::CSS_GRAY));
+            return;
+        };
+        if let Some(icon) = &widget.icon {
+            icons::draw_icon(target, icon, Point::zero(), self.color);
+        }
+        if self.value_since.is_none() {
+            self.scroller.render(target, Point::new(self.text_x(), 1), AwtrixFont::new(Rgb888::WHITE));
+        } else {
+            let width = crate::matrix::WIDTH - self.text_x() as u32;
+            let x = self.text_x() + (width.saturating_sub(AwtrixFont::measure_width(&self.value)) / 2) as i32;
+            Text::new(self.value.as_str(), Point::new(x, 1), AwtrixFont::new(self.color)).draw(target).ok();
+        }
+    }
+
+    pub fn handle_event(&mut self, event: MatrixEventDetails) {
+        if !event.is_single_press() || self.widgets.is_empty() {
+            return;
+        }
+        if event.has_left() {
+            self.show(self.index + self.widgets.len() - 1);
+        } else if event.has_right() {
+            self.show(self.index + 1);
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use alloc::{boxed::Box, string::String};
+use core::fmt::Write as _;
+
+use embassy_time::{Duration, Instant};
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*, text::Text};
+use rwtrix_core::{
+    frame::Redraw,
+    games::{Button, Game, GameKind},
+};
+
+use crate::{
+    games,
+    matrix::{
+        event::{MatrixEvent, MatrixEventDetails},
+        fonts::AwtrixFont,
+        pages::{PageTarget, Pages},
+    },
+};
+
+/// The menu switches between the name of the game and its high score.
+const MENU_SWITCH_MS: u64 = 2000;
+/// The menu stays at least this long after entering the page or finishing a round before the pages rotate on.
+const MIN_MENU_DURATION: Duration = Duration::from_secs(5);
+const OVER_DURATION: Duration = Duration::from_secs(3);
+/// Steps made up at once after the page was not updated for a while, the rest are skipped.
+const MAX_CATCH_UP_STEPS: u32 = 4;
+
+enum State {
+    Menu { since: Instant },
+    Playing { game: Game, next_step: Instant },
+    Over { score: u32, new_high_score: bool, since: Instant },
+}
+
+/// Left and Right pick a game, Select starts it. While playing the game gets every button, a long Select gives up.
+pub struct Games {
+    kind: GameKind,
+    state: State,
+    text: String,
+}
+
+impl Games {
+    pub fn new() -> Pages {
+        Pages::Games(Box::new(Games {
+            kind: GameKind::Snake,
+            state: State::Menu { since: Instant::now() },
+            text: String::with_capacity(12),
+        }))
+    }
+
+    /// A round left behind is given up, a finished one is not shown again.
+    pub fn enter(&mut self) {
+        self.state = State::Menu { since: Instant::now() };
+    }
+
+    pub fn can_rotate(&self) -> bool {
+        matches!(self.state, State::Menu { since } if since.elapsed() >= MIN_MENU_DURATION)
+    }
+
+    pub fn captures_buttons(&self) -> bool {
+        matches!(self.state, State::Playing { .. })
+    }
+
+    pub fn redraw(&self) -> Redraw {
+        match &self.state {
+            State::Playing { next_step, .. } => {
+                Redraw::AfterMs(next_step.checked_duration_since(Instant::now()).unwrap_or_default().as_millis().max(1))
+            }
+            State::Menu { since } => Redraw::AfterMs(MENU_SWITCH_MS - since.elapsed().as_millis() % MENU_SWITCH_MS),
+            State::Over { .. } => Redraw::IDLE,
+        }
+    }
+
+    pub fn update(&mut self) {
+        match &mut self.state {
+            State::Playing { game, next_step } => {
+                let now = Instant::now();
+                let mut steps = 0;
+                while *next_step <= now && !game.is_over() {
+                    if steps == MAX_CATCH_UP_STEPS {
+                        *next_step = now;
+                        break;
+                    }
+                    game.step();
+                    *next_step += Duration::from_millis(game.step_ms());
+                    steps += 1;
+                }
+                if game.is_over() {
+                    let score = game.score();
+                    let new_high_score = games::report_score(game.kind(), score);
+                    info!("{} over with {} points", game.kind().name(), score);
+                    self.state = State::Over { score, new_high_score, since: now };
+                }
+            }
+            State::Over { since, .. } if since.elapsed() >= OVER_DURATION => {
+                self.state = State::Menu { since: Instant::now() };
+            }
+            _ => {}
+        }
+
+        self.text.clear();
+        match &self.state {
+            State::Menu { since } if since.elapsed().as_millis() / MENU_SWITCH_MS % 2 == 1 => {
+                write!(&mut self.text, "hi {}", games::get_high_scores().get(self.kind)).ok();
+            }
+            State::Menu { .. } => self.text.push_str(self.kind.name()),
+            State::Over { score, .. } => {
+                write!(&mut self.text, "{}", score).ok();
+            }
+            State::Playing { .. } => {}
+        }
+    }
+
+    pub fn render<T: PageTarget>(&self, target: &mut T) {
+        target.clear(Rgb888::BLACK).ok();
+        let color = match &self.state {
+            State::Playing { game, .. } => {
+                game.draw(target);
+                return;
+            }
+            State::Over { new_high_score: true, .. } => Rgb888::YELLOW,
+            State::Over { .. } => Rgb888::RED,
+            State::Menu { .. } => Rgb888::WHITE,
+        };
+        let x = (crate::matrix::WIDTH.saturating_sub(AwtrixFont::measure_width(&self.text)) / 2) as i32;
+        Text::new(self.text.as_str(), Point::new(x, 1), AwtrixFont::new(color)).draw(target).ok();
+    }
+
+    fn start(&mut self) {
+        let game = Game::new(self.kind, Instant::now().as_ticks() as u32);
+        let next_step = Instant::now() + Duration::from_millis(game.step_ms());
+        self.state = State::Playing { game, next_step };
+    }
+
+    pub fn handle_event(&mut self, event: MatrixEventDetails) {
+        let button = |event: MatrixEvent| match event {
+            MatrixEvent::Left => Button::Left,
+            MatrixEvent::Select => Button::Select,
+            MatrixEvent::Right => Button::Right,
+        };
+        match &mut self.state {
+            State::Playing { game, .. } if event.is_press() => game.press(button(event.get_main())),
+            State::Playing { .. } if event.is_single_press() && event.is_long_press() && event.has_select() => {
+                self.state = State::Menu { since: Instant::now() };
+            }
+            State::Playing { game, .. } => {
+                // every button is up by the time the release arrives
+                for pressed in event.events.iter() {
+                    game.release(button(*pressed));
+                }
+            }
+            State::Menu { .. } if event.is_single_press() => {
+                let index = GameKind::ALL.iter().position(|kind| *kind == self.kind).unwrap_or_default();
+                let count = GameKind::ALL.len();
+                if event.has_left() {
+                    self.kind = GameKind::ALL[(index + count - 1) % count];
+                } else if event.has_right() {
+                    self.kind = GameKind::ALL[(index + 1) % count];
+                } else {
+                    self.start();
+                    return;
+                }
+                self.state = State::Menu { since: Instant::now() };
+            }
+            _ => {}
+        }
+    }
+}
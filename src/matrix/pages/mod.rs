@@ -1,14 +1,30 @@
 use alloc::{boxed::Box, string::String};
 
+use embassy_time::Duration;
 use embedded_graphics::{pixelcolor::Rgb888, prelude::DrawTarget};
+use rwtrix_core::frame::Redraw;
 
+mod alarm;
 mod battery;
+mod chart;
+mod countdown;
+mod custom;
 mod date;
+mod entities;
+mod games;
+mod stopwatch;
 mod time;
 mod timer;
 
+pub use alarm::Alarm;
 pub use battery::Battery;
+pub use chart::Chart;
+pub use countdown::Countdown;
+pub use custom::Custom;
 pub use date::Date;
+pub use entities::Entities;
+pub use games::Games;
+pub use stopwatch::Stopwatch;
 pub use time::Time;
 pub use timer::Timer;
 
@@ -23,6 +39,13 @@ pub enum Pages {
     Date(Box<date::Date>),
     Timer(Box<timer::Timer>),
     Battery(Box<battery::Battery>),
+    Alarm(Box<alarm::Alarm>),
+    Stopwatch(Box<stopwatch::Stopwatch>),
+    Countdown(Box<countdown::Countdown>),
+    Entities(Box<entities::Entities>),
+    Chart(Box<chart::Chart>),
+    Games(Box<games::Games>),
+    Custom(Box<custom::Custom>),
 }
 
 impl Pages {
@@ -32,6 +55,13 @@ impl Pages {
             Pages::Date(page) => page.update(),
             Pages::Timer(page) => page.update(),
             Pages::Battery(page) => page.update(),
+            Pages::Alarm(page) => page.update(),
+            Pages::Stopwatch(page) => page.update(),
+            Pages::Countdown(page) => page.update(),
+            Pages::Entities(page) => page.update(),
+            Pages::Chart(page) => page.update(),
+            Pages::Games(page) => page.update(),
+            Pages::Custom(page) => page.update(),
         }
     }
 
@@ -41,6 +71,96 @@ impl Pages {
             Pages::Date(page) => page.render(target),
             Pages::Timer(page) => page.render(target),
             Pages::Battery(page) => page.render(target),
+            Pages::Alarm(page) => page.render(target),
+            Pages::Stopwatch(page) => page.render(target),
+            Pages::Countdown(page) => page.render(target),
+            Pages::Entities(page) => page.render(target),
+            Pages::Chart(page) => page.render(target),
+            Pages::Games(page) => page.render(target),
+            Pages::Custom(page) => page.render(target),
+        }
+    }
+
+    /// When the page looks different next, asked after every update.
+    pub fn redraw(&self) -> Redraw {
+        match self {
+            Pages::Time(page) => page.redraw(),
+            Pages::Date(page) => page.redraw(),
+            Pages::Timer(page) => page.redraw(),
+            Pages::Battery(page) => page.redraw(),
+            Pages::Alarm(_) => Redraw::IDLE,
+            Pages::Stopwatch(page) => page.redraw(),
+            Pages::Countdown(page) => page.redraw(),
+            Pages::Entities(page) => page.redraw(),
+            Pages::Chart(page) => page.redraw(),
+            Pages::Games(page) => page.redraw(),
+            Pages::Custom(page) => page.redraw(),
+        }
+    }
+
+    /// Stable name of the page, used to look up per page settings.
+    pub fn name(&self) -> &str {
+        match self {
+            Pages::Time(_) => "time",
+            Pages::Date(_) => "date",
+            Pages::Timer(_) => "timer",
+            Pages::Battery(_) => "battery",
+            Pages::Alarm(_) => "alarm",
+            Pages::Stopwatch(_) => "stopwatch",
+            Pages::Countdown(_) => "countdown",
+            Pages::Entities(_) => "entities",
+            Pages::Chart(_) => "chart",
+            Pages::Games(_) => "games",
+            Pages::Custom(page) => page.name(),
+        }
+    }
+
+    pub fn duration(&self) -> Duration {
+        match self {
+            Pages::Custom(page) => page.duration(),
+            _ => Duration::from_secs(10),
+        }
+    }
+
+    /// Called when the page becomes the visible page.
+    pub fn enter(&mut self) {
+        match self {
+            Pages::Time(page) => page.enter(),
+            Pages::Date(page) => page.enter(),
+            Pages::Battery(page) => page.enter(),
+            Pages::Countdown(page) => page.enter(),
+            Pages::Entities(page) => page.enter(),
+            Pages::Chart(page) => page.enter(),
+            Pages::Games(page) => page.enter(),
+            Pages::Custom(page) => page.enter(),
+            _ => {}
+        }
+    }
+
+    /// Pages showing scrolling text hold the rotation until the text completed a full pass.
+    pub fn can_rotate(&self) -> bool {
+        match self {
+            Pages::Time(page) => page.can_rotate(),
+            Pages::Date(page) => page.can_rotate(),
+            Pages::Countdown(page) => page.can_rotate(),
+            Pages::Entities(page) => page.can_rotate(),
+            Pages::Chart(page) => page.can_rotate(),
+            Pages::Games(page) => page.can_rotate(),
+            Pages::Custom(page) => page.can_rotate(),
+            _ => true,
+        }
+    }
+
+    /// Pages using a long Select press themselves, it toggles the page rotation everywhere else.
+    pub fn handles_long_select(&self) -> bool {
+        matches!(self, Pages::Stopwatch(_))
+    }
+
+    /// The page gets every button event, presses included, instead of the page switching and the page menu.
+    pub fn captures_buttons(&self) -> bool {
+        match self {
+            Pages::Games(page) => page.captures_buttons(),
+            _ => false,
         }
     }
 
@@ -54,6 +174,13 @@ impl Pages {
             Pages::Date(page) => page.handle_event(event),
             Pages::Timer(page) => page.handle_event(event),
             Pages::Battery(page) => page.handle_event(event),
+            Pages::Alarm(page) => page.handle_event(event),
+            Pages::Stopwatch(page) => page.handle_event(event),
+            Pages::Countdown(page) => page.handle_event(event),
+            Pages::Entities(page) => page.handle_event(event),
+            Pages::Chart(page) => page.handle_event(event),
+            Pages::Games(page) => page.handle_event(event),
+            Pages::Custom(page) => page.handle_event(event),
         }
     }
 }
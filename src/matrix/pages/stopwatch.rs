@@ -0,0 +1,123 @@
+use alloc::{boxed::Box, string::String};
+use core::fmt::Write as _;
+
+use embassy_time::{Duration, Instant};
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*, text::Text};
+use rwtrix_core::{
+    frame::Redraw,
+    stopwatch::{self, Stopwatch as StopwatchState},
+};
+
+use crate::matrix::{
+    event::MatrixEventDetails,
+    fonts::AwtrixFont,
+    pages::{PageTarget, Pages},
+};
+
+/// The lap number is shown first when browsing, then the lap time.
+const LAP_NUMBER_DURATION: Duration = Duration::from_millis(800);
+/// Browsing goes back to the running time after this long without a button press.
+const BROWSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Select starts and stops, a long Select resets. Right records a lap while running, Left browses the laps from the
+/// most recent one, as does Right while stopped.
+pub struct Stopwatch {
+    stopwatch: StopwatchState,
+    /// Index of the lap being browsed and when it was picked.
+    browsing: Option<(usize, Instant)>,
+    text: String,
+    color: Rgb888,
+}
+
+impl Stopwatch {
+    pub fn new() -> Pages {
+        Pages::Stopwatch(Box::new(Stopwatch {
+            stopwatch: StopwatchState::new(),
+            browsing: None,
+            text: String::with_capacity(8),
+            color: Rgb888::WHITE,
+        }))
+    }
+
+    fn now_ms() -> u64 {
+        Instant::now().as_millis()
+    }
+
+    /// Tenths of a second tick while running or browsing, nothing moves otherwise.
+    pub fn redraw(&self) -> Redraw {
+        if self.stopwatch.is_running() || self.browsing.is_some() {
+            Redraw::Fps(10)
+        } else {
+            Redraw::IDLE
+        }
+    }
+
+    /// Time is derived from the clock on every update, so the stopwatch keeps running while another page is shown.
+    pub fn update(&mut self) {
+        self.text.clear();
+
+        if let Some((index, since)) = self.browsing {
+            let shown_for = since.elapsed();
+            if shown_for >= BROWSE_TIMEOUT || index >= self.stopwatch.laps().len() {
+                self.browsing = None;
+            } else if shown_for < LAP_NUMBER_DURATION {
+                write!(self.text, "LAP {}", self.stopwatch.lap_number(index)).ok();
+                self.color = Rgb888::YELLOW;
+                return;
+            } else {
+                stopwatch::write_elapsed(&mut self.text, self.stopwatch.laps()[index]);
+                self.color = Rgb888::CYAN;
+                return;
+            }
+        }
+
+        stopwatch::write_elapsed(&mut self.text, self.stopwatch.elapsed_ms(Self::now_ms()));
+        self.color = if self.stopwatch.is_running() { Rgb888::GREEN } else { Rgb888::WHITE };
+    }
+
+    pub fn render<T: PageTarget>(&self, target: &mut T) {
+        target.clear(Rgb888::BLACK).ok();
+        let x = (crate::matrix::WIDTH.saturating_sub(AwtrixFont::measure_width(&self.text)) / 2) as i32;
+        Text::new(self.text.as_str(), Point::new(x, 1), AwtrixFont::new(self.color)).draw(target).ok();
+    }
+
+    /// Moves through the laps, starting from the most recent one.
+    fn browse(&mut self, forward: bool) {
+        let count = self.stopwatch.laps().len();
+        if count == 0 {
+            return;
+        }
+        let index = match self.browsing {
+            None => count - 1,
+            Some((index, _)) if forward => (index + 1) % count,
+            Some((index, _)) => (index + count - 1) % count,
+        };
+        self.browsing = Some((index, Instant::now()));
+    }
+
+    pub fn handle_event(&mut self, event: MatrixEventDetails) {
+        if !event.is_single_press() {
+            return;
+        }
+        if event.has_select() {
+            if event.is_long_press() {
+                self.stopwatch.reset();
+                self.browsing = None;
+                crate::stopwatch::report_laps(&self.stopwatch);
+            } else {
+                self.stopwatch.toggle(Self::now_ms());
+            }
+        } else if event.has_right() {
+            if self.stopwatch.lap(Self::now_ms()).is_some() {
+                // flash the recorded lap
+                self.browsing = Some((self.stopwatch.laps().len() - 1, Instant::now()));
+                crate::stopwatch::report_laps(&self.stopwatch);
+            } else {
+                self.browse(true);
+            }
+        } else if event.has_left() {
+            self.browse(false);
+        }
+        self.update();
+    }
+}
@@ -8,84 +8,184 @@ use embedded_graphics::{
     primitives::{PrimitiveStyle, Rectangle},
     text::Text,
 };
+use rwtrix_core::{clock_face, frame::Redraw};
 
-use crate::matrix::{
-    fonts::AwtrixFont,
-    pages::{PageTarget, Pages},
+use crate::{
+    clock::{self, BlinkStyle, ClockFace, ClockSettings},
+    matrix::{
+        fonts::AwtrixFont,
+        pages::{PageTarget, Pages},
+        scroller::{ScrollMode, Scroller},
+    },
 };
 
+/// Width of the calendar icon plus the gap next to it.
+const CALENDAR_WIDTH: i32 = 10;
+
+fn rgb([r, g, b]: [u8; 3]) -> Rgb888 {
+    Rgb888::new(r, g, b)
+}
+
 pub struct Time {
     rtc: &'static esp_hal::rtc_cntl::Rtc<'static>,
+    settings: ClockSettings,
+    settings_version: u32,
+    current_hour: u8,
+    current_minute: u8,
     current_secs: u8,
+    /// Time until the RTC reaches the next full second.
+    ms_to_next_second: u64,
     current_time: String,
     current_day: String,
     current_day_of_week: u8,
     blink: bool,
+    /// Scrolls the time in words of the fuzzy face.
+    scroller: Scroller,
 }
 
 impl Time {
     pub fn new(rtc: &'static esp_hal::rtc_cntl::Rtc<'static>) -> Pages {
         Pages::Time(Box::new(Time {
             rtc,
+            settings: clock::get_settings(),
+            settings_version: clock::get_settings_version(),
+            current_hour: 0,
+            current_minute: 0,
             current_secs: 0,
+            ms_to_next_second: 0,
             current_time: String::from("00:00:00"),
             current_day: String::from("00"),
             current_day_of_week: 0,
             blink: false,
+            scroller: Scroller::new(crate::matrix::WIDTH, ScrollMode::Loop),
         }))
     }
 
+    pub fn enter(&mut self) {
+        self.scroller.restart();
+    }
+
+    /// The fuzzy face holds the rotation until the words scrolled by once.
+    pub fn can_rotate(&self) -> bool {
+        self.settings.face != ClockFace::Fuzzy || self.scroller.pass_completed()
+    }
+
+    /// Drawn right after every full second, the fuzzy face scrolls.
+    pub fn redraw(&self) -> Redraw {
+        match self.settings.face {
+            ClockFace::Fuzzy => self.scroller.redraw(),
+            _ => Redraw::AfterMs(self.ms_to_next_second),
+        }
+    }
+
     pub fn update(&mut self) {
+        if clock::get_settings_version() != self.settings_version {
+            self.settings_version = clock::get_settings_version();
+            self.settings = clock::get_settings();
+        }
+
         self.current_time.clear();
         self.current_day.clear();
         let now = self.rtc.current_time_us();
+        self.ms_to_next_second = (1_000_000 - now % 1_000_000) / 1000 + 1;
         let now = chrono::NaiveDateTime::from_timestamp_micros(now as i64).unwrap();
         let secs = now.time().second() as u8;
         if secs != self.current_secs {
             self.current_secs = secs;
             self.blink = !self.blink;
         }
-        write!(&mut self.current_time, "{}", now.time().format("%H:%M")).ok();
+        self.current_hour = now.hour() as u8;
+        self.current_minute = now.minute() as u8;
+        self.settings.write_time(&mut self.current_time, self.current_hour, self.current_minute, secs);
         write!(&mut self.current_day, "{}", now.date().format("%d")).ok();
-        self.current_day_of_week = now.date().weekday().number_from_monday() as u8 - 1;
+        self.current_day_of_week = self.settings.weekday_slot(now.date().weekday().num_days_from_monday() as u8);
+
+        if self.settings.face == ClockFace::Fuzzy {
+            self.scroller.set_text(&clock_face::fuzzy_time(self.current_hour, self.current_minute));
+            self.scroller.update();
+        }
+    }
+
+    /// The calendar icon makes room for the time when seconds are shown.
+    fn shows_calendar(&self) -> bool {
+        !self.settings.show_seconds
     }
 
     pub fn render<T: PageTarget>(&self, target: &mut T) {
         target.clear(Rgb888::BLACK).ok();
 
-        Rectangle::new(Point::new(0, 0), Size::new(9, 2))
-            .into_styled(PrimitiveStyle::with_fill(Rgb888::RED))
-            .draw(target)
-            .ok();
-        Rectangle::new(Point::new(0, 2), Size::new(9, 6))
-            .into_styled(PrimitiveStyle::with_fill(Rgb888::WHITE))
-            .draw(target)
-            .ok();
-
-        let day_style = AwtrixFont::new(Rgb888::BLACK);
-        Text::new(self.current_day.as_str(), Point::new(1, 2), day_style).draw(target).ok();
-        let time_style = AwtrixFont::new(Rgb888::YELLOW);
-        Text::new(self.current_time.as_str(), Point::new(12, 1), time_style).draw(target).ok();
+        let (hour, minute, second) = (self.current_hour, self.current_minute, self.current_secs);
+        match self.settings.face {
+            ClockFace::Classic => self.render_classic(target),
+            ClockFace::BigDigits => {
+                let colon_visible = self.settings.blink != BlinkStyle::Blink || self.blink;
+                clock_face::draw_big_digits(target, &self.settings, hour, minute, colon_visible);
+            }
+            ClockFace::Binary => {
+                clock_face::draw_binary(target, &self.settings, hour, minute, second, Rgb888::new(40, 40, 40));
+            }
+            ClockFace::Fuzzy => {
+                self.scroller.render(target, Point::new(0, 1), AwtrixFont::new(rgb(self.settings.digit_color)));
+            }
+        }
+    }
 
-        let blink_color = if self.blink { Rgb888::WHITE } else { Rgb888::CSS_GRAY };
+    fn render_classic<T: PageTarget>(&self, target: &mut T) {
+        let (left, width) = if self.shows_calendar() {
+            self.render_calendar(target);
+            (CALENDAR_WIDTH, crate::matrix::WIDTH as i32 - CALENDAR_WIDTH)
+        } else {
+            (0, crate::matrix::WIDTH as i32)
+        };
 
-        let mut blink_rect =
-            Rectangle::new(Point::new(20, 2), Size::new(1, 1)).into_styled(PrimitiveStyle::with_fill(blink_color));
+        let digit_color = rgb(self.settings.digit_color);
+        let time_width = AwtrixFont::measure_width(&self.current_time) as i32;
+        let time_x = left + (width - time_width).max(0) / 2;
+        Text::new(self.current_time.as_str(), Point::new(time_x, 1), AwtrixFont::new(digit_color)).draw(target).ok();
 
-        blink_rect.draw(target).ok();
-        let blink_rect = blink_rect.translate(Point::new(0, 2));
-        blink_rect.draw(target).ok();
+        // the colons are drawn over to make them tick
+        let colon_color = match self.settings.blink {
+            BlinkStyle::Dim if self.blink => Some(Rgb888::WHITE),
+            BlinkStyle::Dim => Some(Rgb888::CSS_GRAY),
+            BlinkStyle::Blink if self.blink => None,
+            BlinkStyle::Blink => Some(Rgb888::BLACK),
+            BlinkStyle::Solid => None,
+        };
+        if let Some(color) = colon_color {
+            for (index, _) in self.current_time.match_indices(':') {
+                let x = time_x + AwtrixFont::measure_width(&self.current_time[..index]) as i32;
+                for y in [2, 4] {
+                    Pixel(Point::new(x, y), color).draw(target).ok();
+                }
+            }
+        }
 
+        let weekday_color = rgb(self.settings.weekday_color);
+        let bar_left = if self.shows_calendar() { CALENDAR_WIDTH } else { 6 };
         for i in 0..7 {
-            let color = if i == self.current_day_of_week { Rgb888::WHITE } else { Rgb888::CSS_GRAY };
+            let color = if i == self.current_day_of_week { weekday_color } else { Rgb888::CSS_GRAY };
             let i = i as i32;
-            Rectangle::new(Point::new(10 + (2 * i + i), 7), Size::new(2, 1))
+            Rectangle::new(Point::new(bar_left + (2 * i + i), 7), Size::new(2, 1))
                 .into_styled(PrimitiveStyle::with_fill(color))
                 .draw(target)
                 .ok();
         }
     }
 
+    fn render_calendar<T: PageTarget>(&self, target: &mut T) {
+        Rectangle::new(Point::new(0, 0), Size::new(9, 2))
+            .into_styled(PrimitiveStyle::with_fill(rgb(self.settings.calendar_color)))
+            .draw(target)
+            .ok();
+        Rectangle::new(Point::new(0, 2), Size::new(9, 6))
+            .into_styled(PrimitiveStyle::with_fill(Rgb888::WHITE))
+            .draw(target)
+            .ok();
+
+        let day_style = AwtrixFont::new(Rgb888::BLACK);
+        Text::new(self.current_day.as_str(), Point::new(1, 2), day_style).draw(target).ok();
+    }
+
     pub fn handle_event(&mut self, _event: crate::matrix::event::MatrixEventDetails) {
         // No event handling needed for Time page
     }
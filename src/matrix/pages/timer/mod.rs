@@ -1,114 +1,123 @@
 use alloc::{boxed::Box, string::String};
 use core::fmt::Write as _;
 
-use embassy_time::Instant;
+use embassy_time::{Duration, Instant};
 use embedded_graphics::{
     pixelcolor::Rgb888,
     prelude::*,
     primitives::{PrimitiveStyle, Rectangle},
 };
+use rwtrix_core::frame::Redraw;
 
-use crate::matrix::{event::MatrixEventDetails, fonts::AwtrixFont, pages::Pages};
+use crate::{
+    matrix::{event::MatrixEventDetails, fonts::AwtrixFont, pages::Pages},
+    pomodoro::{self, Phase, PomodoroStatus},
+};
 
 static POMODOR_ICON: &[u8] = include_bytes!("./pomodoro.gif");
 
 pub struct Timer {
-    rtc: &'static esp_hal::rtc_cntl::Rtc<'static>,
     icon: tinygif::Gif<'static>,
     last_frame: usize,
     current_frame: usize,
     current_frame_draw: Instant,
     remaining_time_ms: u32,
 
-    timer_started_at: Option<chrono::NaiveDateTime>,
-    timer_duration: chrono::Duration,
+    status: PomodoroStatus,
 
     buf: String,
 }
 
 impl Timer {
-    pub fn new(rtc: &'static esp_hal::rtc_cntl::Rtc<'static>) -> Pages {
+    pub fn new() -> Pages {
         Pages::Timer(Box::new(Timer {
-            rtc,
             icon: tinygif::Gif::from_slice(POMODOR_ICON).unwrap(),
             current_frame: 0,
             last_frame: usize::MAX,
             current_frame_draw: Instant::now(),
             remaining_time_ms: 0,
-            timer_started_at: None,
-            timer_duration: chrono::Duration::minutes(15),
+            status: pomodoro::get_status(),
 
             buf: String::with_capacity(5),
         }))
     }
 
-    pub fn update(&mut self) {}
+    /// Drawn again when the next frame of the icon is due.
+    pub fn redraw(&self) -> Redraw {
+        let shown_for = self.current_frame_draw.elapsed().as_millis();
+        Redraw::AfterMs((self.remaining_time_ms as u64).saturating_sub(shown_for)).min(Redraw::IDLE)
+    }
+
+    pub fn update(&mut self) {
+        self.status = pomodoro::get_status();
 
-    pub fn render<T: super::PageTarget>(&mut self, target: &mut T) {
-        target.clear(Rgb888::BLACK).ok();
-        self.buf.clear();
-        //
-        //let font = embedded_graphics::mono_font::ascii::FONT_4X6;
-        //let style = embedded_graphics::mono_font::MonoTextStyle::new(&font, embedded_graphics::pixelcolor::Rgb888::RED);
-        //let style = super::font::mateine(embedded_graphics::pixelcolor::Rgb888::RED);
-        //let style = AwtrixFont::new(Rgb888::YELLOW);
-        //embedded_graphics::text::Text::new(self.current_time.as_str(), Point::new(3, 1), style).draw(target).ok();
-        let style = AwtrixFont::new(if self.timer_started_at.is_some() { Rgb888::YELLOW } else { Rgb888::WHITE });
-
-        for frame in self.icon.frames().skip(self.current_frame) {
-            //frame.bounding_box().into_styled(PrimitiveStyle::with_fill(Rgb888::BLACK)).draw(target).ok();
-            frame.draw(target).ok();
-            self.remaining_time_ms = frame.delay_centis as u32 * 10;
-            break;
-        }
         if self.current_frame != self.last_frame {
             self.last_frame = self.current_frame;
             self.current_frame_draw = Instant::now();
-        } else if Instant::now().checked_duration_since(self.current_frame_draw).unwrap_or_default()
-            > embassy_time::Duration::from_millis(self.remaining_time_ms as u64)
-        {
+        } else if self.current_frame_draw.elapsed() >= Duration::from_millis(self.remaining_time_ms as u64) {
             self.current_frame = (self.current_frame + 1) % self.icon.frames().count();
-            //self.last_frame = usize::MAX;
+            self.last_frame = self.current_frame;
+            self.current_frame_draw = Instant::now();
         }
+    }
 
-        if let Some(timer_started_at) = self.timer_started_at {
-            let now = self.rtc.current_time_us();
-            let now = chrono::NaiveDateTime::from_timestamp_micros(now as i64).unwrap();
-            let elapsed = now - timer_started_at;
-            let remaining = self.timer_duration - elapsed;
-            if remaining.num_seconds() >= 0 {
-                let minutes = remaining.num_hours();
-                let seconds = remaining.num_minutes() % 60;
-                write!(self.buf, "{:02}:{:02}", minutes, seconds).ok();
-            } else {
-                self.buf.push_str("00:00");
-            }
-        } else {
-            write!(self.buf, "{:02}:{:02}", self.timer_duration.num_hours(), self.timer_duration.num_minutes() % 60)
-                .ok();
+    fn phase_color(&self) -> Rgb888 {
+        if !self.status.running {
+            return Rgb888::WHITE;
+        }
+        match self.status.phase {
+            Phase::Work => Rgb888::RED,
+            Phase::ShortBreak => Rgb888::GREEN,
+            Phase::LongBreak => Rgb888::CYAN,
         }
+    }
 
-        embedded_graphics::text::Text::new(&self.buf.as_str(), Point::new(12, 1), style).draw(target).ok();
+    pub fn render<T: super::PageTarget>(&mut self, target: &mut T) {
+        target.clear(Rgb888::BLACK).ok();
+        self.buf.clear();
+        let color = self.phase_color();
+        let style = AwtrixFont::new(color);
+
+        if let Some(frame) = self.icon.frames().nth(self.current_frame) {
+            frame.draw(target).ok();
+            // frames without a delay get the common 20 ms minimum of GIF players
+            self.remaining_time_ms = (frame.delay_centis as u32 * 10).max(20);
+        }
+
+        let remaining = self.status.remaining_secs;
+        write!(self.buf, "{:02}:{:02}", remaining / 60, remaining % 60).ok();
+        embedded_graphics::text::Text::new(self.buf.as_str(), Point::new(12, 1), style).draw(target).ok();
+
+        // one dot per work session until the long break, filled for the ones already done
+        for session in 0..self.status.sessions_before_long_break {
+            let dot_color = if session < self.status.sessions_in_cycle { color } else { Rgb888::new(40, 40, 40) };
+            Rectangle::new(Point::new(12 + session as i32 * 3, 7), Size::new(2, 1))
+                .into_styled(PrimitiveStyle::with_fill(dot_color))
+                .draw(target)
+                .ok();
+        }
     }
 
     pub fn handle_event(&mut self, event: MatrixEventDetails) {
         info!("Timer page received event: {:?}", event);
-        if event.is_single_press() {
+        if !event.is_single_press() {
+            return;
+        }
+        if event.has_select() {
+            pomodoro::toggle();
+        } else if self.status.stopped {
+            let minutes = pomodoro::get_settings().work_secs / 60;
             if event.has_left() {
-                self.timer_duration -= chrono::Duration::minutes(1);
+                pomodoro::set_phase_minutes(Phase::Work, minutes.saturating_sub(1));
             }
             if event.has_right() {
-                self.timer_duration += chrono::Duration::minutes(1);
-            }
-            if event.has_select() {
-                if self.timer_started_at.is_none() {
-                    let now = self.rtc.current_time_us();
-                    let now = chrono::NaiveDateTime::from_timestamp_micros(now as i64).unwrap();
-                    self.timer_started_at = Some(now);
-                } else {
-                    self.timer_started_at = None;
-                }
+                pomodoro::set_phase_minutes(Phase::Work, minutes + 1);
             }
+        } else if event.has_left() {
+            pomodoro::stop();
+        } else if event.has_right() {
+            pomodoro::skip();
         }
+        self.update();
     }
 }
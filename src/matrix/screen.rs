@@ -0,0 +1,47 @@
+use alloc::vec::Vec;
+use core::{
+    cell::RefCell,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use embassy_sync::{
+    blocking_mutex::{raw::CriticalSectionRawMutex, Mutex},
+    signal::Signal,
+};
+use embassy_time::{with_timeout, Duration};
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*};
+
+use crate::matrix::{HEIGHT, WIDTH};
+
+static CAPTURE_REQUESTED: AtomicBool = AtomicBool::new(false);
+static CAPTURE: Mutex<CriticalSectionRawMutex, RefCell<Vec<Rgb888>>> = Mutex::new(RefCell::new(Vec::new()));
+static CAPTURE_READY: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+
+/// Asks the matrix task for a copy of the next frame, pixels are returned row by row.
+pub async fn capture() -> Option<Vec<Rgb888>> {
+    CAPTURE_READY.reset();
+    CAPTURE_REQUESTED.store(true, Ordering::Relaxed);
+    with_timeout(Duration::from_secs(1), CAPTURE_READY.wait()).await.ok()?;
+    Some(CAPTURE.lock(|capture| core::mem::take(&mut *capture.borrow_mut())))
+}
+
+/// Lets the matrix task draw a frame right away instead of at its next redraw.
+pub(super) fn is_capture_requested() -> bool {
+    CAPTURE_REQUESTED.load(Ordering::Relaxed)
+}
+
+/// Called by the matrix task once a frame is fully drawn.
+pub(super) fn capture_if_requested(pixel: impl Fn(Point) -> Option<Rgb888>) {
+    if !CAPTURE_REQUESTED.swap(false, Ordering::Relaxed) {
+        return;
+    }
+
+    let mut frame = Vec::with_capacity((WIDTH * HEIGHT) as usize);
+    for y in 0..HEIGHT as i32 {
+        for x in 0..WIDTH as i32 {
+            frame.push(pixel(Point::new(x, y)).unwrap_or(Rgb888::BLACK));
+        }
+    }
+    CAPTURE.lock(|capture| *capture.borrow_mut() = frame);
+    CAPTURE_READY.signal(());
+}
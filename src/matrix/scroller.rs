@@ -0,0 +1,138 @@
+use alloc::string::String;
+
+use embassy_time::{Duration, Instant};
+use embedded_graphics::{prelude::*, primitives::Rectangle, text::Text};
+use rwtrix_core::frame::Redraw;
+
+use crate::matrix::{fonts::AwtrixFont, pages::PageTarget};
+
+pub const DEFAULT_SCROLL_SPEED: u32 = 20;
+const SCROLL_START_DELAY: Duration = Duration::from_millis(1000);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScrollMode {
+    /// Scrolls until the end of the text is visible and holds it there.
+    Once,
+    /// Scrolls the text in from the right edge and out to the left, forever.
+    Loop,
+}
+
+/// Single line of text that scrolls horizontally when it does not fit in `width` pixels.
+pub struct Scroller {
+    text: String,
+    text_width: i32,
+    width: i32,
+    mode: ScrollMode,
+    speed: u32,
+    started_at: Instant,
+    offset: i32,
+    passes: u32,
+}
+
+impl Scroller {
+    pub fn new(width: u32, mode: ScrollMode) -> Self {
+        Scroller {
+            text: String::new(),
+            text_width: 0,
+            width: width as i32,
+            mode,
+            speed: DEFAULT_SCROLL_SPEED,
+            started_at: Instant::now(),
+            offset: 0,
+            passes: 0,
+        }
+    }
+
+    /// Scrolling speed in pixels per second.
+    pub fn with_speed(mut self, speed: u32) -> Self {
+        self.speed = speed.max(1);
+        self
+    }
+
+    pub fn set_width(&mut self, width: u32) {
+        if self.width != width as i32 {
+            self.width = width as i32;
+            self.restart();
+        }
+    }
+
+    /// Replaces the text, scrolling only restarts if the text actually changed.
+    pub fn set_text(&mut self, text: &str) {
+        if self.text != text {
+            self.text.clear();
+            self.text.push_str(text);
+            self.text_width = AwtrixFont::measure_width(text) as i32;
+            self.restart();
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn restart(&mut self) {
+        self.started_at = Instant::now();
+        self.offset = 0;
+        self.passes = 0;
+        self.update();
+    }
+
+    pub fn is_scrolling(&self) -> bool {
+        self.text_width > self.width
+    }
+
+    /// Number of completed passes, text that fits is considered to complete a pass immediately.
+    pub fn passes(&self) -> u32 {
+        if self.is_scrolling() {
+            self.passes
+        } else {
+            1
+        }
+    }
+
+    pub fn pass_completed(&self) -> bool {
+        self.passes() > 0
+    }
+
+    /// Animated while the text moves, idle once it fits or holds at its end.
+    pub fn redraw(&self) -> Redraw {
+        let moving =
+            self.is_scrolling() && (self.mode == ScrollMode::Loop || self.offset < self.text_width - self.width);
+        if moving {
+            Redraw::ANIMATION
+        } else {
+            Redraw::IDLE
+        }
+    }
+
+    pub fn update(&mut self) {
+        if !self.is_scrolling() {
+            self.offset = 0;
+            return;
+        }
+
+        let elapsed = Instant::now().checked_duration_since(self.started_at).unwrap_or_default();
+        match self.mode {
+            ScrollMode::Once => {
+                let elapsed = elapsed.checked_sub(SCROLL_START_DELAY).unwrap_or_default();
+                let pixels = (elapsed.as_millis() * self.speed as u64 / 1000) as i32;
+                let max_offset = self.text_width - self.width;
+                self.offset = pixels.min(max_offset);
+                self.passes = if self.offset >= max_offset { 1 } else { 0 };
+            }
+            ScrollMode::Loop => {
+                let pixels = (elapsed.as_millis() * self.speed as u64 / 1000) as i32;
+                let period = self.text_width + self.width;
+                self.offset = (pixels % period) - self.width;
+                self.passes = (pixels / period) as u32;
+            }
+        }
+    }
+
+    pub fn render<T: PageTarget>(&self, target: &mut T, position: Point, style: AwtrixFont) {
+        let area =
+            Rectangle::new(Point::new(position.x, 0), Size::new(self.width as u32, target.bounding_box().size.height));
+        let mut target = target.clipped(&area);
+        Text::new(self.text.as_str(), position - Point::new(self.offset, 0), style).draw(&mut target).ok();
+    }
+}
@@ -9,6 +9,7 @@ use embedded_graphics::{
     text::Text,
 };
 use num_traits::float::Float;
+use rwtrix_core::frame::Redraw;
 
 use super::color;
 use crate::{
@@ -17,6 +18,7 @@ use crate::{
         fonts::AwtrixFont,
         pages::{PageTarget, Pages},
     },
+    state::{IndicatorMode, INDICATOR_COUNT},
     wifi::WiFiState,
 };
 
@@ -24,7 +26,10 @@ pub struct Status {
     wifi_state: WiFiState,
     ha_state: HaState,
     transition_state: bool,
-    indicators_state: [bool; 3],
+    /// Current color of every indicator that is on, blinking and fading already applied.
+    indicator_colors: [Option<Rgb888>; INDICATOR_COUNT],
+    /// An indicator that is on blinks or fades.
+    indicators_animated: bool,
 }
 
 impl Status {
@@ -33,7 +38,8 @@ impl Status {
             wifi_state: WiFiState::Disconnected,
             ha_state: HaState::Disconnected,
             transition_state: false,
-            indicators_state: [false; 3],
+            indicator_colors: [None; INDICATOR_COUNT],
+            indicators_animated: false,
         }
     }
 
@@ -41,7 +47,22 @@ impl Status {
         self.wifi_state = crate::wifi::get_wifi_state();
         self.ha_state = crate::ha::get_ha_state();
         self.transition_state = crate::state::get_transition_state();
-        self.indicators_state = crate::state::get_indicators_state();
+        let now_ms = embassy_time::Instant::now().as_millis();
+        let indicators = crate::state::get_indicators_state();
+        self.indicators_animated =
+            indicators.iter().any(|indicator| indicator.on && indicator.mode != IndicatorMode::Solid);
+        self.indicator_colors = indicators.map(|indicator| {
+            let [r, g, b] = indicator.color_at(now_ms);
+            indicator.on.then_some(Rgb888::new(r, g, b))
+        });
+    }
+
+    pub fn redraw(&self) -> Redraw {
+        if self.indicators_animated {
+            Redraw::ANIMATION
+        } else {
+            Redraw::IDLE
+        }
     }
 
     pub fn render<T: PageTarget>(&self, target: &mut T) {
@@ -58,8 +79,8 @@ impl Status {
             darken,
         );
 
-        if self.indicators_state[0] {
-            wifi_color = Rgb888::WHITE;
+        if let Some(color) = self.indicator_colors[0] {
+            wifi_color = color;
         }
 
         target
@@ -70,14 +91,13 @@ impl Status {
             ])
             .ok();
 
-        let mut indicator1_color = Rgb888::BLACK;
-
-        if self.indicators_state[1] {
-            indicator1_color = Rgb888::WHITE;
+        // the page shows through while the indicator is off
+        if let Some(indicator1_color) = self.indicator_colors[1] {
+            target
+                .draw_iter([Pixel(Point::new(31, 3), indicator1_color), Pixel(Point::new(31, 4), indicator1_color)])
+                .ok();
         }
 
-        target.draw_iter([Pixel(Point::new(31, 3), indicator1_color), Pixel(Point::new(31, 4), indicator1_color)]).ok();
-
         let mut ha_color = color::darken(
             match self.ha_state {
                 HaState::Disconnected => Rgb888::RED,
@@ -89,8 +109,8 @@ impl Status {
             darken,
         );
 
-        if self.indicators_state[2] {
-            ha_color = Rgb888::WHITE;
+        if let Some(color) = self.indicator_colors[2] {
+            ha_color = color;
         }
 
         target
@@ -0,0 +1,202 @@
+use core::convert::Infallible;
+
+use embassy_time::{Duration, Instant};
+use embedded_graphics::{pixelcolor::Rgb888, prelude::*, primitives::Rectangle};
+use rwtrix_core::layers::{LayerId, LayerStack};
+use serde::{Deserialize, Serialize};
+
+use crate::matrix::{
+    pages::{PageTarget, Pages},
+    status::Status,
+};
+
+pub const DEFAULT_TRANSITION_DURATION_MS: u32 = 800;
+pub const MIN_TRANSITION_DURATION_MS: u32 = 100;
+pub const MAX_TRANSITION_DURATION_MS: u32 = 5000;
+const FRAME_TIME_MS: u64 = 25;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TransitionEffect {
+    /// Both pages move to the left, the new page comes in from the right edge.
+    #[default]
+    SlideLeft,
+    SlideRight,
+    SlideUp,
+    SlideDown,
+    /// Fades the current page out and the new page in, the status pixels stay as they are.
+    Fade,
+    /// Uncovers the new page from left to right.
+    Wipe,
+    /// Replaces the current page pixel by pixel in a pseudo random order.
+    Dissolve,
+    /// Picks one of the other effects for every transition.
+    Random,
+}
+
+impl TransitionEffect {
+    pub const ALL: [TransitionEffect; 8] = [
+        TransitionEffect::SlideLeft,
+        TransitionEffect::SlideRight,
+        TransitionEffect::SlideUp,
+        TransitionEffect::SlideDown,
+        TransitionEffect::Fade,
+        TransitionEffect::Wipe,
+        TransitionEffect::Dissolve,
+        TransitionEffect::Random,
+    ];
+
+    /// Names in the same order as [`TransitionEffect::ALL`], also used as the Home Assistant select options.
+    pub const NAMES: [&'static str; 8] =
+        ["slide_left", "slide_right", "slide_up", "slide_down", "fade", "wipe", "dissolve", "random"];
+
+    pub fn from_index(index: usize) -> Option<Self> {
+        Self::ALL.get(index).copied()
+    }
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn name(self) -> &'static str {
+        Self::NAMES[self.index()]
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::NAMES.iter().position(|n| *n == name).and_then(Self::from_index)
+    }
+
+    /// Mirrored effect, used when going back to the previous page.
+    pub fn reversed(self) -> Self {
+        match self {
+            TransitionEffect::SlideLeft => TransitionEffect::SlideRight,
+            TransitionEffect::SlideRight => TransitionEffect::SlideLeft,
+            TransitionEffect::SlideUp => TransitionEffect::SlideDown,
+            TransitionEffect::SlideDown => TransitionEffect::SlideUp,
+            effect => effect,
+        }
+    }
+
+    fn resolve(self) -> Self {
+        match self {
+            TransitionEffect::Random => {
+                let concrete = Self::ALL.len() - 1;
+                Self::ALL[(Instant::now().as_ticks() % concrete as u64) as usize]
+            }
+            effect => effect,
+        }
+    }
+}
+
+/// Animates the change from `current_page` to `new_page`.
+///
+/// Every frame is drawn into the page and status layers of `layers`, `present` is called after every frame and is
+/// responsible for composing and flushing it and for pacing the animation.
+pub fn run<F>(
+    layers: &mut LayerStack,
+    effect: TransitionEffect,
+    duration: Duration,
+    current_page: &mut Pages,
+    new_page: &mut Pages,
+    status: &Status,
+    mut present: F,
+) where
+    F: FnMut(&LayerStack),
+{
+    let effect = effect.resolve();
+    let frames = (duration.as_millis() / FRAME_TIME_MS).max(1) as i32;
+
+    for frame in 1..=frames {
+        layers.reset();
+        let target = layers.layer_mut(LayerId::Page);
+        let size = target.bounding_box().size;
+        let (width, height) = (size.width as i32, size.height as i32);
+        match effect {
+            TransitionEffect::SlideLeft | TransitionEffect::Random => {
+                let shift = width * frame / frames;
+                render_offset(target, current_page, Point::new(-shift, 0));
+                render_offset(target, new_page, Point::new(width - shift, 0));
+            }
+            TransitionEffect::SlideRight => {
+                let shift = width * frame / frames;
+                render_offset(target, current_page, Point::new(shift, 0));
+                render_offset(target, new_page, Point::new(shift - width, 0));
+            }
+            TransitionEffect::SlideUp => {
+                let shift = height * frame / frames;
+                render_offset(target, current_page, Point::new(0, -shift));
+                render_offset(target, new_page, Point::new(0, height - shift));
+            }
+            TransitionEffect::SlideDown => {
+                let shift = height * frame / frames;
+                render_offset(target, current_page, Point::new(0, shift));
+                render_offset(target, new_page, Point::new(0, shift - height));
+            }
+            TransitionEffect::Fade => {
+                let opacity = if frame * 2 <= frames {
+                    current_page.render(target);
+                    255 - 255 * frame * 2 / frames
+                } else {
+                    new_page.render(target);
+                    255 * (frame * 2 - frames) / frames
+                };
+                target.set_opacity(opacity.clamp(0, 255) as u8);
+            }
+            TransitionEffect::Wipe => {
+                current_page.render(target);
+                let area = Rectangle::new(Point::zero(), Size::new((width * frame / frames) as u32, size.height));
+                new_page.render(&mut target.clipped(&area));
+            }
+            TransitionEffect::Dissolve => {
+                current_page.render(target);
+                let pixels = size.width * size.height;
+                let revealed = pixels * frame as u32 / frames as u32;
+                new_page.render(&mut DissolveTarget { target, pixels, revealed });
+            }
+        }
+        status.render(layers.layer_mut(LayerId::Status));
+        present(layers);
+    }
+}
+
+/// Renders the page moved by `offset`, clipped to its own area so it does not paint over the other page.
+fn render_offset<D: PageTarget>(target: &mut D, page: &mut Pages, offset: Point) {
+    let size = target.bounding_box().size;
+    let mut translated = target.translated(offset);
+    let mut clipped = translated.clipped(&Rectangle::new(Point::zero(), size));
+    page.render(&mut clipped);
+}
+
+/// Only lets through the pixels whose pseudo random rank is below `revealed`.
+struct DissolveTarget<'a, D> {
+    target: &'a mut D,
+    pixels: u32,
+    revealed: u32,
+}
+
+/// Maps every pixel index to a unique rank, 97 is coprime with the pixel count so no rank repeats.
+fn dissolve_rank(index: u32, pixels: u32) -> u32 {
+    (index * 97 + 31) % pixels
+}
+
+impl<D: PageTarget> Dimensions for DissolveTarget<'_, D> {
+    fn bounding_box(&self) -> Rectangle {
+        self.target.bounding_box()
+    }
+}
+
+impl<D: PageTarget> DrawTarget for DissolveTarget<'_, D> {
+    type Color = Rgb888;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let bounding_box = self.bounding_box();
+        let (pixel_count, revealed) = (self.pixels, self.revealed);
+        self.target.draw_iter(pixels.into_iter().filter(|Pixel(point, _)| {
+            bounding_box.contains(*point)
+                && dissolve_rank(point.y as u32 * bounding_box.size.width + point.x as u32, pixel_count) < revealed
+        }))
+    }
+}
@@ -0,0 +1,51 @@
+use core::{
+    cell::RefCell,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use embassy_sync::{
+    blocking_mutex::{raw::CriticalSectionRawMutex, Mutex},
+    signal::Signal,
+};
+pub use rwtrix_core::night::{
+    parse_location, parse_pages, parse_schedule, sun_times, to_red, NightAction, NightDetector, NightSettings,
+    NightWindow, MAX_WAKE_SECS,
+};
+
+static NIGHT_SETTINGS: Mutex<CriticalSectionRawMutex, RefCell<Option<NightSettings>>> = Mutex::new(RefCell::new(None));
+static NIGHT_SETTINGS_VERSION: AtomicU32 = AtomicU32::new(0);
+static NIGHT_SETTINGS_CHANGED: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+
+pub fn get_settings() -> NightSettings {
+    NIGHT_SETTINGS.lock(|settings| settings.borrow().clone()).unwrap_or_default()
+}
+
+pub fn modify_settings(f: impl FnOnce(&mut NightSettings)) {
+    let mut settings = get_settings();
+    f(&mut settings);
+    if settings != get_settings() {
+        NIGHT_SETTINGS.lock(|stored| *stored.borrow_mut() = Some(settings));
+        NIGHT_SETTINGS_VERSION.fetch_add(1, Ordering::Relaxed);
+        NIGHT_SETTINGS_CHANGED.signal(());
+    }
+}
+
+/// Changes every time the settings are modified, used by the matrix task to apply them live.
+pub fn get_settings_version() -> u32 {
+    NIGHT_SETTINGS_VERSION.load(Ordering::Relaxed)
+}
+
+#[embassy_executor::task]
+pub async fn night_task(storage: crate::storage::Storage) {
+    let settings = storage.read::<NightSettings>(&crate::storage::Key::NightSettings).await.unwrap_or_default();
+    info!("Loaded night settings: {:?}", settings);
+    NIGHT_SETTINGS.lock(|stored| *stored.borrow_mut() = Some(settings));
+    NIGHT_SETTINGS_VERSION.fetch_add(1, Ordering::Relaxed);
+
+    loop {
+        NIGHT_SETTINGS_CHANGED.wait().await;
+        let settings = get_settings();
+        storage.save(&crate::storage::Key::NightSettings, &settings).await.expect("failed saving night settings");
+        info!("Night settings saved: {:?}", settings);
+    }
+}
@@ -0,0 +1,109 @@
+use alloc::{string::String, vec::Vec};
+use core::{
+    cell::RefCell,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use embassy_sync::blocking_mutex::{raw::CriticalSectionRawMutex, Mutex};
+use serde::Deserialize;
+
+pub const MAX_NOTIFICATIONS: usize = 8;
+pub const DEFAULT_NOTIFICATION_DURATION_SECS: u16 = 5;
+
+/// Pending notifications, kept sorted by descending priority and in arrival order within a priority.
+static NOTIFICATIONS: Mutex<CriticalSectionRawMutex, RefCell<Vec<Notification>>> = Mutex::new(RefCell::new(Vec::new()));
+static DISMISS_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    pub text: String,
+    pub icon: Option<String>,
+    pub color: [u8; 3],
+    pub duration: u16,
+    pub hold: bool,
+    pub priority: u8,
+}
+
+#[derive(Debug)]
+pub enum NotificationError {
+    InvalidJson(serde_json_core::de::Error),
+    EmptyText,
+    QueueFull,
+}
+
+#[derive(Deserialize)]
+struct NotificationCommand<'a> {
+    text: &'a str,
+    icon: Option<&'a str>,
+    color: Option<[u8; 3]>,
+    duration: Option<u16>,
+    hold: Option<bool>,
+    priority: Option<u8>,
+}
+
+pub fn handle_command(payload: &str) -> Result<(), NotificationError> {
+    let (command, _) =
+        serde_json_core::from_str::<NotificationCommand>(payload).map_err(NotificationError::InvalidJson)?;
+    if command.text.is_empty() {
+        return Err(NotificationError::EmptyText);
+    }
+
+    push(Notification {
+        text: command.text.into(),
+        icon: command.icon.map(Into::into),
+        color: command.color.unwrap_or([255, 255, 255]),
+        duration: command.duration.unwrap_or(DEFAULT_NOTIFICATION_DURATION_SECS),
+        hold: command.hold.unwrap_or(false),
+        priority: command.priority.unwrap_or(0),
+    })
+}
+
+pub fn push(notification: Notification) -> Result<(), NotificationError> {
+    NOTIFICATIONS.lock(|notifications| {
+        let mut notifications = notifications.borrow_mut();
+        if notifications.len() >= MAX_NOTIFICATIONS {
+            return Err(NotificationError::QueueFull);
+        }
+        let position = notifications
+            .iter()
+            .position(|queued| queued.priority < notification.priority)
+            .unwrap_or(notifications.len());
+        notifications.insert(position, notification);
+        Ok(())
+    })
+}
+
+/// Puts a preempted notification back in front of the other notifications of the same priority.
+pub fn requeue(notification: Notification) {
+    NOTIFICATIONS.lock(|notifications| {
+        let mut notifications = notifications.borrow_mut();
+        let position = notifications
+            .iter()
+            .position(|queued| queued.priority <= notification.priority)
+            .unwrap_or(notifications.len());
+        notifications.insert(position, notification);
+    });
+}
+
+pub fn pop_next() -> Option<Notification> {
+    NOTIFICATIONS.lock(|notifications| {
+        let mut notifications = notifications.borrow_mut();
+        if notifications.is_empty() {
+            None
+        } else {
+            Some(notifications.remove(0))
+        }
+    })
+}
+
+pub fn peek_next_priority() -> Option<u8> {
+    NOTIFICATIONS.lock(|notifications| notifications.borrow().first().map(|notification| notification.priority))
+}
+
+pub fn request_dismiss() {
+    DISMISS_REQUESTED.store(true, Ordering::Relaxed);
+}
+
+pub fn take_dismiss_request() -> bool {
+    DISMISS_REQUESTED.swap(false, Ordering::Relaxed)
+}
@@ -6,6 +6,9 @@ use crate::udp::UdpBuffers;
 
 mod sntpc;
 
+/// Zone of the local wall time the RTC keeps.
+pub const TIMEZONE: chrono_tz::Tz = chrono_tz::Europe::Warsaw;
+
 static NTP_SYNC: Signal<CriticalSectionRawMutex, chrono::NaiveDateTime> = Signal::new();
 
 async fn ntp_request(stack: embassy_net::Stack<'static>) -> Result<chrono::NaiveDateTime, ()> {
@@ -40,7 +43,7 @@ async fn ntp_request(stack: embassy_net::Stack<'static>) -> Result<chrono::Naive
     match result {
         Ok(packet) => match packet.to_datetime() {
             Some(datetime) => {
-                let datetime = datetime.with_timezone(&chrono_tz::Europe::Warsaw);
+                let datetime = datetime.with_timezone(&TIMEZONE);
                 // info!("NTP time received: {:?}", defmt::Debug2Format(&datetime));
 
                 return Ok(datetime.naive_local());
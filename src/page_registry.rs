@@ -0,0 +1,227 @@
+use alloc::{string::String, vec::Vec};
+use core::{
+    cell::RefCell,
+    fmt::Write as _,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use embassy_sync::{
+    blocking_mutex::{raw::CriticalSectionRawMutex, Mutex},
+    signal::Signal,
+};
+use embassy_time::Duration;
+use serde::{Deserialize, Serialize};
+
+use crate::custom_apps;
+
+/// Pages compiled into the firmware, in their default order.
+pub const BUILTIN_PAGES: [&str; 10] =
+    ["time", "date", "timer", "battery", "alarm", "stopwatch", "countdown", "entities", "chart", "games"];
+pub const MIN_PAGE_DURATION_SECS: u16 = 2;
+pub const MAX_PAGE_DURATION_SECS: u16 = 3600;
+pub const DEFAULT_PAGE_DURATION_SECS: u16 = 10;
+
+/// Stored page order, pages that are not listed yet (like newly installed custom apps) are shown after them.
+static PAGE_REGISTRY: Mutex<CriticalSectionRawMutex, RefCell<Vec<PageEntry>>> = Mutex::new(RefCell::new(Vec::new()));
+static PAGE_REGISTRY_VERSION: AtomicU32 = AtomicU32::new(0);
+static PAGE_REGISTRY_CHANGED: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+static PAGE_REGISTRY_EXTERNAL_CHANGED: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+static CURRENT_PAGE: Mutex<CriticalSectionRawMutex, RefCell<String>> = Mutex::new(RefCell::new(String::new()));
+static CURRENT_PAGE_CHANGED: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+/// Page to jump to, picked up by the matrix task on its next iteration.
+static REQUESTED_PAGE: Mutex<CriticalSectionRawMutex, RefCell<Option<String>>> = Mutex::new(RefCell::new(None));
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PageEntry {
+    pub name: String,
+    pub enabled: bool,
+    /// Display duration in seconds, `None` keeps the page default.
+    pub duration: Option<u16>,
+}
+
+impl PageEntry {
+    fn new(name: &str) -> Self {
+        PageEntry { name: name.into(), enabled: true, duration: None }
+    }
+}
+
+#[derive(Debug)]
+pub enum PageRegistryError {
+    InvalidJson(serde_json_core::de::Error),
+    UnknownPage,
+}
+
+/// Page settings command as pushed over MQTT, every field besides `name` is optional and a `duration` of 0 goes
+/// back to the page default.
+#[derive(Deserialize)]
+struct PageCommand<'a> {
+    name: &'a str,
+    enabled: Option<bool>,
+    duration: Option<u16>,
+    position: Option<usize>,
+}
+
+pub fn handle_command(payload: &str) -> Result<(), PageRegistryError> {
+    let (command, _) = serde_json_core::from_str::<PageCommand>(payload).map_err(PageRegistryError::InvalidJson)?;
+
+    let mut entries = get_entries();
+    let index = entries.iter().position(|entry| entry.name == command.name).ok_or(PageRegistryError::UnknownPage)?;
+    let entry = &mut entries[index];
+    if let Some(enabled) = command.enabled {
+        entry.enabled = enabled;
+    }
+    if let Some(duration) = command.duration {
+        entry.duration = match duration {
+            0 => None,
+            duration => Some(duration.clamp(MIN_PAGE_DURATION_SECS, MAX_PAGE_DURATION_SECS)),
+        };
+    }
+    if let Some(position) = command.position {
+        let entry = entries.remove(index);
+        entries.insert(position.min(entries.len()), entry);
+    }
+
+    set_entries(entries);
+    Ok(())
+}
+
+/// Custom apps can not take these names, the built-in page would always be shown in their place.
+pub fn is_builtin(name: &str) -> bool {
+    BUILTIN_PAGES.contains(&name)
+}
+
+/// Every known page in display order, including the disabled ones.
+pub fn get_entries() -> Vec<PageEntry> {
+    let custom_app_names: Vec<String> = custom_apps::get_custom_apps().into_iter().map(|app| app.name).collect();
+    let is_known = |name: &str| is_builtin(name) || custom_app_names.iter().any(|custom| custom == name);
+
+    let mut entries: Vec<PageEntry> = PAGE_REGISTRY
+        .lock(|registry| registry.borrow().iter().filter(|entry| is_known(&entry.name)).cloned().collect());
+    for name in BUILTIN_PAGES.iter().copied().chain(custom_app_names.iter().map(String::as_str)) {
+        if !entries.iter().any(|entry| entry.name == name) {
+            entries.push(PageEntry::new(name));
+        }
+    }
+    entries
+}
+
+pub fn set_entries(entries: Vec<PageEntry>) {
+    PAGE_REGISTRY.lock(|registry| *registry.borrow_mut() = entries);
+    PAGE_REGISTRY_VERSION.fetch_add(1, Ordering::Relaxed);
+    PAGE_REGISTRY_CHANGED.signal(());
+    PAGE_REGISTRY_EXTERNAL_CHANGED.signal(());
+}
+
+pub fn set_page_enabled(name: &str, enabled: bool) -> Result<(), PageRegistryError> {
+    let mut entries = get_entries();
+    let entry = entries.iter_mut().find(|entry| entry.name == name).ok_or(PageRegistryError::UnknownPage)?;
+    if entry.enabled != enabled {
+        entry.enabled = enabled;
+        set_entries(entries);
+    }
+    Ok(())
+}
+
+pub fn is_page_enabled(name: &str) -> bool {
+    get_entries().iter().any(|entry| entry.name == name && entry.enabled)
+}
+
+/// Names of the pages to rotate through, never empty so there is always something to show.
+pub fn get_enabled_pages() -> Vec<String> {
+    let enabled: Vec<String> =
+        get_entries().into_iter().filter(|entry| entry.enabled).map(|entry| entry.name).collect();
+    if enabled.is_empty() {
+        alloc::vec![BUILTIN_PAGES[0].into()]
+    } else {
+        enabled
+    }
+}
+
+pub fn get_page_duration(name: &str) -> Option<Duration> {
+    PAGE_REGISTRY.lock(|registry| {
+        registry
+            .borrow()
+            .iter()
+            .find(|entry| entry.name == name)
+            .and_then(|entry| entry.duration)
+            .map(|duration| Duration::from_secs(duration as u64))
+    })
+}
+
+/// Page order formatted for Home Assistant, disabled pages are prefixed with `!` and custom durations follow a `:`.
+pub fn get_entries_description() -> String {
+    let mut description = String::new();
+    for (i, entry) in get_entries().iter().enumerate() {
+        if i > 0 {
+            description.push(',');
+        }
+        if !entry.enabled {
+            description.push('!');
+        }
+        description.push_str(&entry.name);
+        if let Some(duration) = entry.duration {
+            write!(description, ":{}", duration).ok();
+        }
+    }
+    description
+}
+
+/// Name of the page currently shown, updated by the matrix task.
+pub fn get_current_page() -> String {
+    CURRENT_PAGE.lock(|current| current.borrow().clone())
+}
+
+pub fn set_current_page(name: &str) {
+    let changed = CURRENT_PAGE.lock(|current| {
+        let mut current = current.borrow_mut();
+        if current.as_str() == name {
+            return false;
+        }
+        current.clear();
+        current.push_str(name);
+        true
+    });
+    if changed {
+        CURRENT_PAGE_CHANGED.signal(());
+    }
+}
+
+/// Resolves with the new page name once the matrix task switched pages.
+pub async fn wait_for_current_page_change() -> String {
+    CURRENT_PAGE_CHANGED.wait().await;
+    get_current_page()
+}
+
+/// Asks the matrix task to show `name` right away, unknown or disabled pages are ignored there.
+pub fn request_page(name: &str) {
+    REQUESTED_PAGE.lock(|requested| *requested.borrow_mut() = Some(name.into()));
+}
+
+pub fn take_requested_page() -> Option<String> {
+    REQUESTED_PAGE.lock(|requested| requested.borrow_mut().take())
+}
+
+/// Changes every time the registry is modified, used by the matrix task to rebuild its pages.
+pub fn get_page_registry_version() -> u32 {
+    PAGE_REGISTRY_VERSION.load(Ordering::Relaxed)
+}
+
+/// Resolves after the registry was modified, used to keep Home Assistant in sync with edits made on the device.
+pub async fn wait_for_change() {
+    PAGE_REGISTRY_EXTERNAL_CHANGED.wait().await
+}
+
+#[embassy_executor::task]
+pub async fn page_registry_task(storage: crate::storage::Storage) {
+    let entries = storage.read::<Vec<PageEntry>>(&crate::storage::Key::PageRegistry).await.unwrap_or_default();
+    info!("Loaded {} page registry entries", entries.len());
+    PAGE_REGISTRY.lock(|registry| *registry.borrow_mut() = entries);
+    PAGE_REGISTRY_VERSION.fetch_add(1, Ordering::Relaxed);
+
+    loop {
+        PAGE_REGISTRY_CHANGED.wait().await;
+        let entries = PAGE_REGISTRY.lock(|registry| registry.borrow().clone());
+        storage.save(&crate::storage::Key::PageRegistry, &entries).await.expect("failed saving page registry");
+        info!("Page registry saved: {}", entries.len());
+    }
+}
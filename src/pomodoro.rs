@@ -0,0 +1,123 @@
+use core::cell::RefCell;
+
+use embassy_futures::select::{select, Either};
+use embassy_sync::{
+    blocking_mutex::{raw::CriticalSectionRawMutex, Mutex},
+    signal::Signal,
+};
+use embassy_time::{Duration, Instant, Timer};
+use rwtrix_core::pomodoro::Pomodoro;
+pub use rwtrix_core::pomodoro::{Phase, PomodoroSettings};
+
+use crate::buzzer;
+
+pub const MIN_PHASE_MINUTES: u32 = 1;
+pub const MAX_PHASE_MINUTES: u32 = 180;
+const TICK_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Shared between the timer page, Home Assistant and the task ringing the alarms, created on first use.
+static POMODORO: Mutex<CriticalSectionRawMutex, RefCell<Option<Pomodoro>>> = Mutex::new(RefCell::new(None));
+static SETTINGS_CHANGED: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+
+/// Snapshot of the timer for display purposes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PomodoroStatus {
+    pub phase: Phase,
+    pub running: bool,
+    pub stopped: bool,
+    pub remaining_secs: u32,
+    pub completed_sessions: u32,
+    pub sessions_in_cycle: u8,
+    pub sessions_before_long_break: u8,
+}
+
+fn now_ms() -> u64 {
+    Instant::now().as_millis()
+}
+
+fn with_pomodoro<R>(f: impl FnOnce(&mut Pomodoro) -> R) -> R {
+    POMODORO.lock(|pomodoro| {
+        let mut pomodoro = pomodoro.borrow_mut();
+        f(pomodoro.get_or_insert_with(|| Pomodoro::new(PomodoroSettings::default())))
+    })
+}
+
+pub fn get_status() -> PomodoroStatus {
+    with_pomodoro(|pomodoro| {
+        let now = now_ms();
+        PomodoroStatus {
+            phase: pomodoro.phase(),
+            running: pomodoro.is_running(),
+            stopped: pomodoro.is_stopped(),
+            // rounded up so the display shows 00:00 only once the phase is over
+            remaining_secs: pomodoro.remaining_ms(now).div_ceil(1000) as u32,
+            completed_sessions: pomodoro.completed_sessions(),
+            sessions_in_cycle: pomodoro.sessions_in_cycle(),
+            sessions_before_long_break: pomodoro.settings().sessions_before_long_break,
+        }
+    })
+}
+
+/// Starts, pauses or resumes the current phase.
+pub fn toggle() {
+    with_pomodoro(|pomodoro| pomodoro.toggle(now_ms()));
+}
+
+pub fn stop() {
+    with_pomodoro(|pomodoro| pomodoro.stop());
+}
+
+pub fn skip() {
+    with_pomodoro(|pomodoro| pomodoro.skip(now_ms()));
+}
+
+pub fn start_phase(phase: Phase) {
+    with_pomodoro(|pomodoro| pomodoro.start_phase(phase, now_ms()));
+}
+
+pub fn get_settings() -> PomodoroSettings {
+    with_pomodoro(|pomodoro| *pomodoro.settings())
+}
+
+pub fn set_phase_minutes(phase: Phase, minutes: u32) {
+    let secs = minutes.clamp(MIN_PHASE_MINUTES, MAX_PHASE_MINUTES) * 60;
+    with_pomodoro(|pomodoro| {
+        let mut settings = *pomodoro.settings();
+        match phase {
+            Phase::Work => settings.work_secs = secs,
+            Phase::ShortBreak => settings.short_break_secs = secs,
+            Phase::LongBreak => settings.long_break_secs = secs,
+        }
+        pomodoro.set_settings(settings);
+    });
+    SETTINGS_CHANGED.signal(());
+}
+
+/// Advances the phases and rings the buzzer at the end of each one, whether the timer page is visible or not.
+#[embassy_executor::task]
+pub async fn pomodoro_task(storage: crate::storage::Storage) {
+    if let Ok(settings) = storage.read::<PomodoroSettings>(&crate::storage::Key::PomodoroSettings).await {
+        with_pomodoro(|pomodoro| pomodoro.set_settings(settings));
+    }
+
+    loop {
+        match select(SETTINGS_CHANGED.wait(), Timer::after(TICK_INTERVAL)).await {
+            Either::First(_) => {
+                let settings = get_settings();
+                storage
+                    .save(&crate::storage::Key::PomodoroSettings, &settings)
+                    .await
+                    .expect("failed saving pomodoro settings");
+                info!("Pomodoro settings saved: {:?}", settings);
+            }
+            Either::Second(_) => {
+                let Some(ended) = with_pomodoro(|pomodoro| pomodoro.tick(now_ms())) else {
+                    continue;
+                };
+                let next = get_status().phase;
+                info!("Pomodoro {} finished, starting {}", ended.name(), next.name());
+                buzzer::play(if next == Phase::LongBreak { buzzer::LONG_ALARM } else { buzzer::PHASE_END });
+            }
+        }
+    }
+}
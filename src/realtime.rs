@@ -0,0 +1,108 @@
+use alloc::vec;
+use core::{
+    cell::{Cell, RefCell},
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use embassy_futures::select::{select, Either};
+use embassy_net::udp::UdpSocket;
+use embassy_sync::blocking_mutex::{raw::CriticalSectionRawMutex, Mutex};
+use embassy_time::{Duration, Instant};
+use rwtrix_core::realtime::{self, Packet, PacketError};
+
+use crate::{
+    matrix::{HEIGHT, WIDTH},
+    udp::UdpBuffers,
+};
+
+pub const FRAME_LEN: usize = (WIDTH * HEIGHT * 3) as usize;
+/// Pages come back when no frame arrived for this long, same default as WLED.
+const REALTIME_TIMEOUT: Duration = Duration::from_millis(2500);
+const E131_START_UNIVERSE: u16 = 1;
+const MAX_PACKET_SIZE: usize = 1500;
+
+/// Last complete frame, RGB row by row from the top left corner.
+static FRAME: Mutex<CriticalSectionRawMutex, RefCell<[u8; FRAME_LEN]>> = Mutex::new(RefCell::new([0; FRAME_LEN]));
+static FRAME_VERSION: AtomicU32 = AtomicU32::new(0);
+static LAST_FRAME: Mutex<CriticalSectionRawMutex, Cell<Option<Instant>>> = Mutex::new(Cell::new(None));
+
+/// Whether a sender is streaming, pages are suspended while it does.
+pub fn is_active() -> bool {
+    LAST_FRAME.lock(|last| last.get()).is_some_and(|last| last.elapsed() < REALTIME_TIMEOUT)
+}
+
+pub fn get_frame_version() -> u32 {
+    FRAME_VERSION.load(Ordering::Relaxed)
+}
+
+pub fn with_frame<R>(f: impl FnOnce(&[u8; FRAME_LEN]) -> R) -> R {
+    FRAME.lock(|frame| f(&frame.borrow()))
+}
+
+fn publish(staging: &[u8]) {
+    FRAME.lock(|frame| frame.borrow_mut().copy_from_slice(staging));
+    LAST_FRAME.lock(|last| {
+        if last.get().is_none() {
+            info!("Realtime streaming started");
+        }
+        last.set(Some(Instant::now()));
+    });
+    FRAME_VERSION.fetch_add(1, Ordering::Relaxed);
+}
+
+fn stop() {
+    if LAST_FRAME.lock(|last| last.take()).is_some() {
+        info!("Realtime streaming stopped");
+    }
+}
+
+/// Listens for DDP and E1.31 frames, the matrix task shows them instead of the pages while they keep coming.
+#[embassy_executor::task]
+pub async fn realtime_task(stack: embassy_net::Stack<'static>) {
+    crate::wifi::wait_for_connection(&stack).await;
+
+    let mut ddp_buffers = UdpBuffers::with_rx_size(2 * MAX_PACKET_SIZE);
+    let (rx_meta, rx_buffer, tx_meta, tx_buffer) = ddp_buffers.as_mut();
+    let mut ddp_socket = UdpSocket::new(stack, rx_meta, rx_buffer, tx_meta, tx_buffer);
+    let mut e131_buffers = UdpBuffers::with_rx_size(2 * MAX_PACKET_SIZE);
+    let (rx_meta, rx_buffer, tx_meta, tx_buffer) = e131_buffers.as_mut();
+    let mut e131_socket = UdpSocket::new(stack, rx_meta, rx_buffer, tx_meta, tx_buffer);
+
+    if let Err(e) = ddp_socket.bind(realtime::DDP_PORT) {
+        error!("Failed to bind DDP socket: {:?}", e);
+        return;
+    }
+    if let Err(e) = e131_socket.bind(realtime::E131_PORT) {
+        error!("Failed to bind E1.31 socket: {:?}", e);
+        return;
+    }
+    info!("Realtime listening on UDP {} (DDP) and {} (E1.31)", realtime::DDP_PORT, realtime::E131_PORT);
+
+    let mut ddp_packet = vec![0u8; MAX_PACKET_SIZE];
+    let mut e131_packet = vec![0u8; MAX_PACKET_SIZE];
+    let mut staging = vec![0u8; FRAME_LEN];
+
+    loop {
+        let packet = match select(ddp_socket.recv_from(&mut ddp_packet), e131_socket.recv_from(&mut e131_packet)).await
+        {
+            Either::First(Ok((len, _))) => realtime::parse_ddp(&ddp_packet[..len]),
+            Either::Second(Ok((len, _))) => realtime::parse_e131(&e131_packet[..len], E131_START_UNIVERSE, FRAME_LEN),
+            Either::First(Err(e)) | Either::Second(Err(e)) => {
+                warn!("Realtime receive failed: {:?}", e);
+                continue;
+            }
+        };
+
+        match packet {
+            Ok(Packet::Data { offset, data, push }) => {
+                realtime::apply(&mut staging, offset, data);
+                if push {
+                    publish(&staging);
+                }
+            }
+            Ok(Packet::Terminate) => stop(),
+            Err(PacketError::Ignored) => {}
+            Err(e) => debug!("Dropping realtime packet: {:?}", e),
+        }
+    }
+}
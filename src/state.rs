@@ -1,11 +1,94 @@
-use core::sync::atomic::{AtomicBool, Ordering};
+use alloc::{string::String, vec::Vec};
+use core::{
+    cell::{Cell, RefCell},
+    sync::atomic::{AtomicBool, AtomicU32, AtomicU8, Ordering},
+};
 
-use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, signal::Signal};
+use embassy_sync::{
+    blocking_mutex::{raw::CriticalSectionRawMutex, Mutex},
+    signal::Signal,
+};
+use embassy_time::Duration;
+use serde::{Deserialize, Serialize};
+
+use crate::matrix::transition::{self, TransitionEffect};
 
 static TRANSITION_STATE: AtomicBool = AtomicBool::new(true);
 static TRANSITION_INTERNAL_CHANGED: Signal<CriticalSectionRawMutex, bool> = Signal::new();
 
-static INDICATORS_STATE: [AtomicBool; 3] = [AtomicBool::new(false), AtomicBool::new(false), AtomicBool::new(false)];
+static TRANSITION_EFFECT: AtomicU8 = AtomicU8::new(TransitionEffect::SlideLeft as u8);
+static TRANSITION_DURATION_MS: AtomicU32 = AtomicU32::new(transition::DEFAULT_TRANSITION_DURATION_MS);
+/// Per page transition effects overriding the global one, keyed by the page name.
+static PAGE_TRANSITIONS: Mutex<CriticalSectionRawMutex, RefCell<Vec<(String, TransitionEffect)>>> =
+    Mutex::new(RefCell::new(Vec::new()));
+
+pub const INDICATOR_COUNT: usize = 3;
+pub const DEFAULT_INDICATOR_PERIOD_MS: u16 = 1000;
+
+static INDICATORS_STATE: Mutex<CriticalSectionRawMutex, Cell<[Indicator; INDICATOR_COUNT]>> =
+    Mutex::new(Cell::new([Indicator::OFF; INDICATOR_COUNT]));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IndicatorMode {
+    Solid,
+    Blink,
+    /// Smoothly pulses between off and the full color.
+    Fade,
+}
+
+impl IndicatorMode {
+    pub const ALL: [IndicatorMode; 3] = [IndicatorMode::Solid, IndicatorMode::Blink, IndicatorMode::Fade];
+    pub const NAMES: [&'static str; 3] = ["solid", "blink", "fade"];
+
+    pub fn from_index(index: usize) -> Option<Self> {
+        Self::ALL.get(index).copied()
+    }
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Indicator {
+    pub on: bool,
+    pub color: [u8; 3],
+    pub brightness: u8,
+    pub mode: IndicatorMode,
+    /// Length of one blink or fade cycle.
+    pub period_ms: u16,
+}
+
+impl Indicator {
+    pub const OFF: Indicator = Indicator {
+        on: false,
+        color: [255, 255, 255],
+        brightness: 255,
+        mode: IndicatorMode::Solid,
+        period_ms: DEFAULT_INDICATOR_PERIOD_MS,
+    };
+
+    /// Color to show `elapsed_ms` into the current cycle, black while the indicator is off or blinked out.
+    pub fn color_at(&self, elapsed_ms: u64) -> [u8; 3] {
+        if !self.on {
+            return [0; 3];
+        }
+        let period = self.period_ms.max(1) as u64;
+        let phase = elapsed_ms % period;
+        let scale = match self.mode {
+            IndicatorMode::Solid => 255,
+            IndicatorMode::Blink if phase < period / 2 => 255,
+            IndicatorMode::Blink => 0,
+            // triangle wave, up during the first half of the period and down during the second
+            IndicatorMode::Fade => (255 - (phase * 510 / period).abs_diff(255)) as u32,
+        };
+        let scale = scale * self.brightness as u32 / 255;
+        self.color.map(|c| (c as u32 * scale / 255) as u8)
+    }
+}
+
+static DISPLAY_STATE: Mutex<CriticalSectionRawMutex, Cell<DisplayState>> =
+    Mutex::new(Cell::new(DisplayState { on: true, brightness: None }));
 
 static STATE_CHANGED: Signal<CriticalSectionRawMutex, ()> = Signal::new();
 
@@ -28,28 +111,130 @@ pub async fn wait_for_internal_transition_state_change() -> bool {
     TRANSITION_INTERNAL_CHANGED.wait().await
 }
 
-pub fn set_indicator_state(index: usize, state: bool) {
-    if index < INDICATORS_STATE.len() {
-        INDICATORS_STATE[index].store(state, Ordering::Relaxed);
+pub fn get_transition_effect() -> TransitionEffect {
+    TransitionEffect::from_index(TRANSITION_EFFECT.load(Ordering::Relaxed) as usize).unwrap_or_default()
+}
+
+pub fn set_transition_effect(effect: TransitionEffect) {
+    TRANSITION_EFFECT.store(effect.index() as u8, Ordering::Relaxed);
+    STATE_CHANGED.signal(());
+}
+
+pub fn get_transition_duration() -> Duration {
+    Duration::from_millis(TRANSITION_DURATION_MS.load(Ordering::Relaxed) as u64)
+}
+
+pub fn set_transition_duration(duration_ms: u32) {
+    let duration_ms = duration_ms.clamp(transition::MIN_TRANSITION_DURATION_MS, transition::MAX_TRANSITION_DURATION_MS);
+    TRANSITION_DURATION_MS.store(duration_ms, Ordering::Relaxed);
+    STATE_CHANGED.signal(());
+}
+
+/// Effect used when changing to the page named `page`, falls back to the global effect.
+pub fn get_page_transition_effect(page: &str) -> TransitionEffect {
+    PAGE_TRANSITIONS
+        .lock(|transitions| transitions.borrow().iter().find(|(name, _)| name == page).map(|(_, effect)| *effect))
+        .unwrap_or_else(get_transition_effect)
+}
+
+/// Overrides the effect for a single page, `None` goes back to the global effect.
+pub fn set_page_transition_effect(page: &str, effect: Option<TransitionEffect>) {
+    PAGE_TRANSITIONS.lock(|transitions| {
+        let mut transitions = transitions.borrow_mut();
+        transitions.retain(|(name, _)| name != page);
+        if let Some(effect) = effect {
+            transitions.push((page.into(), effect));
+        }
+    });
+    STATE_CHANGED.signal(());
+}
+
+/// Page overrides formatted as `page=effect` pairs separated by commas.
+pub fn get_page_transitions_description() -> String {
+    PAGE_TRANSITIONS.lock(|transitions| {
+        let mut description = String::new();
+        for (i, (page, effect)) in transitions.borrow().iter().enumerate() {
+            if i > 0 {
+                description.push(',');
+            }
+            description.push_str(page);
+            description.push('=');
+            description.push_str(effect.name());
+        }
+        description
+    })
+}
+
+pub fn set_indicator(index: usize, indicator: Indicator) {
+    if index < INDICATOR_COUNT {
+        INDICATORS_STATE.lock(|indicators| {
+            let mut state = indicators.get();
+            state[index] = indicator;
+            indicators.set(state);
+        });
         STATE_CHANGED.signal(());
     }
 }
 
-pub fn get_indicators_state() -> [bool; 3] {
-    [
-        INDICATORS_STATE[0].load(Ordering::Relaxed),
-        INDICATORS_STATE[1].load(Ordering::Relaxed),
-        INDICATORS_STATE[2].load(Ordering::Relaxed),
-    ]
+pub fn get_indicator(index: usize) -> Indicator {
+    get_indicators_state()[index]
+}
+
+pub fn get_indicators_state() -> [Indicator; INDICATOR_COUNT] {
+    INDICATORS_STATE.lock(|indicators| indicators.get())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DisplayState {
+    pub on: bool,
+    /// Fixed panel brightness, `None` follows the light sensor.
+    pub brightness: Option<u8>,
+}
+
+pub fn get_display_state() -> DisplayState {
+    DISPLAY_STATE.lock(|state| state.get())
+}
+
+pub fn set_display_state(display: DisplayState) {
+    DISPLAY_STATE.lock(|state| state.set(display));
+    STATE_CHANGED.signal(());
+}
+
+/// Indicators used to be stored as plain on/off flags, those turn into solid white indicators.
+fn decode_indicators(raw: &[u8]) -> Option<[Indicator; INDICATOR_COUNT]> {
+    if let Ok(indicators) = postcard::from_bytes::<[Indicator; INDICATOR_COUNT]>(raw) {
+        return Some(indicators);
+    }
+    let legacy = postcard::from_bytes::<[bool; INDICATOR_COUNT]>(raw).ok()?;
+    info!("Migrating indicators from the on/off format: {:?}", legacy);
+    Some(legacy.map(|on| Indicator { on, ..Indicator::OFF }))
 }
 
 #[embassy_executor::task]
 pub async fn state_task(storage: crate::storage::Storage) {
     let transition = storage.read::<bool>(&crate::storage::Key::TransitionState).await.unwrap_or(true);
     TRANSITION_STATE.store(transition, Ordering::Relaxed);
-    let indicators = storage.read::<[bool; 3]>(&crate::storage::Key::IndicatorsState).await.unwrap_or([false; 3]);
-    for (i, state) in indicators.iter().enumerate() {
-        INDICATORS_STATE[i].store(*state, Ordering::Relaxed);
+    let indicators = storage
+        .read_raw(&crate::storage::Key::IndicatorsState)
+        .await
+        .ok()
+        .and_then(|raw| decode_indicators(&raw))
+        .unwrap_or([Indicator::OFF; INDICATOR_COUNT]);
+    INDICATORS_STATE.lock(|state| state.set(indicators));
+    let effect = storage.read::<TransitionEffect>(&crate::storage::Key::TransitionEffect).await.unwrap_or_default();
+    TRANSITION_EFFECT.store(effect.index() as u8, Ordering::Relaxed);
+    let duration = storage
+        .read::<u32>(&crate::storage::Key::TransitionDuration)
+        .await
+        .unwrap_or(transition::DEFAULT_TRANSITION_DURATION_MS);
+    TRANSITION_DURATION_MS.store(duration, Ordering::Relaxed);
+    let page_transitions = storage
+        .read::<Vec<(String, TransitionEffect)>>(&crate::storage::Key::PageTransitions)
+        .await
+        .unwrap_or_default();
+    PAGE_TRANSITIONS.lock(|transitions| *transitions.borrow_mut() = page_transitions);
+    if let Ok(display) = storage.read::<DisplayState>(&crate::storage::Key::DisplayState).await {
+        DISPLAY_STATE.lock(|state| state.set(display));
     }
 
     loop {
@@ -58,6 +243,27 @@ pub async fn state_task(storage: crate::storage::Storage) {
         let indicators = get_indicators_state();
         storage.save(&crate::storage::Key::TransitionState, &transition).await.expect("failed saving transition state");
         storage.save(&crate::storage::Key::IndicatorsState, &indicators).await.expect("failed saving indicators state");
-        info!("State saved: transition={}, indicators={:?}", transition, indicators);
+        let effect = get_transition_effect();
+        let duration = TRANSITION_DURATION_MS.load(Ordering::Relaxed);
+        let page_transitions = PAGE_TRANSITIONS.lock(|transitions| transitions.borrow().clone());
+        storage.save(&crate::storage::Key::TransitionEffect, &effect).await.expect("failed saving transition effect");
+        storage
+            .save(&crate::storage::Key::TransitionDuration, &duration)
+            .await
+            .expect("failed saving transition duration");
+        storage
+            .save(&crate::storage::Key::PageTransitions, &page_transitions)
+            .await
+            .expect("failed saving page transitions");
+        let display = get_display_state();
+        storage.save(&crate::storage::Key::DisplayState, &display).await.expect("failed saving display state");
+        info!(
+            "State saved: transition={}, indicators={:?}, effect={}, duration={}ms, display={:?}",
+            transition,
+            indicators,
+            effect.name(),
+            duration,
+            display
+        );
     }
 }
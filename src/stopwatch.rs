@@ -0,0 +1,30 @@
+use alloc::string::String;
+
+use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, signal::Signal};
+use rwtrix_core::stopwatch::Stopwatch;
+
+/// Latest laps of the stopwatch page, waited on by Home Assistant.
+static LAPS: Signal<CriticalSectionRawMutex, Laps> = Signal::new();
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Laps {
+    /// Duration of the most recent lap, `None` when no lap was recorded.
+    pub last_lap_ms: Option<u64>,
+    /// Every recorded lap as a JSON object, see [`Stopwatch::laps_json`].
+    pub json: String,
+}
+
+impl Laps {
+    pub fn of(stopwatch: &Stopwatch) -> Self {
+        Laps { last_lap_ms: stopwatch.laps().last().copied(), json: stopwatch.laps_json() }
+    }
+}
+
+/// Called by the stopwatch page whenever a lap is recorded or the laps are reset.
+pub fn report_laps(stopwatch: &Stopwatch) {
+    LAPS.signal(Laps::of(stopwatch));
+}
+
+pub async fn wait_for_laps() -> Laps {
+    LAPS.wait().await
+}
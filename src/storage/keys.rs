@@ -7,4 +7,18 @@ pub enum Key<'a> {
     Wifi(&'a str),
     TransitionState,
     IndicatorsState,
+    CustomApps,
+    TransitionEffect,
+    TransitionDuration,
+    PageTransitions,
+    PageRegistry,
+    DisplayState,
+    PomodoroSettings,
+    Alarms,
+    Countdowns,
+    ClockSettings,
+    DateSettings,
+    NightSettings,
+    EntityWidgets,
+    GameHighScores,
 }
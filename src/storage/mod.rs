@@ -1,4 +1,4 @@
-use alloc::vec;
+use alloc::{vec, vec::Vec};
 use core::cell::RefCell;
 
 use ekv::Database;
@@ -151,6 +151,14 @@ impl Storage {
     }
 
     pub async fn read<'a, T: serde::de::DeserializeOwned>(&self, key: &'a Key<'a>) -> Result<T, StorageError> {
+        let value_buf = self.read_raw(key).await?;
+        let value: T = postcard::from_bytes(&value_buf).expect("failed deserializing from postcard");
+
+        Ok(value)
+    }
+
+    /// Reads the postcard encoded value without decoding it, for values whose layout changed between versions.
+    pub async fn read_raw<'a>(&self, key: &'a Key<'a>) -> Result<Vec<u8>, StorageError> {
         let mut key = postcard::to_allocvec(key).expect("failed serializing key to postcard");
 
         let read = self.db.read_transaction().await;
@@ -162,8 +170,6 @@ impl Storage {
         key.push(0xFF); // separator
         read.read(&key, &mut value_buf).await?;
 
-        let value: T = postcard::from_bytes(&value_buf).expect("failed deserializing from postcard");
-
-        Ok(value)
+        Ok(value_buf)
     }
 }
@@ -1,4 +1,4 @@
-use alloc::boxed::Box;
+use alloc::{boxed::Box, vec};
 
 use embassy_net::{
     dns::DnsQueryType,
@@ -23,6 +23,11 @@ impl UdpBuffers {
         }
     }
 
+    /// Buffers with a larger receive buffer, for sockets getting datagrams bigger than the 128 bytes of [`Self::new`].
+    pub fn with_rx_size(rx_size: usize) -> Self {
+        Self { rx_buffer: vec![0; rx_size].into_boxed_slice(), ..Self::new() }
+    }
+
     pub fn as_mut<'a>(
         &'a mut self,
     ) -> (&'a mut [PacketMetadata], &'a mut [u8], &'a mut [PacketMetadata], &'a mut [u8]) {